@@ -0,0 +1,117 @@
+use serde::{Deserialize, Serialize};
+
+/// Field bucketed in the fleet summary unless told otherwise.
+pub const DEFAULT_SUMMARY_BUCKET_FIELD: &str = "attributes.firmware_version";
+
+/// Statistics of a numeric field over the things matching a fleet-indexing query.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct Statistics {
+    /// The number of things, or of values of the field if one is aggregated.
+    pub count: u64,
+    /// The average of the values.
+    pub average: Option<f64>,
+    /// The sum of the values.
+    pub sum: Option<f64>,
+    /// The smallest value.
+    pub minimum: Option<f64>,
+    /// The largest value.
+    pub maximum: Option<f64>,
+    /// The sum of the squared values.
+    pub sum_of_squares: Option<f64>,
+    /// The variance of the values.
+    pub variance: Option<f64>,
+    /// The standard deviation of the values.
+    pub std_deviation: Option<f64>,
+}
+
+impl Statistics {
+    /// Computes the statistics of the given values, as `GetStatistics` does.
+    pub(crate) fn of(values: &[f64]) -> Self {
+        if values.is_empty() {
+            return Self::default();
+        }
+        let count = values.len() as f64;
+        let sum: f64 = values.iter().sum();
+        let sum_of_squares: f64 = values.iter().map(|value| value * value).sum();
+        let average = sum / count;
+        let variance = sum_of_squares / count - average * average;
+        Self {
+            count: values.len() as u64,
+            average: Some(average),
+            sum: Some(sum),
+            minimum: values.iter().copied().reduce(f64::min),
+            maximum: values.iter().copied().reduce(f64::max),
+            sum_of_squares: Some(sum_of_squares),
+            variance: Some(variance),
+            std_deviation: Some(variance.sqrt()),
+        }
+    }
+}
+
+/// Value below which a percentage of the values of a field fall.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct Percentile {
+    /// The percentage, e.g. 90.0.
+    pub percent: f64,
+    /// The value at that percentage.
+    pub value: f64,
+}
+
+/// Computes percentiles of the given values by linear interpolation, empty without values.
+pub(crate) fn percentiles(values: &[f64], percents: &[f64]) -> Vec<Percentile> {
+    let mut sorted = values.to_vec();
+    sorted.sort_by(f64::total_cmp);
+    let Some(last) = sorted.len().checked_sub(1) else {
+        return Vec::new();
+    };
+    percents
+        .iter()
+        .map(|&percent| {
+            let rank = percent.clamp(0.0, 100.0) / 100.0 * last as f64;
+            let (lower, upper) = (sorted[rank.floor() as usize], sorted[rank.ceil() as usize]);
+            Percentile {
+                percent,
+                value: lower + (upper - lower) * rank.fract(),
+            }
+        })
+        .collect()
+}
+
+/// Number of things sharing a value of a field.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Bucket {
+    /// The value of the field.
+    pub key: String,
+    /// How many things have it.
+    pub count: u64,
+}
+
+/// Statistics and percentiles of a numeric field.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct FieldStatistics {
+    /// The aggregated field, e.g. `attributes.uptime_hours`.
+    pub field: String,
+    /// The statistics of the field.
+    pub statistics: Statistics,
+    /// The percentiles of the field, in the order they were asked for.
+    pub percentiles: Vec<Percentile>,
+}
+
+/// Counts over the whole fleet, aggregated by fleet indexing instead of listing every thing.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct FleetSummary {
+    /// The number of things.
+    pub things: u64,
+    /// The number of connected things.
+    pub connected: u64,
+    /// The number of disconnected things. Things whose connectivity is not indexed are neither.
+    pub disconnected: u64,
+    /// The number of distinct thing types.
+    pub thing_types: u64,
+    /// The field the things are bucketed by, e.g. `attributes.firmware_version`.
+    pub bucket_field: String,
+    /// The most common values of the bucketed field, most common first.
+    pub buckets: Vec<Bucket>,
+    /// Statistics of a numeric field, if one is summarized.
+    pub metric: Option<FieldStatistics>,
+}
@@ -0,0 +1,169 @@
+use std::collections::BTreeMap;
+
+use async_trait::async_trait;
+use aws_sdk_iot::primitives::DateTime;
+use serde_json::Value;
+
+use crate::{
+    ActiveViolation, AuditFinding, Bucket, Certificate, Component, ComponentVersion, CoreDevice,
+    Deployment, DeploymentDetail, DeploymentQuery, DeploymentSpec, EffectiveDeployment,
+    InstalledComponent, Job, JobExecution, Percentile, Policy, Result, SearchPage, Shadow,
+    Statistics, Thing, ThingGroup,
+};
+
+pub use {aws::AwsBackend, fixture::FixtureBackend};
+
+pub mod aws;
+pub mod fixture;
+
+/// Source of the IoT Core and Greengrass data used by [`AwsCloud`](crate::AwsCloud).
+///
+/// [`AwsBackend`] talks to the AWS APIs while [`FixtureBackend`] serves an in-memory
+/// fleet so the library and the TUI can run offline.
+#[async_trait]
+pub trait CloudBackend: Send + Sync + std::fmt::Debug {
+    /// Lists all Greengrass core devices.
+    async fn list_core_devices(&self) -> Result<Vec<CoreDevice>>;
+
+    /// Returns whether the given thing is currently connected to IoT Core.
+    async fn get_thing_connectivity(&self, thing_name: &str) -> Result<bool>;
+
+    /// Returns the tags of a core device.
+    async fn get_core_device_tags(&self, thing_name: &str) -> Result<BTreeMap<String, String>>;
+
+    /// Returns the IoT registry entry of a thing.
+    async fn describe_thing(&self, thing_name: &str) -> Result<Thing>;
+
+    /// Lists the ARNs of the principals, such as certificates, attached to a thing.
+    async fn list_thing_principals(&self, thing_name: &str) -> Result<Vec<String>>;
+
+    /// Describes a certificate. The returned certificate has no thing name.
+    async fn describe_certificate(&self, certificate_id: &str) -> Result<Certificate>;
+
+    /// Lists the names of the policies attached to a target such as a certificate ARN.
+    async fn list_attached_policies(&self, target: &str) -> Result<Vec<String>>;
+
+    /// Returns a policy with the document of its default version.
+    async fn get_policy(&self, policy_name: &str) -> Result<Policy>;
+
+    /// Lists all thing groups. The returned groups have no tags and no place in the hierarchy.
+    async fn list_thing_groups(&self) -> Result<Vec<ThingGroup>>;
+
+    /// Returns a thing group with its place in the group hierarchy. The returned group has no
+    /// tags.
+    async fn describe_thing_group(&self, group_name: &str) -> Result<ThingGroup>;
+
+    /// Lists the names of the things in a thing group, including its child groups' if
+    /// `recursive` is set.
+    async fn list_things_in_thing_group(
+        &self,
+        group_name: &str,
+        recursive: bool,
+    ) -> Result<Vec<String>>;
+
+    /// Adds a thing to a static thing group.
+    async fn add_thing_to_thing_group(&self, group_name: &str, thing_name: &str) -> Result<()>;
+
+    /// Removes a thing from a static thing group.
+    async fn remove_thing_from_thing_group(&self, group_name: &str, thing_name: &str)
+    -> Result<()>;
+
+    /// Creates a thing group whose members are the things matching a fleet-indexing query.
+    async fn create_dynamic_thing_group(&self, group_name: &str, query: &str)
+    -> Result<ThingGroup>;
+
+    /// Returns a page of the things matching a fleet-indexing query.
+    async fn search_index(&self, query: &str, next_token: Option<&str>) -> Result<SearchPage>;
+
+    /// Aggregates the things matching a fleet-indexing query. Without a field only the
+    /// count is set.
+    async fn get_statistics(&self, query: &str, field: Option<&str>) -> Result<Statistics>;
+
+    /// Counts the distinct values of a field over the things matching a fleet-indexing query.
+    async fn get_cardinality(&self, query: &str, field: &str) -> Result<u64>;
+
+    /// Returns percentiles of a numeric field over the things matching a fleet-indexing query.
+    async fn get_percentiles(
+        &self,
+        query: &str,
+        field: &str,
+        percents: &[f64],
+    ) -> Result<Vec<Percentile>>;
+
+    /// Counts the things matching a fleet-indexing query by value of a field, keeping the
+    /// `max_buckets` most common values.
+    async fn get_buckets(
+        &self,
+        query: &str,
+        field: &str,
+        max_buckets: usize,
+    ) -> Result<Vec<Bucket>>;
+
+    /// Returns the tags of an IoT resource such as a thing group.
+    async fn list_tags_for_resource(&self, resource_arn: &str) -> Result<BTreeMap<String, String>>;
+
+    /// Lists the components in the account's private catalog.
+    async fn list_components(&self) -> Result<Vec<Component>>;
+
+    /// Lists every version of the component with the given ARN.
+    async fn list_component_versions(&self, arn: &str) -> Result<Vec<ComponentVersion>>;
+
+    /// Returns the recipe of the component version with the given ARN as YAML.
+    async fn get_component_recipe(&self, arn: &str) -> Result<String>;
+
+    /// Lists every deployment matching the query. Tags are only returned by [`get_deployment`].
+    ///
+    /// [`get_deployment`]: CloudBackend::get_deployment
+    async fn list_deployments(&self, query: &DeploymentQuery) -> Result<Vec<Deployment>>;
+
+    /// Returns the full description of a deployment revision.
+    async fn get_deployment(&self, deployment_id: &str) -> Result<DeploymentDetail>;
+
+    /// Lists the components installed on a core device, including dependencies.
+    async fn list_installed_components(&self, thing_name: &str) -> Result<Vec<InstalledComponent>>;
+
+    /// Lists the deployments applied to a core device.
+    async fn list_effective_deployments(
+        &self,
+        thing_name: &str,
+    ) -> Result<Vec<EffectiveDeployment>>;
+
+    /// Creates a deployment, or a new revision of the existing one for the target.
+    ///
+    /// Returns the ID of the new deployment.
+    async fn create_deployment(&self, spec: &DeploymentSpec) -> Result<String>;
+
+    /// Cancels a deployment, leaving it CANCELED.
+    async fn cancel_deployment(&self, deployment_id: &str) -> Result<()>;
+
+    /// Lists all IoT jobs. The execution counts are only filled in by [`describe_job`].
+    ///
+    /// [`describe_job`]: CloudBackend::describe_job
+    async fn list_jobs(&self) -> Result<Vec<Job>>;
+
+    /// Returns the full description of an IoT job, including its execution counts.
+    async fn describe_job(&self, job_id: &str) -> Result<Job>;
+
+    /// Lists the executions of an IoT job, one per thing.
+    async fn list_job_executions_for_job(&self, job_id: &str) -> Result<Vec<JobExecution>>;
+
+    /// Lists the Device Defender audit findings made since the given time.
+    async fn list_audit_findings(&self, since: DateTime) -> Result<Vec<AuditFinding>>;
+
+    /// Lists the Device Defender behaviors that things currently violate.
+    async fn list_active_violations(&self) -> Result<Vec<ActiveViolation>>;
+
+    /// Lists the names of a thing's named shadows.
+    async fn list_named_shadows(&self, thing_name: &str) -> Result<Vec<String>>;
+
+    /// Returns a thing's classic shadow, or the named one if `shadow_name` is given.
+    async fn get_shadow(&self, thing_name: &str, shadow_name: Option<&str>) -> Result<Shadow>;
+
+    /// Publishes an update to a shadow, creating the shadow if it does not exist.
+    async fn update_shadow(
+        &self,
+        thing_name: &str,
+        shadow_name: Option<&str>,
+        payload: &Value,
+    ) -> Result<()>;
+}
@@ -0,0 +1,1113 @@
+use std::{collections::BTreeMap, fmt, time::Duration};
+
+use async_trait::async_trait;
+use aws_config::{
+    BehaviorVersion, meta::region::RegionProviderChain,
+    stalled_stream_protection::StalledStreamProtectionConfig,
+};
+use aws_sdk_greengrassv2::{
+    self,
+    error::{ProvideErrorMetadata, SdkError},
+    types::{
+        ComponentDeploymentSpecification, ComponentVisibilityScope, DeploymentHistoryFilter,
+        DeploymentIoTJobConfiguration, InstalledComponentTopologyFilter, RecipeOutputFormat,
+    },
+};
+use aws_sdk_iot::{self, primitives::DateTime};
+use aws_sdk_iotdataplane::primitives::Blob;
+use aws_types::{region::Region, sdk_config::SdkConfig};
+use serde_json::Value;
+use tokio::sync::OnceCell;
+
+use super::CloudBackend;
+use crate::{
+    ActiveViolation, AuditFinding, Bucket, Certificate, Component, ComponentVersion, CoreDevice,
+    Deployment, DeploymentDetail, DeploymentHistory, DeploymentQuery, DeploymentSpec,
+    EffectiveDeployment, Error, InstalledComponent, Job, JobExecution, JobProcessDetails,
+    Percentile, Policy, Result, SearchPage, Shadow, Statistics, Thing, ThingDocument, ThingGroup,
+};
+
+mod convert;
+
+use convert::metric_value;
+
+/// Backend talking to the AWS Greengrass and IoT Core APIs.
+#[derive(Debug)]
+pub struct AwsBackend {
+    /// Local AWS config.
+    shared_config: SdkConfig,
+    /// Name of the AWS profile in use.
+    profile: String,
+    /// Region the clients are connected to.
+    region: String,
+    /// Greengrass connection client.
+    gg_client: aws_sdk_greengrassv2::Client,
+    /// IoT Core connection client.
+    iot_client: aws_sdk_iot::Client,
+    /// IoT data-plane client, created on first use once the account's endpoint is known.
+    data_client: OnceCell<aws_sdk_iotdataplane::Client>,
+}
+
+impl AwsBackend {
+    /// Constructs a new instance.
+    pub async fn new(profile: &str, region: &str) -> Result<Self> {
+        let region_provider = RegionProviderChain::first_try(Region::new(region.to_owned()))
+            .or_default_provider()
+            .or_else(Region::new("eu-west-1"));
+
+        let shared_config = aws_config::defaults(BehaviorVersion::latest())
+            .stalled_stream_protection(
+                StalledStreamProtectionConfig::enabled()
+                    .upload_enabled(false)
+                    .grace_period(Duration::from_secs(10))
+                    .build(),
+            )
+            .profile_name(profile)
+            .region(region_provider)
+            .load()
+            .await;
+
+        let region = shared_config
+            .region()
+            .map(|r| r.to_string())
+            .unwrap_or_else(|| region.to_owned());
+        let client = aws_sdk_greengrassv2::Client::new(&shared_config);
+        let iot_client = aws_sdk_iot::Client::new(&shared_config);
+
+        let backend = Self {
+            shared_config,
+            profile: profile.to_owned(),
+            region,
+            gg_client: client,
+            iot_client,
+            data_client: OnceCell::new(),
+        };
+
+        // Test to see if we need to authenicate
+        if let Err(e) = backend
+            .gg_client
+            .list_components()
+            .max_results(1)
+            .send()
+            .await
+        {
+            let error = backend.error(e, "greengrass:ListComponents");
+            tracing::error!("Failed to connect: {}", error);
+            return Err(error);
+        }
+
+        Ok(backend)
+    }
+
+    /// Maps an SDK error for the given IAM action into a typed error.
+    fn error<E, R>(&self, err: SdkError<E, R>, action: &str) -> Error
+    where
+        E: ProvideErrorMetadata + std::error::Error + 'static,
+        R: fmt::Debug + 'static,
+    {
+        Error::from_sdk(err, action, &self.profile, &self.region)
+    }
+
+    /// Returns the IoT data-plane client, looking up the account's data endpoint the first time.
+    async fn data_client(&self) -> Result<&aws_sdk_iotdataplane::Client> {
+        self.data_client
+            .get_or_try_init(|| async {
+                let resp = self
+                    .iot_client
+                    .describe_endpoint()
+                    .endpoint_type("iot:Data-ATS")
+                    .send()
+                    .await
+                    .map_err(|e| self.error(e, "iot:DescribeEndpoint"))?;
+                let address = resp
+                    .endpoint_address
+                    .ok_or_else(|| Error::NotFound(String::from("IoT data endpoint")))?;
+                let config = aws_sdk_iotdataplane::config::Builder::from(&self.shared_config)
+                    .endpoint_url(format!("https://{address}"))
+                    .build();
+                Ok(aws_sdk_iotdataplane::Client::from_conf(config))
+            })
+            .await
+    }
+}
+
+#[async_trait]
+impl CloudBackend for AwsBackend {
+    async fn list_core_devices(&self) -> Result<Vec<CoreDevice>> {
+        let resp = self
+            .gg_client
+            .list_core_devices()
+            .into_paginator()
+            .send()
+            .try_collect()
+            .await
+            .map_err(|e| self.error(e, "greengrass:ListCoreDevices"))?;
+
+        Ok(resp
+            .into_iter()
+            .flat_map(|x| x.core_devices.unwrap_or_default())
+            .map(|device| CoreDevice {
+                thing_name: device
+                    .core_device_thing_name()
+                    .unwrap_or_default()
+                    .to_string(),
+                status: device.status().map(|s| s.to_string()).unwrap_or_default(),
+                last_status_update_timestamp: device
+                    .last_status_update_timestamp()
+                    .map(|t| t.to_string())
+                    .unwrap_or_default(),
+            })
+            .collect())
+    }
+
+    async fn get_thing_connectivity(&self, thing_name: &str) -> Result<bool> {
+        let connectivity = self
+            .iot_client
+            .get_thing_connectivity_data()
+            .thing_name(thing_name)
+            .send()
+            .await
+            .map_err(|e| self.error(e, "iot:GetThingConnectivityData"))?;
+
+        Ok(connectivity.connected().unwrap_or_default())
+    }
+
+    async fn get_core_device_tags(&self, thing_name: &str) -> Result<BTreeMap<String, String>> {
+        let device = self
+            .gg_client
+            .get_core_device()
+            .core_device_thing_name(thing_name)
+            .send()
+            .await
+            .map_err(|e| self.error(e, "greengrass:GetCoreDevice"))?;
+
+        Ok(device.tags.unwrap_or_default().into_iter().collect())
+    }
+
+    async fn describe_thing(&self, thing_name: &str) -> Result<Thing> {
+        let thing = self
+            .iot_client
+            .describe_thing()
+            .thing_name(thing_name)
+            .send()
+            .await
+            .map_err(|e| self.error(e, "iot:DescribeThing"))?;
+
+        Ok(Thing {
+            thing_name: thing.thing_name.unwrap_or_else(|| thing_name.to_string()),
+            thing_type_name: thing.thing_type_name,
+            billing_group_name: thing.billing_group_name,
+            attributes: thing.attributes.unwrap_or_default().into_iter().collect(),
+        })
+    }
+
+    async fn list_thing_principals(&self, thing_name: &str) -> Result<Vec<String>> {
+        let resp = self
+            .iot_client
+            .list_thing_principals()
+            .thing_name(thing_name)
+            .into_paginator()
+            .send()
+            .try_collect()
+            .await
+            .map_err(|e| self.error(e, "iot:ListThingPrincipals"))?;
+
+        Ok(resp
+            .into_iter()
+            .flat_map(|x| x.principals.unwrap_or_default())
+            .collect())
+    }
+
+    async fn list_attached_policies(&self, target: &str) -> Result<Vec<String>> {
+        let resp = self
+            .iot_client
+            .list_attached_policies()
+            .target(target)
+            .into_paginator()
+            .send()
+            .try_collect()
+            .await
+            .map_err(|e| self.error(e, "iot:ListAttachedPolicies"))?;
+
+        Ok(resp
+            .into_iter()
+            .flat_map(|x| x.policies.unwrap_or_default())
+            .filter_map(|policy| policy.policy_name)
+            .collect())
+    }
+
+    async fn get_policy(&self, policy_name: &str) -> Result<Policy> {
+        let resp = self
+            .iot_client
+            .get_policy()
+            .policy_name(policy_name)
+            .send()
+            .await
+            .map_err(|e| self.error(e, "iot:GetPolicy"))?;
+
+        Ok(Policy {
+            policy_name: resp.policy_name.unwrap_or_else(|| policy_name.to_string()),
+            policy_arn: resp.policy_arn.unwrap_or_default(),
+            policy_document: resp.policy_document.unwrap_or_default(),
+        })
+    }
+
+    async fn describe_certificate(&self, certificate_id: &str) -> Result<Certificate> {
+        let resp = self
+            .iot_client
+            .describe_certificate()
+            .certificate_id(certificate_id)
+            .send()
+            .await
+            .map_err(|e| self.error(e, "iot:DescribeCertificate"))?;
+        let certificate = resp
+            .certificate_description
+            .ok_or_else(|| Error::NotFound(format!("Certificate {certificate_id}")))?;
+        let validity = certificate.validity;
+
+        Ok(Certificate {
+            thing_name: String::new(),
+            certificate_id: certificate
+                .certificate_id
+                .unwrap_or_else(|| certificate_id.to_string()),
+            certificate_arn: certificate.certificate_arn.unwrap_or_default(),
+            status: certificate
+                .status
+                .map(|s| s.to_string())
+                .unwrap_or_default(),
+            creation_date: certificate
+                .creation_date
+                .map(|t| t.to_string())
+                .unwrap_or_default(),
+            valid_from: validity
+                .as_ref()
+                .and_then(|v| v.not_before)
+                .map(|t| t.to_string())
+                .unwrap_or_default(),
+            valid_to: validity
+                .as_ref()
+                .and_then(|v| v.not_after)
+                .map(|t| t.to_string())
+                .unwrap_or_default(),
+        })
+    }
+
+    async fn list_thing_groups(&self) -> Result<Vec<ThingGroup>> {
+        let resp = self
+            .iot_client
+            .list_thing_groups()
+            .into_paginator()
+            .send()
+            .try_collect()
+            .await
+            .map_err(|e| self.error(e, "iot:ListThingGroups"))?;
+
+        Ok(resp
+            .into_iter()
+            .flat_map(|x| x.thing_groups.unwrap_or_default())
+            .map(|group| ThingGroup {
+                name: group.group_name.unwrap_or_default(),
+                arn: group.group_arn.unwrap_or_default(),
+                ..Default::default()
+            })
+            .collect())
+    }
+
+    async fn describe_thing_group(&self, group_name: &str) -> Result<ThingGroup> {
+        let group = self
+            .iot_client
+            .describe_thing_group()
+            .thing_group_name(group_name)
+            .send()
+            .await
+            .map_err(|e| self.error(e, "iot:DescribeThingGroup"))?;
+
+        let metadata = group.thing_group_metadata;
+        Ok(ThingGroup {
+            name: group
+                .thing_group_name
+                .unwrap_or_else(|| group_name.to_string()),
+            arn: group.thing_group_arn.unwrap_or_default(),
+            parent_group_name: metadata.as_ref().and_then(|m| m.parent_group_name.clone()),
+            root_to_parent: metadata
+                .and_then(|m| m.root_to_parent_thing_groups)
+                .unwrap_or_default()
+                .into_iter()
+                .filter_map(|group| group.group_name)
+                .collect(),
+            query_string: group.query_string,
+            tags: BTreeMap::new(),
+        })
+    }
+
+    async fn list_things_in_thing_group(
+        &self,
+        group_name: &str,
+        recursive: bool,
+    ) -> Result<Vec<String>> {
+        let resp = self
+            .iot_client
+            .list_things_in_thing_group()
+            .thing_group_name(group_name)
+            .recursive(recursive)
+            .into_paginator()
+            .send()
+            .try_collect()
+            .await
+            .map_err(|e| self.error(e, "iot:ListThingsInThingGroup"))?;
+
+        Ok(resp
+            .into_iter()
+            .flat_map(|x| x.things.unwrap_or_default())
+            .collect())
+    }
+
+    async fn add_thing_to_thing_group(&self, group_name: &str, thing_name: &str) -> Result<()> {
+        self.iot_client
+            .add_thing_to_thing_group()
+            .thing_group_name(group_name)
+            .thing_name(thing_name)
+            .send()
+            .await
+            .map_err(|e| self.error(e, "iot:AddThingToThingGroup"))?;
+        Ok(())
+    }
+
+    async fn remove_thing_from_thing_group(
+        &self,
+        group_name: &str,
+        thing_name: &str,
+    ) -> Result<()> {
+        self.iot_client
+            .remove_thing_from_thing_group()
+            .thing_group_name(group_name)
+            .thing_name(thing_name)
+            .send()
+            .await
+            .map_err(|e| self.error(e, "iot:RemoveThingFromThingGroup"))?;
+        Ok(())
+    }
+
+    async fn create_dynamic_thing_group(
+        &self,
+        group_name: &str,
+        query: &str,
+    ) -> Result<ThingGroup> {
+        let group = self
+            .iot_client
+            .create_dynamic_thing_group()
+            .thing_group_name(group_name)
+            .query_string(query)
+            .send()
+            .await
+            .map_err(|e| self.error(e, "iot:CreateDynamicThingGroup"))?;
+
+        Ok(ThingGroup {
+            name: group
+                .thing_group_name
+                .unwrap_or_else(|| group_name.to_string()),
+            arn: group.thing_group_arn.unwrap_or_default(),
+            query_string: group.query_string,
+            ..Default::default()
+        })
+    }
+
+    async fn search_index(&self, query: &str, next_token: Option<&str>) -> Result<SearchPage> {
+        let page = self
+            .iot_client
+            .search_index()
+            .query_string(query)
+            .set_next_token(next_token.map(str::to_string))
+            .send()
+            .await
+            .map_err(|e| not_enabled(self.error(e, "iot:SearchIndex"), "Fleet indexing"))?;
+
+        Ok(SearchPage {
+            things: page
+                .things
+                .unwrap_or_default()
+                .into_iter()
+                .map(|thing| {
+                    let connectivity = thing.connectivity;
+                    ThingDocument {
+                        thing_name: thing.thing_name.unwrap_or_default(),
+                        thing_type_name: thing.thing_type_name,
+                        thing_group_names: thing.thing_group_names.unwrap_or_default(),
+                        attributes: thing.attributes.unwrap_or_default().into_iter().collect(),
+                        connected: connectivity.as_ref().and_then(|c| c.connected),
+                        disconnect_reason: connectivity.and_then(|c| c.disconnect_reason),
+                    }
+                })
+                .collect(),
+            next_token: page.next_token,
+        })
+    }
+
+    async fn get_statistics(&self, query: &str, field: Option<&str>) -> Result<Statistics> {
+        let resp = self
+            .iot_client
+            .get_statistics()
+            .query_string(query)
+            .set_aggregation_field(field.map(str::to_string))
+            .send()
+            .await
+            .map_err(|e| not_enabled(self.error(e, "iot:GetStatistics"), "Fleet indexing"))?;
+
+        Ok(resp
+            .statistics
+            .map(|statistics| Statistics {
+                count: u64::try_from(statistics.count).unwrap_or_default(),
+                average: statistics.average,
+                sum: statistics.sum,
+                minimum: statistics.minimum,
+                maximum: statistics.maximum,
+                sum_of_squares: statistics.sum_of_squares,
+                variance: statistics.variance,
+                std_deviation: statistics.std_deviation,
+            })
+            .unwrap_or_default())
+    }
+
+    async fn get_cardinality(&self, query: &str, field: &str) -> Result<u64> {
+        let resp = self
+            .iot_client
+            .get_cardinality()
+            .query_string(query)
+            .aggregation_field(field)
+            .send()
+            .await
+            .map_err(|e| not_enabled(self.error(e, "iot:GetCardinality"), "Fleet indexing"))?;
+
+        Ok(u64::try_from(resp.cardinality).unwrap_or_default())
+    }
+
+    async fn get_percentiles(
+        &self,
+        query: &str,
+        field: &str,
+        percents: &[f64],
+    ) -> Result<Vec<Percentile>> {
+        let resp = self
+            .iot_client
+            .get_percentiles()
+            .query_string(query)
+            .aggregation_field(field)
+            .set_percents(Some(percents.to_vec()))
+            .send()
+            .await
+            .map_err(|e| not_enabled(self.error(e, "iot:GetPercentiles"), "Fleet indexing"))?;
+
+        Ok(resp
+            .percentiles
+            .unwrap_or_default()
+            .into_iter()
+            .map(|pair| Percentile {
+                percent: pair.percent,
+                value: pair.value,
+            })
+            .collect())
+    }
+
+    async fn get_buckets(
+        &self,
+        query: &str,
+        field: &str,
+        max_buckets: usize,
+    ) -> Result<Vec<Bucket>> {
+        let aggregation = aws_sdk_iot::types::BucketsAggregationType::builder()
+            .terms_aggregation(
+                aws_sdk_iot::types::TermsAggregation::builder()
+                    .max_buckets(i32::try_from(max_buckets).unwrap_or(i32::MAX))
+                    .build(),
+            )
+            .build();
+        let resp = self
+            .iot_client
+            .get_buckets_aggregation()
+            .query_string(query)
+            .aggregation_field(field)
+            .buckets_aggregation_type(aggregation)
+            .send()
+            .await
+            .map_err(|e| {
+                not_enabled(self.error(e, "iot:GetBucketsAggregation"), "Fleet indexing")
+            })?;
+
+        Ok(resp
+            .buckets
+            .unwrap_or_default()
+            .into_iter()
+            .map(|bucket| Bucket {
+                key: bucket.key_value.unwrap_or_default(),
+                count: u64::try_from(bucket.count).unwrap_or_default(),
+            })
+            .collect())
+    }
+
+    async fn list_tags_for_resource(&self, resource_arn: &str) -> Result<BTreeMap<String, String>> {
+        let resp = self
+            .iot_client
+            .list_tags_for_resource()
+            .resource_arn(resource_arn)
+            .into_paginator()
+            .send()
+            .try_collect()
+            .await
+            .map_err(|e| self.error(e, "iot:ListTagsForResource"))?;
+
+        Ok(resp
+            .into_iter()
+            .flat_map(|x| x.tags.unwrap_or_default())
+            .map(|tag| (tag.key, tag.value.unwrap_or_default()))
+            .collect())
+    }
+
+    async fn list_deployments(&self, query: &DeploymentQuery) -> Result<Vec<Deployment>> {
+        let history_filter = match query.history {
+            DeploymentHistory::LatestOnly => DeploymentHistoryFilter::LatestOnly,
+            DeploymentHistory::All => DeploymentHistoryFilter::All,
+        };
+
+        let resp = self
+            .gg_client
+            .list_deployments()
+            .history_filter(history_filter)
+            .set_target_arn(query.target_arn.clone())
+            .into_paginator()
+            .send()
+            .try_collect()
+            .await
+            .map_err(|e| self.error(e, "greengrass:ListDeployments"))?;
+
+        Ok(resp
+            .into_iter()
+            .flat_map(|x| x.deployments.unwrap_or_default())
+            .filter(|deployment| deployment.deployment_name.is_some())
+            .map(|deployment| Deployment {
+                deployment_id: deployment.deployment_id.unwrap_or_default(),
+                deployment_name: deployment.deployment_name.unwrap_or_default(),
+                target_arn: deployment.target_arn.unwrap_or_default(),
+                revision_id: deployment.revision_id.unwrap_or_default(),
+                deployment_status: deployment
+                    .deployment_status
+                    .map(|s| s.to_string())
+                    .unwrap_or_default(),
+                creation_timestamp: deployment
+                    .creation_timestamp
+                    .map(|t| t.to_string())
+                    .unwrap_or_default(),
+                is_latest_for_target: deployment.is_latest_for_target,
+                tags: BTreeMap::new(),
+            })
+            .collect())
+    }
+
+    async fn list_components(&self) -> Result<Vec<Component>> {
+        let resp = self
+            .gg_client
+            .list_components()
+            .scope(ComponentVisibilityScope::Private)
+            .into_paginator()
+            .send()
+            .try_collect()
+            .await
+            .map_err(|e| self.error(e, "greengrass:ListComponents"))?;
+
+        Ok(resp
+            .into_iter()
+            .flat_map(|x| x.components.unwrap_or_default())
+            .map(|component| {
+                let latest = component.latest_version();
+                Component {
+                    arn: component.arn().unwrap_or_default().to_string(),
+                    component_name: component.component_name().unwrap_or_default().to_string(),
+                    latest_version: latest
+                        .and_then(|l| l.component_version())
+                        .unwrap_or_default()
+                        .to_string(),
+                    publisher: latest
+                        .and_then(|l| l.publisher())
+                        .unwrap_or_default()
+                        .to_string(),
+                    description: latest
+                        .and_then(|l| l.description())
+                        .unwrap_or_default()
+                        .to_string(),
+                    creation_timestamp: latest
+                        .and_then(|l| l.creation_timestamp())
+                        .map(|t| t.to_string())
+                        .unwrap_or_default(),
+                }
+            })
+            .collect())
+    }
+
+    async fn list_component_versions(&self, arn: &str) -> Result<Vec<ComponentVersion>> {
+        let resp = self
+            .gg_client
+            .list_component_versions()
+            .arn(arn)
+            .into_paginator()
+            .send()
+            .try_collect()
+            .await
+            .map_err(|e| self.error(e, "greengrass:ListComponentVersions"))?;
+
+        Ok(resp
+            .into_iter()
+            .flat_map(|x| x.component_versions.unwrap_or_default())
+            .map(|version| ComponentVersion {
+                component_name: version.component_name().unwrap_or_default().to_string(),
+                component_version: version.component_version().unwrap_or_default().to_string(),
+                arn: version.arn().unwrap_or_default().to_string(),
+            })
+            .collect())
+    }
+
+    async fn get_component_recipe(&self, arn: &str) -> Result<String> {
+        let resp = self
+            .gg_client
+            .get_component()
+            .arn(arn)
+            .recipe_output_format(RecipeOutputFormat::Yaml)
+            .send()
+            .await
+            .map_err(|e| self.error(e, "greengrass:GetComponent"))?;
+
+        String::from_utf8(resp.recipe.into_inner()).map_err(|e| Error::Service {
+            code: String::from("InvalidRecipe"),
+            message: format!("Recipe for {arn} is not UTF-8: {e}"),
+        })
+    }
+
+    async fn get_deployment(&self, deployment_id: &str) -> Result<DeploymentDetail> {
+        let deployment = self
+            .gg_client
+            .get_deployment()
+            .deployment_id(deployment_id)
+            .send()
+            .await
+            .map_err(|e| self.error(e, "greengrass:GetDeployment"))?;
+
+        Ok(DeploymentDetail {
+            deployment_id: deployment.deployment_id.unwrap_or_default(),
+            deployment_name: deployment.deployment_name.unwrap_or_default(),
+            target_arn: deployment.target_arn.unwrap_or_default(),
+            revision_id: deployment.revision_id.unwrap_or_default(),
+            deployment_status: deployment
+                .deployment_status
+                .map(|s| s.to_string())
+                .unwrap_or_default(),
+            creation_timestamp: deployment
+                .creation_timestamp
+                .map(|t| t.to_string())
+                .unwrap_or_default(),
+            is_latest_for_target: deployment.is_latest_for_target,
+            parent_target_arn: deployment.parent_target_arn,
+            iot_job_id: deployment.iot_job_id,
+            components: deployment
+                .components
+                .unwrap_or_default()
+                .into_iter()
+                .map(|(name, spec)| (name, spec.into()))
+                .collect(),
+            deployment_policies: deployment.deployment_policies.map(Into::into),
+            iot_job_configuration: deployment.iot_job_configuration.map(Into::into),
+            tags: deployment.tags.unwrap_or_default().into_iter().collect(),
+        })
+    }
+
+    async fn list_installed_components(&self, thing_name: &str) -> Result<Vec<InstalledComponent>> {
+        let resp = self
+            .gg_client
+            .list_installed_components()
+            .core_device_thing_name(thing_name)
+            .topology_filter(InstalledComponentTopologyFilter::All)
+            .into_paginator()
+            .send()
+            .try_collect()
+            .await
+            .map_err(|e| self.error(e, "greengrass:ListInstalledComponents"))?;
+
+        Ok(resp
+            .into_iter()
+            .flat_map(|x| x.installed_components.unwrap_or_default())
+            .map(|component| InstalledComponent {
+                component_name: component.component_name.unwrap_or_default(),
+                component_version: component.component_version.unwrap_or_default(),
+                lifecycle_state: component
+                    .lifecycle_state
+                    .map(|s| s.to_string())
+                    .unwrap_or_default(),
+                lifecycle_state_details: component.lifecycle_state_details.unwrap_or_default(),
+                lifecycle_status_codes: component.lifecycle_status_codes.unwrap_or_default(),
+                is_root: component.is_root,
+                last_status_change_timestamp: component
+                    .last_status_change_timestamp
+                    .map(|t| t.to_string())
+                    .unwrap_or_default(),
+            })
+            .collect())
+    }
+
+    async fn list_effective_deployments(
+        &self,
+        thing_name: &str,
+    ) -> Result<Vec<EffectiveDeployment>> {
+        let resp = self
+            .gg_client
+            .list_effective_deployments()
+            .core_device_thing_name(thing_name)
+            .into_paginator()
+            .send()
+            .try_collect()
+            .await
+            .map_err(|e| self.error(e, "greengrass:ListEffectiveDeployments"))?;
+
+        Ok(resp
+            .into_iter()
+            .flat_map(|x| x.effective_deployments.unwrap_or_default())
+            .map(|deployment| {
+                let (error_types, error_stack) = deployment
+                    .status_details
+                    .map(|details| {
+                        (
+                            details.error_types.unwrap_or_default(),
+                            details.error_stack.unwrap_or_default(),
+                        )
+                    })
+                    .unwrap_or_default();
+                EffectiveDeployment {
+                    deployment_id: deployment.deployment_id,
+                    deployment_name: deployment.deployment_name,
+                    target_arn: deployment.target_arn,
+                    core_device_execution_status: deployment
+                        .core_device_execution_status
+                        .to_string(),
+                    reason: deployment.reason.unwrap_or_default(),
+                    error_types,
+                    error_stack,
+                    creation_timestamp: deployment.creation_timestamp.to_string(),
+                    modified_timestamp: deployment.modified_timestamp.to_string(),
+                }
+            })
+            .collect())
+    }
+
+    async fn create_deployment(&self, spec: &DeploymentSpec) -> Result<String> {
+        let invalid =
+            |e: aws_sdk_greengrassv2::error::BuildError| Error::InvalidRequest(e.to_string());
+
+        let components = spec
+            .components
+            .iter()
+            .map(|(name, component)| {
+                ComponentDeploymentSpecification::try_from(component).map(|c| (name.clone(), c))
+            })
+            .collect::<Result<_, _>>()
+            .map_err(invalid)?;
+        let iot_job_configuration = spec
+            .iot_job_configuration
+            .as_ref()
+            .map(DeploymentIoTJobConfiguration::try_from)
+            .transpose()
+            .map_err(invalid)?;
+
+        let resp = self
+            .gg_client
+            .create_deployment()
+            .target_arn(&spec.target_arn)
+            .deployment_name(&spec.deployment_name)
+            .set_parent_target_arn(spec.parent_target_arn.clone())
+            .set_components(Some(components))
+            .set_deployment_policies(spec.deployment_policies.as_ref().map(Into::into))
+            .set_iot_job_configuration(iot_job_configuration)
+            .set_tags((!spec.tags.is_empty()).then(|| spec.tags.clone().into_iter().collect()))
+            .send()
+            .await
+            .map_err(|e| self.error(e, "greengrass:CreateDeployment"))?;
+
+        Ok(resp.deployment_id.unwrap_or_default())
+    }
+
+    async fn cancel_deployment(&self, deployment_id: &str) -> Result<()> {
+        self.gg_client
+            .cancel_deployment()
+            .deployment_id(deployment_id)
+            .send()
+            .await
+            .map_err(|e| self.error(e, "greengrass:CancelDeployment"))?;
+        Ok(())
+    }
+
+    async fn list_jobs(&self) -> Result<Vec<Job>> {
+        let resp = self
+            .iot_client
+            .list_jobs()
+            .into_paginator()
+            .send()
+            .try_collect()
+            .await
+            .map_err(|e| self.error(e, "iot:ListJobs"))?;
+
+        Ok(resp
+            .into_iter()
+            .flat_map(|x| x.jobs.unwrap_or_default())
+            .map(|job| Job {
+                job_id: job.job_id.unwrap_or_default(),
+                job_arn: job.job_arn.unwrap_or_default(),
+                status: job.status.map(|s| s.to_string()).unwrap_or_default(),
+                target_selection: job
+                    .target_selection
+                    .map(|s| s.to_string())
+                    .unwrap_or_default(),
+                created_at: job.created_at.map(|t| t.to_string()).unwrap_or_default(),
+                last_updated_at: job
+                    .last_updated_at
+                    .map(|t| t.to_string())
+                    .unwrap_or_default(),
+                completed_at: job.completed_at.map(|t| t.to_string()).unwrap_or_default(),
+                ..Default::default()
+            })
+            .collect())
+    }
+
+    async fn describe_job(&self, job_id: &str) -> Result<Job> {
+        let resp = self
+            .iot_client
+            .describe_job()
+            .job_id(job_id)
+            .send()
+            .await
+            .map_err(|e| self.error(e, "iot:DescribeJob"))?;
+        let job = resp
+            .job
+            .ok_or_else(|| Error::NotFound(format!("Job {job_id}")))?;
+        let details = job
+            .job_process_details
+            .unwrap_or_else(|| aws_sdk_iot::types::JobProcessDetails::builder().build());
+
+        Ok(Job {
+            job_id: job.job_id.unwrap_or_default(),
+            job_arn: job.job_arn.unwrap_or_default(),
+            status: job.status.map(|s| s.to_string()).unwrap_or_default(),
+            target_selection: job
+                .target_selection
+                .map(|s| s.to_string())
+                .unwrap_or_default(),
+            targets: job.targets.unwrap_or_default(),
+            description: job.description.unwrap_or_default(),
+            created_at: job.created_at.map(|t| t.to_string()).unwrap_or_default(),
+            last_updated_at: job
+                .last_updated_at
+                .map(|t| t.to_string())
+                .unwrap_or_default(),
+            completed_at: job.completed_at.map(|t| t.to_string()).unwrap_or_default(),
+            process_details: JobProcessDetails {
+                succeeded: details.number_of_succeeded_things.unwrap_or_default(),
+                failed: details.number_of_failed_things.unwrap_or_default(),
+                in_progress: details.number_of_in_progress_things.unwrap_or_default(),
+                queued: details.number_of_queued_things.unwrap_or_default(),
+                canceled: details.number_of_canceled_things.unwrap_or_default(),
+                rejected: details.number_of_rejected_things.unwrap_or_default(),
+                removed: details.number_of_removed_things.unwrap_or_default(),
+                timed_out: details.number_of_timed_out_things.unwrap_or_default(),
+            },
+        })
+    }
+
+    async fn list_job_executions_for_job(&self, job_id: &str) -> Result<Vec<JobExecution>> {
+        let resp = self
+            .iot_client
+            .list_job_executions_for_job()
+            .job_id(job_id)
+            .into_paginator()
+            .send()
+            .try_collect()
+            .await
+            .map_err(|e| self.error(e, "iot:ListJobExecutionsForJob"))?;
+
+        Ok(resp
+            .into_iter()
+            .flat_map(|x| x.execution_summaries.unwrap_or_default())
+            .map(|execution| {
+                let summary = execution
+                    .job_execution_summary
+                    .unwrap_or_else(|| aws_sdk_iot::types::JobExecutionSummary::builder().build());
+                JobExecution {
+                    thing_arn: execution.thing_arn.unwrap_or_default(),
+                    status: summary.status.map(|s| s.to_string()).unwrap_or_default(),
+                    execution_number: summary.execution_number.unwrap_or_default(),
+                    retry_attempt: summary.retry_attempt.unwrap_or_default(),
+                    queued_at: summary.queued_at.map(|t| t.to_string()).unwrap_or_default(),
+                    started_at: summary
+                        .started_at
+                        .map(|t| t.to_string())
+                        .unwrap_or_default(),
+                    last_updated_at: summary
+                        .last_updated_at
+                        .map(|t| t.to_string())
+                        .unwrap_or_default(),
+                }
+            })
+            .collect())
+    }
+
+    async fn list_audit_findings(&self, since: DateTime) -> Result<Vec<AuditFinding>> {
+        let resp = self
+            .iot_client
+            .list_audit_findings()
+            .start_time(since)
+            .end_time(DateTime::from(std::time::SystemTime::now()))
+            .list_suppressed_findings(false)
+            .into_paginator()
+            .send()
+            .try_collect()
+            .await
+            .map_err(|e| not_enabled(self.error(e, "iot:ListAuditFindings"), "Device Defender"))?;
+
+        Ok(resp
+            .into_iter()
+            .flat_map(|x| x.findings.unwrap_or_default())
+            .map(|finding| {
+                let resource = finding.non_compliant_resource;
+                let identifier = resource
+                    .as_ref()
+                    .and_then(|r| r.resource_identifier.clone());
+                AuditFinding {
+                    finding_id: finding.finding_id.unwrap_or_default(),
+                    check_name: finding.check_name.unwrap_or_default(),
+                    severity: finding
+                        .severity
+                        .and_then(|s| s.as_str().parse().ok())
+                        .unwrap_or_default(),
+                    finding_time: finding
+                        .finding_time
+                        .map(|t| t.to_string())
+                        .unwrap_or_default(),
+                    resource_type: resource
+                        .and_then(|r| r.resource_type)
+                        .map(|t| t.to_string())
+                        .unwrap_or_default(),
+                    client_id: identifier.as_ref().and_then(|i| i.client_id.clone()),
+                    certificate_id: identifier.and_then(|i| i.device_certificate_id),
+                    reason: finding.reason_for_non_compliance.unwrap_or_default(),
+                    reason_code: finding.reason_for_non_compliance_code.unwrap_or_default(),
+                }
+            })
+            .collect())
+    }
+
+    async fn list_active_violations(&self) -> Result<Vec<ActiveViolation>> {
+        let resp = self
+            .iot_client
+            .list_active_violations()
+            .list_suppressed_alerts(false)
+            .into_paginator()
+            .send()
+            .try_collect()
+            .await
+            .map_err(|e| {
+                not_enabled(self.error(e, "iot:ListActiveViolations"), "Device Defender")
+            })?;
+
+        Ok(resp
+            .into_iter()
+            .flat_map(|x| x.active_violations.unwrap_or_default())
+            .map(|violation| {
+                let behavior = violation.behavior;
+                ActiveViolation {
+                    violation_id: violation.violation_id.unwrap_or_default(),
+                    thing_name: violation.thing_name.unwrap_or_default(),
+                    security_profile_name: violation.security_profile_name.unwrap_or_default(),
+                    behavior_name: behavior
+                        .as_ref()
+                        .map(|b| b.name.clone())
+                        .unwrap_or_default(),
+                    metric: behavior.and_then(|b| b.metric),
+                    last_value: violation.last_violation_value.as_ref().map(metric_value),
+                    confidence_level: violation
+                        .violation_event_additional_info
+                        .and_then(|info| info.confidence_level)
+                        .map(|level| level.to_string()),
+                    violation_start_time: violation
+                        .violation_start_time
+                        .map(|t| t.to_string())
+                        .unwrap_or_default(),
+                    last_violation_time: violation
+                        .last_violation_time
+                        .map(|t| t.to_string())
+                        .unwrap_or_default(),
+                }
+            })
+            .collect())
+    }
+
+    async fn list_named_shadows(&self, thing_name: &str) -> Result<Vec<String>> {
+        let client = self.data_client().await?;
+        let mut names = Vec::new();
+        let mut next_token = None;
+
+        // The operation has no paginator, so follow the tokens by hand.
+        loop {
+            let resp = client
+                .list_named_shadows_for_thing()
+                .thing_name(thing_name)
+                .set_next_token(next_token)
+                .send()
+                .await
+                .map_err(|e| self.error(e, "iot:ListNamedShadowsForThing"))?;
+            names.extend(resp.results.unwrap_or_default());
+            next_token = resp.next_token;
+            if next_token.is_none() {
+                break;
+            }
+        }
+
+        Ok(names)
+    }
+
+    async fn get_shadow(&self, thing_name: &str, shadow_name: Option<&str>) -> Result<Shadow> {
+        let resp = self
+            .data_client()
+            .await?
+            .get_thing_shadow()
+            .thing_name(thing_name)
+            .set_shadow_name(shadow_name.map(str::to_string))
+            .send()
+            .await
+            .map_err(|e| self.error(e, "iot:GetThingShadow"))?;
+        let payload = resp.payload.map(Blob::into_inner).unwrap_or_default();
+
+        Shadow::from_document(thing_name, shadow_name, &payload)
+    }
+
+    async fn update_shadow(
+        &self,
+        thing_name: &str,
+        shadow_name: Option<&str>,
+        payload: &Value,
+    ) -> Result<()> {
+        let payload =
+            serde_json::to_vec(payload).map_err(|e| Error::InvalidRequest(e.to_string()))?;
+        self.data_client()
+            .await?
+            .update_thing_shadow()
+            .thing_name(thing_name)
+            .set_shadow_name(shadow_name.map(str::to_string))
+            .payload(Blob::new(payload))
+            .send()
+            .await
+            .map_err(|e| self.error(e, "iot:UpdateThingShadow"))?;
+        Ok(())
+    }
+}
+
+/// Maps the "not found" returned by an optional service the account has not enabled, such as
+/// the missing `AWS_Things` index of fleet indexing, to [`Error::NotEnabled`].
+fn not_enabled(error: Error, service: &str) -> Error {
+    match error {
+        Error::NotFound(_) => Error::NotEnabled(service.to_string()),
+        error => error,
+    }
+}
@@ -0,0 +1,231 @@
+//! Conversions between the Greengrass and IoT SDK types and the library's own.
+
+use aws_sdk_greengrassv2::{error::BuildError, types as gg};
+use aws_sdk_iot::types as iot;
+
+use crate::{
+    AbortCriteria, ComponentDeploymentSpec, ComponentUpdatePolicy, ConfigurationUpdate,
+    DeploymentPolicies, ExponentialRolloutRate, IotJobConfiguration, RunWith, SystemResourceLimits,
+};
+
+impl From<gg::ComponentDeploymentSpecification> for ComponentDeploymentSpec {
+    fn from(spec: gg::ComponentDeploymentSpecification) -> Self {
+        Self {
+            component_version: spec.component_version,
+            configuration_update: spec.configuration_update.map(|update| ConfigurationUpdate {
+                merge: update.merge,
+                reset: update.reset.unwrap_or_default(),
+            }),
+            run_with: spec.run_with.map(|run_with| RunWith {
+                posix_user: run_with.posix_user,
+                windows_user: run_with.windows_user,
+                system_resource_limits: run_with.system_resource_limits.map(|limits| {
+                    SystemResourceLimits {
+                        memory: limits.memory,
+                        cpus: limits.cpus,
+                    }
+                }),
+            }),
+        }
+    }
+}
+
+impl From<gg::DeploymentPolicies> for DeploymentPolicies {
+    fn from(policies: gg::DeploymentPolicies) -> Self {
+        Self {
+            failure_handling_policy: policies.failure_handling_policy.map(|p| p.to_string()),
+            component_update_policy: policies.component_update_policy.map(|policy| {
+                ComponentUpdatePolicy {
+                    timeout_in_seconds: policy.timeout_in_seconds,
+                    action: policy.action.map(|a| a.to_string()),
+                }
+            }),
+            configuration_validation_timeout_in_seconds: policies
+                .configuration_validation_policy
+                .and_then(|policy| policy.timeout_in_seconds),
+        }
+    }
+}
+
+impl From<gg::DeploymentIoTJobConfiguration> for IotJobConfiguration {
+    fn from(config: gg::DeploymentIoTJobConfiguration) -> Self {
+        let rollout = config.job_executions_rollout_config;
+        Self {
+            maximum_per_minute: rollout.as_ref().and_then(|r| r.maximum_per_minute),
+            exponential_rate: rollout.and_then(|r| r.exponential_rate).map(|rate| {
+                ExponentialRolloutRate {
+                    base_rate_per_minute: rate.base_rate_per_minute,
+                    increment_factor: rate.increment_factor,
+                    number_of_notified_things: rate
+                        .rate_increase_criteria
+                        .as_ref()
+                        .and_then(|c| c.number_of_notified_things),
+                    number_of_succeeded_things: rate
+                        .rate_increase_criteria
+                        .as_ref()
+                        .and_then(|c| c.number_of_succeeded_things),
+                }
+            }),
+            abort_criteria: config
+                .abort_config
+                .map(|abort| abort.criteria_list)
+                .unwrap_or_default()
+                .into_iter()
+                .map(|criteria| AbortCriteria {
+                    failure_type: criteria.failure_type.to_string(),
+                    action: criteria.action.to_string(),
+                    threshold_percentage: criteria.threshold_percentage,
+                    min_number_of_executed_things: criteria.min_number_of_executed_things,
+                })
+                .collect(),
+            in_progress_timeout_in_minutes: config
+                .timeout_config
+                .and_then(|timeout| timeout.in_progress_timeout_in_minutes),
+        }
+    }
+}
+
+impl TryFrom<&ComponentDeploymentSpec> for gg::ComponentDeploymentSpecification {
+    type Error = BuildError;
+
+    fn try_from(spec: &ComponentDeploymentSpec) -> Result<Self, Self::Error> {
+        gg::ComponentDeploymentSpecification::builder()
+            .component_version(&spec.component_version)
+            .set_configuration_update(spec.configuration_update.as_ref().map(|update| {
+                gg::ComponentConfigurationUpdate::builder()
+                    .set_merge(update.merge.clone())
+                    .set_reset((!update.reset.is_empty()).then(|| update.reset.clone()))
+                    .build()
+            }))
+            .set_run_with(spec.run_with.as_ref().map(|run_with| {
+                gg::ComponentRunWith::builder()
+                    .set_posix_user(run_with.posix_user.clone())
+                    .set_windows_user(run_with.windows_user.clone())
+                    .set_system_resource_limits(run_with.system_resource_limits.as_ref().map(
+                        |limits| {
+                            gg::SystemResourceLimits::builder()
+                                .memory(limits.memory)
+                                .cpus(limits.cpus)
+                                .build()
+                        },
+                    ))
+                    .build()
+            }))
+            .build()
+    }
+}
+
+impl From<&DeploymentPolicies> for gg::DeploymentPolicies {
+    fn from(policies: &DeploymentPolicies) -> Self {
+        gg::DeploymentPolicies::builder()
+            .set_failure_handling_policy(
+                policies
+                    .failure_handling_policy
+                    .as_deref()
+                    .map(gg::DeploymentFailureHandlingPolicy::from),
+            )
+            .set_component_update_policy(policies.component_update_policy.as_ref().map(|policy| {
+                gg::DeploymentComponentUpdatePolicy::builder()
+                    .set_timeout_in_seconds(policy.timeout_in_seconds)
+                    .set_action(
+                        policy
+                            .action
+                            .as_deref()
+                            .map(gg::DeploymentComponentUpdatePolicyAction::from),
+                    )
+                    .build()
+            }))
+            .set_configuration_validation_policy(
+                policies
+                    .configuration_validation_timeout_in_seconds
+                    .map(|timeout| {
+                        gg::DeploymentConfigurationValidationPolicy::builder()
+                            .timeout_in_seconds(timeout)
+                            .build()
+                    }),
+            )
+            .build()
+    }
+}
+
+impl TryFrom<&IotJobConfiguration> for gg::DeploymentIoTJobConfiguration {
+    type Error = BuildError;
+
+    fn try_from(config: &IotJobConfiguration) -> Result<Self, Self::Error> {
+        let exponential_rate = config
+            .exponential_rate
+            .as_ref()
+            .map(|rate| {
+                gg::IoTJobExponentialRolloutRate::builder()
+                    .base_rate_per_minute(rate.base_rate_per_minute)
+                    .increment_factor(rate.increment_factor)
+                    .rate_increase_criteria(
+                        gg::IoTJobRateIncreaseCriteria::builder()
+                            .set_number_of_notified_things(rate.number_of_notified_things)
+                            .set_number_of_succeeded_things(rate.number_of_succeeded_things)
+                            .build(),
+                    )
+                    .build()
+            })
+            .transpose()?;
+        let rollout =
+            (config.maximum_per_minute.is_some() || exponential_rate.is_some()).then(|| {
+                gg::IoTJobExecutionsRolloutConfig::builder()
+                    .set_maximum_per_minute(config.maximum_per_minute)
+                    .set_exponential_rate(exponential_rate)
+                    .build()
+            });
+
+        let abort_config = if config.abort_criteria.is_empty() {
+            None
+        } else {
+            let criteria_list = config
+                .abort_criteria
+                .iter()
+                .map(|criteria| {
+                    gg::IoTJobAbortCriteria::builder()
+                        .failure_type(gg::IoTJobExecutionFailureType::from(
+                            criteria.failure_type.as_str(),
+                        ))
+                        .action(gg::IoTJobAbortAction::from(criteria.action.as_str()))
+                        .threshold_percentage(criteria.threshold_percentage)
+                        .min_number_of_executed_things(criteria.min_number_of_executed_things)
+                        .build()
+                })
+                .collect::<Result<Vec<_>, _>>()?;
+            Some(
+                gg::IoTJobAbortConfig::builder()
+                    .set_criteria_list(Some(criteria_list))
+                    .build()?,
+            )
+        };
+
+        Ok(gg::DeploymentIoTJobConfiguration::builder()
+            .set_job_executions_rollout_config(rollout)
+            .set_abort_config(abort_config)
+            .set_timeout_config(config.in_progress_timeout_in_minutes.map(|timeout| {
+                gg::IoTJobTimeoutConfig::builder()
+                    .in_progress_timeout_in_minutes(timeout)
+                    .build()
+            }))
+            .build())
+    }
+}
+
+/// Formats the value of a Device Defender metric, whichever kind of value it holds.
+pub(super) fn metric_value(value: &iot::MetricValue) -> String {
+    let join = |values: Vec<String>| values.join(", ");
+    if let Some(count) = value.count {
+        count.to_string()
+    } else if let Some(number) = value.number {
+        number.to_string()
+    } else if let Some(numbers) = &value.numbers {
+        join(numbers.iter().map(f64::to_string).collect())
+    } else if let Some(ports) = &value.ports {
+        join(ports.iter().map(i32::to_string).collect())
+    } else if let Some(cidrs) = &value.cidrs {
+        cidrs.join(", ")
+    } else {
+        value.strings.as_deref().unwrap_or_default().join(", ")
+    }
+}
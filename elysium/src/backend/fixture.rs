@@ -0,0 +1,813 @@
+use std::{
+    collections::{BTreeMap, BTreeSet, HashMap},
+    fs,
+    path::Path,
+    sync::{Mutex, MutexGuard},
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use async_trait::async_trait;
+use aws_sdk_iot::primitives::{DateTime, DateTimeFormat};
+use serde::{Deserialize, Serialize};
+use serde_json::{Value, json};
+
+use super::CloudBackend;
+use crate::{
+    ActiveViolation, AuditFinding, Bucket, Certificate, Component, ComponentVersion, CoreDevice,
+    Deployment, DeploymentDetail, DeploymentQuery, DeploymentSpec, EffectiveDeployment, Error,
+    InstalledComponent, Job, JobExecution, JobProcessDetails, Percentile, Policy, Result,
+    SearchPage, Shadow, Statistics, Thing, ThingDocument, ThingGroup,
+    aggregation::percentiles,
+    search::{Query, field_values},
+    shadow,
+};
+
+/// Fake fleet description loaded from a JSON or YAML fixture.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Fleet {
+    /// Greengrass core devices.
+    pub core_devices: Vec<CoreDevice>,
+    /// Tags of the core devices, keyed by thing name.
+    pub core_device_tags: HashMap<String, BTreeMap<String, String>>,
+    /// Connectivity state keyed by thing name. Missing things are disconnected.
+    pub connectivity: HashMap<String, bool>,
+    /// IoT registry entries keyed by thing name.
+    pub things: HashMap<String, Thing>,
+    /// Principal ARNs attached to each thing, keyed by thing name.
+    pub thing_principals: HashMap<String, Vec<String>>,
+    /// Certificates, without thing names.
+    pub certificates: Vec<Certificate>,
+    /// IoT policies.
+    pub policies: Vec<Policy>,
+    /// Names of the policies attached to each target, keyed by target ARN.
+    pub attached_policies: HashMap<String, Vec<String>>,
+    /// Thing groups.
+    pub thing_groups: Vec<ThingGroup>,
+    /// Names of the things directly in each thing group, keyed by group name.
+    pub thing_group_members: HashMap<String, Vec<String>>,
+    /// Tags of IoT resources such as thing groups, keyed by resource ARN.
+    pub tags: HashMap<String, BTreeMap<String, String>>,
+    /// Components in the private catalog.
+    pub components: Vec<Component>,
+    /// Versions keyed by component name, including public components.
+    pub component_versions: HashMap<String, Vec<ComponentVersion>>,
+    /// Recipes keyed by component version ARN.
+    pub recipes: HashMap<String, String>,
+    /// Deployments, including earlier revisions for each target.
+    pub deployments: Vec<DeploymentDetail>,
+    /// Installed components keyed by core device thing name.
+    pub installed_components: HashMap<String, Vec<InstalledComponent>>,
+    /// Effective deployments keyed by core device thing name.
+    pub effective_deployments: HashMap<String, Vec<EffectiveDeployment>>,
+    /// IoT jobs, including their execution counts.
+    pub jobs: Vec<Job>,
+    /// Job executions keyed by job ID.
+    pub job_executions: HashMap<String, Vec<JobExecution>>,
+    /// Device Defender audit findings.
+    pub audit_findings: Vec<AuditFinding>,
+    /// Device Defender active violations.
+    pub active_violations: Vec<ActiveViolation>,
+    /// Device shadows keyed by thing name.
+    pub shadows: HashMap<String, ThingShadows>,
+    /// Services the account has not enabled, `fleet_indexing` or `device_defender`. Their calls
+    /// fail as they do on AWS.
+    pub disabled_services: BTreeSet<String>,
+}
+
+/// Things per page of search results, small so that callers have to follow the next tokens.
+const SEARCH_PAGE_SIZE: usize = 2;
+/// Name of fleet indexing in [`Fleet::disabled_services`].
+const FLEET_INDEXING: &str = "fleet_indexing";
+/// Name of Device Defender in [`Fleet::disabled_services`].
+const DEVICE_DEFENDER: &str = "device_defender";
+
+impl Fleet {
+    /// Index documents of every thing in the fleet, sorted by name.
+    ///
+    /// Dynamic group memberships are left out, since they are themselves found by searching.
+    fn thing_documents(&self) -> Vec<ThingDocument> {
+        let mut names: Vec<&String> = self
+            .things
+            .keys()
+            .chain(self.core_devices.iter().map(|device| &device.thing_name))
+            .chain(self.thing_group_members.values().flatten())
+            .collect();
+        names.sort();
+        names.dedup();
+
+        names
+            .into_iter()
+            .map(|name| {
+                let thing = self.things.get(name).cloned().unwrap_or_default();
+                let mut thing_group_names: Vec<String> = self
+                    .thing_group_members
+                    .iter()
+                    .filter(|(_, members)| members.contains(name))
+                    .map(|(group, _)| group.clone())
+                    .collect();
+                thing_group_names.sort();
+                ThingDocument {
+                    thing_name: name.clone(),
+                    thing_type_name: thing.thing_type_name,
+                    thing_group_names,
+                    attributes: thing.attributes,
+                    connected: self.connectivity.get(name).copied(),
+                    disconnect_reason: None,
+                }
+            })
+            .collect()
+    }
+
+    /// Fails like the AWS backend does when the account has not enabled a service.
+    fn check_enabled(&self, service: &str) -> Result<()> {
+        if self.disabled_services.contains(service) {
+            let name = match service {
+                FLEET_INDEXING => "Fleet indexing",
+                DEVICE_DEFENDER => "Device Defender",
+                _ => service,
+            };
+            return Err(Error::NotEnabled(name.to_string()));
+        }
+        Ok(())
+    }
+
+    /// Fails unless the group exists and its members can be changed by hand.
+    fn check_static_group(&self, group_name: &str) -> Result<()> {
+        match self
+            .thing_groups
+            .iter()
+            .find(|group| group.name == group_name)
+        {
+            None => Err(Error::NotFound(format!("Thing group {group_name}"))),
+            Some(group) if group.query_string.is_some() => Err(Error::Service {
+                code: String::from("InvalidRequestException"),
+                message: format!("Cannot change the members of dynamic thing group {group_name}"),
+            }),
+            Some(_) => Ok(()),
+        }
+    }
+
+    /// Values of a field over the things matching a query, one per thing and value.
+    fn matching_values(&self, query: &str, field: &str) -> Result<Vec<String>> {
+        let query = Query::parse(query)?;
+        Ok(self
+            .thing_documents()
+            .iter()
+            .filter(|thing| query.matches(thing))
+            .flat_map(|thing| field_values(thing, field))
+            .collect())
+    }
+
+    /// Numeric values of a field over the things matching a query, skipping the others.
+    fn numeric_values(&self, query: &str, field: &str) -> Result<Vec<f64>> {
+        Ok(self
+            .matching_values(query, field)?
+            .iter()
+            .filter_map(|value| value.parse().ok())
+            .collect())
+    }
+}
+
+/// Shadow documents of a thing, shaped like `{"state": {"desired", "reported"}, "version"}`.
+///
+/// The delta is computed when a shadow is read, as the service does.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ThingShadows {
+    /// The classic shadow.
+    pub classic: Option<Value>,
+    /// Named shadows keyed by shadow name.
+    pub named: BTreeMap<String, Value>,
+}
+
+impl ThingShadows {
+    fn get(&self, shadow_name: Option<&str>) -> Option<&Value> {
+        match shadow_name {
+            Some(name) => self.named.get(name),
+            None => self.classic.as_ref(),
+        }
+    }
+}
+
+/// In-memory backend serving a [`Fleet`].
+///
+/// Changes such as new deployments are applied to the in-memory fleet only.
+#[derive(Debug, Default)]
+pub struct FixtureBackend {
+    /// The fake fleet.
+    fleet: Mutex<Fleet>,
+}
+
+impl From<Fleet> for FixtureBackend {
+    fn from(fleet: Fleet) -> Self {
+        Self {
+            fleet: Mutex::new(fleet),
+        }
+    }
+}
+
+impl FixtureBackend {
+    /// Loads a fixture from a file, picking the format from the file extension.
+    pub fn from_path(path: impl AsRef<Path>) -> Result<Self> {
+        let path = path.as_ref();
+        let contents = fs::read_to_string(path)
+            .map_err(|e| Error::Fixture(format!("Failed to read {}: {}", path.display(), e)))?;
+
+        match path.extension().and_then(|e| e.to_str()) {
+            Some("json") => Self::from_json(&contents),
+            Some("yaml") | Some("yml") => Self::from_yaml(&contents),
+            _ => Err(Error::Fixture(format!(
+                "Unsupported fixture format: {}",
+                path.display()
+            ))),
+        }
+    }
+
+    /// Parses a JSON fixture.
+    pub fn from_json(contents: &str) -> Result<Self> {
+        serde_json::from_str::<Fleet>(contents)
+            .map(Self::from)
+            .map_err(|e| Error::Fixture(e.to_string()))
+    }
+
+    /// Parses a YAML fixture.
+    pub fn from_yaml(contents: &str) -> Result<Self> {
+        serde_yaml::from_str::<Fleet>(contents)
+            .map(Self::from)
+            .map_err(|e| Error::Fixture(e.to_string()))
+    }
+
+    /// Returns a copy of the current state of the fleet.
+    pub fn fleet(&self) -> Fleet {
+        self.lock().clone()
+    }
+
+    fn lock(&self) -> MutexGuard<'_, Fleet> {
+        // The fleet stays consistent even if a holder panicked, so ignore poisoning.
+        self.fleet.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+#[async_trait]
+impl CloudBackend for FixtureBackend {
+    async fn list_core_devices(&self) -> Result<Vec<CoreDevice>> {
+        Ok(self.lock().core_devices.clone())
+    }
+
+    async fn get_thing_connectivity(&self, thing_name: &str) -> Result<bool> {
+        Ok(self
+            .lock()
+            .connectivity
+            .get(thing_name)
+            .copied()
+            .unwrap_or_default())
+    }
+
+    async fn get_core_device_tags(&self, thing_name: &str) -> Result<BTreeMap<String, String>> {
+        Ok(self
+            .lock()
+            .core_device_tags
+            .get(thing_name)
+            .cloned()
+            .unwrap_or_default())
+    }
+
+    async fn describe_thing(&self, thing_name: &str) -> Result<Thing> {
+        let thing = self
+            .lock()
+            .things
+            .get(thing_name)
+            .cloned()
+            .ok_or_else(|| Error::NotFound(format!("Thing {thing_name}")))?;
+        Ok(Thing {
+            thing_name: thing_name.to_string(),
+            ..thing
+        })
+    }
+
+    async fn list_thing_principals(&self, thing_name: &str) -> Result<Vec<String>> {
+        Ok(self
+            .lock()
+            .thing_principals
+            .get(thing_name)
+            .cloned()
+            .unwrap_or_default())
+    }
+
+    async fn list_attached_policies(&self, target: &str) -> Result<Vec<String>> {
+        Ok(self
+            .lock()
+            .attached_policies
+            .get(target)
+            .cloned()
+            .unwrap_or_default())
+    }
+
+    async fn get_policy(&self, policy_name: &str) -> Result<Policy> {
+        self.lock()
+            .policies
+            .iter()
+            .find(|policy| policy.policy_name == policy_name)
+            .cloned()
+            .ok_or_else(|| Error::NotFound(format!("Policy {policy_name}")))
+    }
+
+    async fn describe_certificate(&self, certificate_id: &str) -> Result<Certificate> {
+        self.lock()
+            .certificates
+            .iter()
+            .find(|certificate| certificate.certificate_id == certificate_id)
+            .cloned()
+            .ok_or_else(|| Error::NotFound(format!("Certificate {certificate_id}")))
+    }
+
+    async fn list_thing_groups(&self) -> Result<Vec<ThingGroup>> {
+        // Like `ListThingGroups`, leave the hierarchy to `describe_thing_group`.
+        Ok(self
+            .lock()
+            .thing_groups
+            .iter()
+            .map(|group| ThingGroup {
+                name: group.name.clone(),
+                arn: group.arn.clone(),
+                ..Default::default()
+            })
+            .collect())
+    }
+
+    async fn describe_thing_group(&self, group_name: &str) -> Result<ThingGroup> {
+        let fleet = self.lock();
+        let group = fleet
+            .thing_groups
+            .iter()
+            .find(|group| group.name == group_name)
+            .ok_or_else(|| Error::NotFound(format!("Thing group {group_name}")))?;
+
+        // Walk up from the parent, as the registry stores only the path to the root.
+        let mut root_to_parent = Vec::new();
+        let mut parent = group.parent_group_name.clone();
+        while let Some(name) = parent {
+            parent = fleet
+                .thing_groups
+                .iter()
+                .find(|group| group.name == name)
+                .and_then(|group| group.parent_group_name.clone());
+            root_to_parent.insert(0, name);
+        }
+        Ok(ThingGroup {
+            root_to_parent,
+            tags: BTreeMap::new(),
+            ..group.clone()
+        })
+    }
+
+    async fn list_things_in_thing_group(
+        &self,
+        group_name: &str,
+        recursive: bool,
+    ) -> Result<Vec<String>> {
+        let fleet = self.lock();
+        let mut groups = vec![group_name.to_string()];
+        let mut things = Vec::new();
+        while let Some(name) = groups.pop() {
+            things.extend(
+                fleet
+                    .thing_group_members
+                    .get(&name)
+                    .into_iter()
+                    .flatten()
+                    .cloned(),
+            );
+            let query = fleet
+                .thing_groups
+                .iter()
+                .find(|group| group.name == name)
+                .and_then(|group| group.query_string.as_deref());
+            if let Some(query) = query {
+                let query = Query::parse(query)?;
+                things.extend(
+                    fleet
+                        .thing_documents()
+                        .into_iter()
+                        .filter(|thing| query.matches(thing))
+                        .map(|thing| thing.thing_name),
+                );
+            }
+            if recursive {
+                groups.extend(
+                    fleet
+                        .thing_groups
+                        .iter()
+                        .filter(|group| group.parent_group_name.as_deref() == Some(name.as_str()))
+                        .map(|group| group.name.clone()),
+                );
+            }
+        }
+        things.sort();
+        things.dedup();
+        Ok(things)
+    }
+
+    async fn add_thing_to_thing_group(&self, group_name: &str, thing_name: &str) -> Result<()> {
+        let mut fleet = self.lock();
+        fleet.check_static_group(group_name)?;
+        if !fleet
+            .thing_documents()
+            .iter()
+            .any(|thing| thing.thing_name == thing_name)
+        {
+            return Err(Error::NotFound(format!("Thing {thing_name}")));
+        }
+        let members = fleet
+            .thing_group_members
+            .entry(group_name.to_string())
+            .or_default();
+        if !members.iter().any(|member| member == thing_name) {
+            members.push(thing_name.to_string());
+        }
+        Ok(())
+    }
+
+    async fn remove_thing_from_thing_group(
+        &self,
+        group_name: &str,
+        thing_name: &str,
+    ) -> Result<()> {
+        let mut fleet = self.lock();
+        fleet.check_static_group(group_name)?;
+        if let Some(members) = fleet.thing_group_members.get_mut(group_name) {
+            members.retain(|member| member != thing_name);
+        }
+        Ok(())
+    }
+
+    async fn create_dynamic_thing_group(
+        &self,
+        group_name: &str,
+        query: &str,
+    ) -> Result<ThingGroup> {
+        Query::parse(query)?;
+        let mut fleet = self.lock();
+        if fleet
+            .thing_groups
+            .iter()
+            .any(|group| group.name == group_name)
+        {
+            return Err(Error::Service {
+                code: String::from("ResourceAlreadyExistsException"),
+                message: format!("Thing group {group_name} already exists"),
+            });
+        }
+        // Reuse the account and region of the existing groups.
+        let prefix = fleet
+            .thing_groups
+            .first()
+            .and_then(|group| group.arn.rsplit_once('/'))
+            .map_or(
+                "arn:aws:iot:us-east-1:000000000000:thinggroup",
+                |(prefix, _)| prefix,
+            )
+            .to_string();
+        let group = ThingGroup {
+            name: group_name.to_string(),
+            arn: format!("{prefix}/{group_name}"),
+            query_string: Some(query.to_string()),
+            ..Default::default()
+        };
+        fleet.thing_groups.push(group.clone());
+        Ok(group)
+    }
+
+    async fn search_index(&self, query: &str, next_token: Option<&str>) -> Result<SearchPage> {
+        self.lock().check_enabled(FLEET_INDEXING)?;
+        let query = Query::parse(query)?;
+        let start = match next_token {
+            Some(token) => token.parse().map_err(|_| Error::Service {
+                code: String::from("InvalidRequestException"),
+                message: format!("Invalid next token {token}"),
+            })?,
+            None => 0,
+        };
+        let matches: Vec<ThingDocument> = self
+            .lock()
+            .thing_documents()
+            .into_iter()
+            .filter(|thing| query.matches(thing))
+            .collect();
+        let end = matches.len().min(start + SEARCH_PAGE_SIZE);
+
+        Ok(SearchPage {
+            things: matches.get(start..end).unwrap_or_default().to_vec(),
+            next_token: (end < matches.len()).then(|| end.to_string()),
+        })
+    }
+
+    async fn get_statistics(&self, query: &str, field: Option<&str>) -> Result<Statistics> {
+        let fleet = self.lock();
+        fleet.check_enabled(FLEET_INDEXING)?;
+        match field {
+            Some(field) => Ok(Statistics::of(&fleet.numeric_values(query, field)?)),
+            None => {
+                let query = Query::parse(query)?;
+                let count = fleet
+                    .thing_documents()
+                    .iter()
+                    .filter(|thing| query.matches(thing))
+                    .count();
+                Ok(Statistics {
+                    count: count as u64,
+                    ..Default::default()
+                })
+            }
+        }
+    }
+
+    async fn get_cardinality(&self, query: &str, field: &str) -> Result<u64> {
+        self.lock().check_enabled(FLEET_INDEXING)?;
+        let values: BTreeSet<String> = self
+            .lock()
+            .matching_values(query, field)?
+            .into_iter()
+            .collect();
+        Ok(values.len() as u64)
+    }
+
+    async fn get_percentiles(
+        &self,
+        query: &str,
+        field: &str,
+        percents: &[f64],
+    ) -> Result<Vec<Percentile>> {
+        self.lock().check_enabled(FLEET_INDEXING)?;
+        Ok(percentiles(
+            &self.lock().numeric_values(query, field)?,
+            percents,
+        ))
+    }
+
+    async fn get_buckets(
+        &self,
+        query: &str,
+        field: &str,
+        max_buckets: usize,
+    ) -> Result<Vec<Bucket>> {
+        self.lock().check_enabled(FLEET_INDEXING)?;
+        let mut counts: BTreeMap<String, u64> = BTreeMap::new();
+        for value in self.lock().matching_values(query, field)? {
+            *counts.entry(value).or_default() += 1;
+        }
+        let mut buckets: Vec<Bucket> = counts
+            .into_iter()
+            .map(|(key, count)| Bucket { key, count })
+            .collect();
+        // Stable, so values with the same count stay in alphabetical order.
+        buckets.sort_by_key(|bucket| std::cmp::Reverse(bucket.count));
+        buckets.truncate(max_buckets);
+        Ok(buckets)
+    }
+
+    async fn list_tags_for_resource(&self, resource_arn: &str) -> Result<BTreeMap<String, String>> {
+        Ok(self
+            .lock()
+            .tags
+            .get(resource_arn)
+            .cloned()
+            .unwrap_or_default())
+    }
+
+    async fn list_deployments(&self, query: &DeploymentQuery) -> Result<Vec<Deployment>> {
+        Ok(self
+            .lock()
+            .deployments
+            .iter()
+            .map(|deployment| Deployment {
+                tags: BTreeMap::new(),
+                ..Deployment::from(deployment)
+            })
+            .filter(|deployment| query.matches(deployment))
+            .collect())
+    }
+
+    async fn list_components(&self) -> Result<Vec<Component>> {
+        Ok(self.lock().components.clone())
+    }
+
+    async fn list_component_versions(&self, arn: &str) -> Result<Vec<ComponentVersion>> {
+        // Component ARNs end in `:components:<name>`, for public and private components alike.
+        let name = arn.rsplit(':').next().unwrap_or(arn);
+        self.lock()
+            .component_versions
+            .get(name)
+            .cloned()
+            .ok_or_else(|| Error::NotFound(format!("Component {arn}")))
+    }
+
+    async fn get_component_recipe(&self, arn: &str) -> Result<String> {
+        self.lock()
+            .recipes
+            .get(arn)
+            .cloned()
+            .ok_or_else(|| Error::NotFound(format!("Recipe for {arn}")))
+    }
+
+    async fn get_deployment(&self, deployment_id: &str) -> Result<DeploymentDetail> {
+        self.lock()
+            .deployments
+            .iter()
+            .find(|deployment| deployment.deployment_id == deployment_id)
+            .cloned()
+            .ok_or_else(|| Error::NotFound(format!("Deployment {deployment_id}")))
+    }
+
+    async fn list_installed_components(&self, thing_name: &str) -> Result<Vec<InstalledComponent>> {
+        Ok(self
+            .lock()
+            .installed_components
+            .get(thing_name)
+            .cloned()
+            .unwrap_or_default())
+    }
+
+    async fn list_effective_deployments(
+        &self,
+        thing_name: &str,
+    ) -> Result<Vec<EffectiveDeployment>> {
+        Ok(self
+            .lock()
+            .effective_deployments
+            .get(thing_name)
+            .cloned()
+            .unwrap_or_default())
+    }
+
+    async fn create_deployment(&self, spec: &DeploymentSpec) -> Result<String> {
+        let mut fleet = self.lock();
+        let deployment_id = format!("fixture-{:08x}", fleet.deployments.len() + 1);
+        let mut revision = 0;
+
+        for deployment in fleet.deployments.iter_mut() {
+            if deployment.target_arn == spec.target_arn {
+                revision = revision.max(deployment.revision_id.parse().unwrap_or(0));
+                if deployment.is_latest_for_target {
+                    deployment.is_latest_for_target = false;
+                    deployment.deployment_status = String::from("INACTIVE");
+                }
+            }
+        }
+
+        fleet.deployments.push(DeploymentDetail {
+            deployment_id: deployment_id.clone(),
+            deployment_name: spec.deployment_name.clone(),
+            target_arn: spec.target_arn.clone(),
+            revision_id: (revision + 1).to_string(),
+            deployment_status: String::from("ACTIVE"),
+            is_latest_for_target: true,
+            parent_target_arn: spec.parent_target_arn.clone(),
+            components: spec.components.clone(),
+            deployment_policies: spec.deployment_policies.clone(),
+            iot_job_configuration: spec.iot_job_configuration.clone(),
+            tags: spec.tags.clone(),
+            ..Default::default()
+        });
+
+        Ok(deployment_id)
+    }
+
+    async fn cancel_deployment(&self, deployment_id: &str) -> Result<()> {
+        let mut fleet = self.lock();
+        let deployment = fleet
+            .deployments
+            .iter_mut()
+            .find(|deployment| deployment.deployment_id == deployment_id)
+            .ok_or_else(|| Error::NotFound(format!("Deployment {deployment_id}")))?;
+        deployment.deployment_status = String::from("CANCELED");
+        Ok(())
+    }
+
+    async fn list_jobs(&self) -> Result<Vec<Job>> {
+        // Like the service, the listing leaves out what only DescribeJob returns.
+        Ok(self
+            .lock()
+            .jobs
+            .iter()
+            .map(|job| Job {
+                targets: Vec::new(),
+                description: String::new(),
+                process_details: JobProcessDetails::default(),
+                ..job.clone()
+            })
+            .collect())
+    }
+
+    async fn describe_job(&self, job_id: &str) -> Result<Job> {
+        self.lock()
+            .jobs
+            .iter()
+            .find(|job| job.job_id == job_id)
+            .cloned()
+            .ok_or_else(|| Error::NotFound(format!("Job {job_id}")))
+    }
+
+    async fn list_job_executions_for_job(&self, job_id: &str) -> Result<Vec<JobExecution>> {
+        Ok(self
+            .lock()
+            .job_executions
+            .get(job_id)
+            .cloned()
+            .unwrap_or_default())
+    }
+
+    async fn list_audit_findings(&self, since: DateTime) -> Result<Vec<AuditFinding>> {
+        self.lock().check_enabled(DEVICE_DEFENDER)?;
+        Ok(self
+            .lock()
+            .audit_findings
+            .iter()
+            .filter(|finding| {
+                DateTime::from_str(&finding.finding_time, DateTimeFormat::DateTime)
+                    .is_ok_and(|time| time.secs() >= since.secs())
+            })
+            .cloned()
+            .collect())
+    }
+
+    async fn list_active_violations(&self) -> Result<Vec<ActiveViolation>> {
+        let fleet = self.lock();
+        fleet.check_enabled(DEVICE_DEFENDER)?;
+        Ok(fleet.active_violations.clone())
+    }
+
+    async fn list_named_shadows(&self, thing_name: &str) -> Result<Vec<String>> {
+        Ok(self
+            .lock()
+            .shadows
+            .get(thing_name)
+            .map(|shadows| shadows.named.keys().cloned().collect())
+            .unwrap_or_default())
+    }
+
+    async fn get_shadow(&self, thing_name: &str, shadow_name: Option<&str>) -> Result<Shadow> {
+        let mut document = self
+            .lock()
+            .shadows
+            .get(thing_name)
+            .and_then(|shadows| shadows.get(shadow_name).cloned())
+            .ok_or_else(|| {
+                let name = shadow_name.unwrap_or(crate::CLASSIC_SHADOW);
+                Error::NotFound(format!("Shadow {name} of {thing_name}"))
+            })?;
+
+        let state = &document["state"];
+        if let Some(delta) = shadow::delta(&state["desired"], state.get("reported")) {
+            document["state"]["delta"] = delta;
+        }
+        Shadow::from_document(thing_name, shadow_name, document.to_string().as_bytes())
+    }
+
+    async fn update_shadow(
+        &self,
+        thing_name: &str,
+        shadow_name: Option<&str>,
+        payload: &Value,
+    ) -> Result<()> {
+        let mut fleet = self.lock();
+        // A rejected update must not create the shadow, so check the version first.
+        let existing = fleet
+            .shadows
+            .get(thing_name)
+            .and_then(|shadows| match shadow_name {
+                Some(name) => shadows.named.get(name),
+                None => shadows.classic.as_ref(),
+            });
+        let version = existing
+            .and_then(|document| document["version"].as_i64())
+            .unwrap_or_default();
+        if let Some(expected) = payload["version"].as_i64() {
+            if expected != version {
+                return Err(Error::Service {
+                    code: String::from("ConflictException"),
+                    message: format!("Version conflict: expected {expected}, found {version}"),
+                });
+            }
+        }
+
+        let shadows = fleet.shadows.entry(thing_name.to_string()).or_default();
+        let document = match shadow_name {
+            Some(name) => shadows
+                .named
+                .entry(name.to_string())
+                .or_insert_with(|| json!({})),
+            None => shadows.classic.get_or_insert_with(|| json!({})),
+        };
+        shadow::merge(&mut document["state"], &payload["state"]);
+        document["version"] = json!(version + 1);
+        document["timestamp"] = json!(
+            SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .map(|elapsed| elapsed.as_secs())
+                .unwrap_or_default()
+        );
+        Ok(())
+    }
+}
@@ -0,0 +1,396 @@
+use std::{
+    collections::{BTreeSet, HashSet},
+    fmt,
+    fs::{self, OpenOptions},
+    io::Write,
+    path::{Path, PathBuf},
+    str::FromStr,
+    time::Duration,
+};
+
+use crate::{CloudBackend, Error, Property, Result};
+
+/// Default number of membership changes made per second.
+pub const DEFAULT_BULK_RATE: u32 = 10;
+
+/// Whether things are added to or removed from a thing group.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum MembershipChange {
+    Add,
+    Remove,
+}
+
+impl fmt::Display for MembershipChange {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            MembershipChange::Add => write!(f, "add"),
+            MembershipChange::Remove => write!(f, "remove"),
+        }
+    }
+}
+
+impl FromStr for MembershipChange {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s.to_ascii_lowercase().as_str() {
+            "add" => Ok(MembershipChange::Add),
+            "remove" => Ok(MembershipChange::Remove),
+            _ => Err(Error::InvalidRequest(format!(
+                "Expected add or remove, got {s}"
+            ))),
+        }
+    }
+}
+
+/// Things to add to or remove from a thing group.
+#[derive(Clone, Debug, PartialEq)]
+pub struct BulkMembership {
+    /// Whether the things are added or removed.
+    pub change: MembershipChange,
+    /// The name of the static thing group.
+    pub group_name: String,
+    /// The things, without duplicates.
+    pub thing_names: Vec<String>,
+}
+
+impl BulkMembership {
+    /// Plans a membership change, dropping duplicate thing names.
+    pub fn new(
+        change: MembershipChange,
+        group_name: impl Into<String>,
+        thing_names: Vec<String>,
+    ) -> Self {
+        let mut seen = HashSet::new();
+        Self {
+            change,
+            group_name: group_name.into(),
+            thing_names: thing_names
+                .into_iter()
+                .filter(|name| seen.insert(name.clone()))
+                .collect(),
+        }
+    }
+
+    /// The first line of a resume file, which ties it to this change.
+    fn resume_header(&self) -> String {
+        format!("# {} {}", self.change, self.group_name)
+    }
+}
+
+/// How a bulk membership change runs.
+#[derive(Clone, Debug, PartialEq)]
+pub struct BulkOptions {
+    /// Only reports what would change.
+    pub dry_run: bool,
+    /// Maximum number of changes made per second.
+    pub rate: u32,
+    /// File recording the things already handled, so an interrupted run can pick up where it
+    /// stopped. It is removed once a run finishes without failures.
+    pub resume_file: Option<PathBuf>,
+}
+
+impl Default for BulkOptions {
+    fn default() -> Self {
+        Self {
+            dry_run: false,
+            rate: DEFAULT_BULK_RATE,
+            resume_file: None,
+        }
+    }
+}
+
+/// Parses a CSV or newline-separated list of thing names.
+///
+/// The thing name is the first column. Blank lines, `#` comments and a `thingName` or
+/// `thing_name` header are skipped, as are duplicates.
+pub fn parse_thing_names(contents: &str) -> Vec<String> {
+    let mut seen = HashSet::new();
+    contents
+        .lines()
+        .map(|line| {
+            line.split(',')
+                .next()
+                .unwrap_or_default()
+                .trim()
+                .trim_matches('"')
+        })
+        .filter(|name| !name.is_empty() && !name.starts_with('#'))
+        .filter(|name| !matches!(*name, "thingName" | "thing_name"))
+        .filter(|name| seen.insert(name.to_string()))
+        .map(str::to_string)
+        .collect()
+}
+
+/// Reads a CSV or newline-separated list of thing names, see [`parse_thing_names`].
+pub fn read_thing_names(path: impl AsRef<Path>) -> Result<Vec<String>> {
+    let path = path.as_ref();
+    let contents = fs::read_to_string(path)
+        .map_err(|e| Error::File(format!("Failed to read {}: {}", path.display(), e)))?;
+    Ok(parse_thing_names(&contents))
+}
+
+/// Things handled by an earlier run of the same change, from its resume file.
+pub(crate) fn read_resume_file(
+    path: &Path,
+    membership: &BulkMembership,
+) -> Result<HashSet<String>> {
+    let contents = match fs::read_to_string(path) {
+        Ok(contents) => contents,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(HashSet::new()),
+        Err(e) => {
+            return Err(Error::File(format!(
+                "Failed to read {}: {}",
+                path.display(),
+                e
+            )));
+        }
+    };
+    let mut lines = contents.lines();
+    match lines.next() {
+        None => Ok(HashSet::new()),
+        Some(header) if header == membership.resume_header() => Ok(lines
+            .filter(|line| !line.is_empty())
+            .map(str::to_string)
+            .collect()),
+        Some(header) => Err(Error::InvalidRequest(format!(
+            "{} belongs to another change ({})",
+            path.display(),
+            header.trim_start_matches("# ")
+        ))),
+    }
+}
+
+/// Records a handled thing in the resume file, creating it if needed.
+pub(crate) fn append_resume_file(
+    path: &Path,
+    membership: &BulkMembership,
+    thing_name: &str,
+) -> Result<()> {
+    let error =
+        |e: std::io::Error| Error::File(format!("Failed to write {}: {}", path.display(), e));
+    let mut file = OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(path)
+        .map_err(error)?;
+    if file.metadata().map_err(error)?.len() == 0 {
+        writeln!(file, "{}", membership.resume_header()).map_err(error)?;
+    }
+    writeln!(file, "{thing_name}").map_err(error)
+}
+
+/// Adds things to, or removes them from, a static thing group, one at a time.
+///
+/// Things that are already members, or not members when removing, are skipped. Changes are
+/// made at most `options.rate` per second and failures do not stop the run. With a resume
+/// file, things recorded by an earlier run of the same change are not retried.
+pub async fn run_bulk_membership(
+    backend: &dyn CloudBackend,
+    membership: &BulkMembership,
+    options: &BulkOptions,
+) -> Result<BulkReport> {
+    let group = backend.describe_thing_group(&membership.group_name).await?;
+    if group.query_string.is_some() {
+        return Err(Error::InvalidRequest(format!(
+            "{} is a dynamic thing group, its members follow its query",
+            group.name
+        )));
+    }
+    let members: BTreeSet<String> = backend
+        .list_things_in_thing_group(&membership.group_name, false)
+        .await?
+        .into_iter()
+        .collect();
+    let resumed = match &options.resume_file {
+        Some(path) => read_resume_file(path, membership)?,
+        None => Default::default(),
+    };
+
+    let mut interval = tokio::time::interval(Duration::from_secs_f64(
+        1.0 / f64::from(options.rate.max(1)),
+    ));
+    interval.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+    let mut items = Vec::new();
+    for thing_name in &membership.thing_names {
+        let is_member = members.contains(thing_name);
+        let outcome = if resumed.contains(thing_name) {
+            BulkOutcome::Resumed
+        } else if membership.change == MembershipChange::Add && is_member {
+            BulkOutcome::Skipped(String::from("already a member"))
+        } else if membership.change == MembershipChange::Remove && !is_member {
+            BulkOutcome::Skipped(String::from("not a member"))
+        } else if options.dry_run {
+            BulkOutcome::Planned
+        } else {
+            interval.tick().await;
+            let result = match membership.change {
+                MembershipChange::Add => {
+                    backend
+                        .add_thing_to_thing_group(&membership.group_name, thing_name)
+                        .await
+                }
+                MembershipChange::Remove => {
+                    backend
+                        .remove_thing_from_thing_group(&membership.group_name, thing_name)
+                        .await
+                }
+            };
+            match result {
+                Ok(()) => {
+                    if let Some(path) = &options.resume_file {
+                        append_resume_file(path, membership, thing_name)?;
+                    }
+                    BulkOutcome::Done
+                }
+                Err(e) => {
+                    tracing::error!(
+                        "Failed to {} {} in {}: {}",
+                        membership.change,
+                        thing_name,
+                        membership.group_name,
+                        e
+                    );
+                    BulkOutcome::Failed(e.to_string())
+                }
+            }
+        };
+        items.push(BulkItem {
+            thing_name: thing_name.clone(),
+            outcome,
+        });
+    }
+
+    let report = BulkReport::new(membership, options.dry_run, items);
+    if !options.dry_run && !report.has_failures() {
+        if let Some(path) = &options.resume_file {
+            if path.exists() {
+                std::fs::remove_file(path).map_err(|e| {
+                    Error::File(format!("Failed to remove {}: {}", path.display(), e))
+                })?;
+            }
+        }
+    }
+    tracing::info!(
+        "{} things in {}: {}",
+        membership.change,
+        membership.group_name,
+        report.summary()
+    );
+    Ok(report)
+}
+
+/// What happened, or would happen, to a single thing.
+#[derive(Clone, Debug, PartialEq)]
+pub enum BulkOutcome {
+    /// The change would be made, in a dry run.
+    Planned,
+    /// The change was made.
+    Done,
+    /// The change was made by an earlier run, according to the resume file.
+    Resumed,
+    /// There was nothing to do, e.g. the thing was already a member.
+    Skipped(String),
+    /// The change failed.
+    Failed(String),
+}
+
+impl fmt::Display for BulkOutcome {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            BulkOutcome::Planned => write!(f, "planned"),
+            BulkOutcome::Done => write!(f, "done"),
+            BulkOutcome::Resumed => write!(f, "done earlier"),
+            BulkOutcome::Skipped(_) => write!(f, "skipped"),
+            BulkOutcome::Failed(_) => write!(f, "failed"),
+        }
+    }
+}
+
+/// Outcome of a bulk membership change for a single thing.
+#[derive(Clone, Debug, PartialEq)]
+pub struct BulkItem {
+    /// The name of the thing.
+    pub thing_name: String,
+    /// What happened to it.
+    pub outcome: BulkOutcome,
+}
+
+/// Per-thing report of a bulk membership change.
+#[derive(Clone, Debug, PartialEq)]
+pub struct BulkReport {
+    /// Whether things were added or removed.
+    pub change: MembershipChange,
+    /// The name of the thing group.
+    pub group_name: String,
+    /// Whether nothing was changed.
+    pub dry_run: bool,
+    /// Inner type.
+    inner: Vec<BulkItem>,
+}
+
+impl BulkReport {
+    pub(crate) fn new(membership: &BulkMembership, dry_run: bool, items: Vec<BulkItem>) -> Self {
+        Self {
+            change: membership.change,
+            group_name: membership.group_name.clone(),
+            dry_run,
+            inner: items,
+        }
+    }
+
+    /// Returns the outcome for each thing, in the order they were given.
+    pub fn as_slice(&self) -> &[BulkItem] {
+        &self.inner
+    }
+
+    /// Number of things with an outcome like the given one, ignoring reasons.
+    pub fn count(&self, outcome: &BulkOutcome) -> usize {
+        self.inner
+            .iter()
+            .filter(|item| std::mem::discriminant(&item.outcome) == std::mem::discriminant(outcome))
+            .count()
+    }
+
+    /// Whether any change failed.
+    pub fn has_failures(&self) -> bool {
+        self.count(&BulkOutcome::Failed(String::new())) > 0
+    }
+
+    /// One-line summary, e.g. `12 done, 3 skipped, 1 failed`.
+    pub fn summary(&self) -> String {
+        let outcomes = [
+            BulkOutcome::Planned,
+            BulkOutcome::Done,
+            BulkOutcome::Resumed,
+            BulkOutcome::Skipped(String::new()),
+            BulkOutcome::Failed(String::new()),
+        ];
+        let parts: Vec<String> = outcomes
+            .iter()
+            .map(|outcome| (self.count(outcome), outcome))
+            .filter(|(count, _)| *count > 0)
+            .map(|(count, outcome)| format!("{count} {outcome}"))
+            .collect();
+        if parts.is_empty() { String::from("no things") } else { parts.join(", ") }
+    }
+}
+
+impl<'a> Property<'a> for BulkReport {
+    fn items(&self) -> Vec<Vec<String>> {
+        self.inner
+            .iter()
+            .map(|item| {
+                let detail = match &item.outcome {
+                    BulkOutcome::Skipped(reason) | BulkOutcome::Failed(reason) => reason.clone(),
+                    _ => String::new(),
+                };
+                vec![
+                    item.thing_name.to_string(),
+                    item.outcome.to_string(),
+                    detail,
+                ]
+            })
+            .collect()
+    }
+}
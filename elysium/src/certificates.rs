@@ -0,0 +1,166 @@
+use std::collections::BTreeMap;
+
+use aws_sdk_iot::primitives::{DateTime, DateTimeFormat};
+use serde::{Deserialize, Serialize};
+
+use crate::Property;
+
+/// Seconds in a day, for turning validity ends into days left.
+const SECONDS_PER_DAY: i64 = 24 * 60 * 60;
+
+/// X.509 certificate attached to a thing.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Certificate {
+    /// The name of the thing the certificate is attached to.
+    pub thing_name: String,
+    /// The ID of the certificate.
+    pub certificate_id: String,
+    /// The ARN of the certificate.
+    pub certificate_arn: String,
+    /// The status of the certificate, e.g. ACTIVE, INACTIVE or REVOKED.
+    pub status: String,
+    /// The time at which the certificate was created, expressed in ISO 8601 format.
+    pub creation_date: String,
+    /// The start of the validity period, expressed in ISO 8601 format.
+    pub valid_from: String,
+    /// The end of the validity period, expressed in ISO 8601 format.
+    pub valid_to: String,
+}
+
+impl Certificate {
+    /// Whole days from `now` until the certificate expires, negative once it has expired.
+    ///
+    /// `None` if the end of the validity period is unknown.
+    pub fn days_left(&self, now: DateTime) -> Option<i64> {
+        let valid_to = DateTime::from_str(&self.valid_to, DateTimeFormat::DateTime).ok()?;
+        Some((valid_to.secs() - now.secs()).div_euclid(SECONDS_PER_DAY))
+    }
+
+    /// Whether devices can still connect with the certificate.
+    pub fn is_active(&self) -> bool {
+        self.status == "ACTIVE"
+    }
+
+    /// Whether the certificate is active and expires within `days` of `now`, or already has.
+    pub fn expires_within(&self, days: i64, now: DateTime) -> bool {
+        self.is_active() && self.days_left(now).is_some_and(|left| left < days)
+    }
+}
+
+/// The ID of a certificate from its ARN, `None` for other principals.
+pub(crate) fn certificate_id(principal_arn: &str) -> Option<&str> {
+    principal_arn.split_once(":cert/").map(|(_, id)| id)
+}
+
+/// Certificate expiring soon, as listed in an [`ExpiryReport`].
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct ExpiringCertificate {
+    /// The certificate.
+    #[serde(flatten)]
+    pub certificate: Certificate,
+    /// Whole days until it expires, negative once it has expired.
+    pub days_left: i64,
+}
+
+/// Active certificates that expire within a number of days, soonest first.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct ExpiryReport {
+    /// The time the report was made for, expressed in ISO 8601 format.
+    pub generated_at: String,
+    /// The number of days looked ahead.
+    pub within_days: i64,
+    /// The certificates expiring in that window.
+    pub certificates: Vec<ExpiringCertificate>,
+    /// Things whose certificates could not be looked up, with why, so they were not checked.
+    pub failures: BTreeMap<String, String>,
+}
+
+impl ExpiryReport {
+    /// Whether no certificate expires in the window.
+    pub fn is_empty(&self) -> bool {
+        self.certificates.is_empty()
+    }
+}
+
+/// Certificates attached to the fleet's things, one entry per thing and certificate.
+#[derive(Clone, Debug, Default)]
+pub struct Certificates {
+    /// Inner type.
+    inner: Vec<Certificate>,
+    /// Why the certificates of a thing could not be looked up, by thing name.
+    failures: BTreeMap<String, String>,
+}
+
+impl From<Vec<Certificate>> for Certificates {
+    fn from(inner: Vec<Certificate>) -> Self {
+        Self {
+            inner,
+            failures: BTreeMap::new(),
+        }
+    }
+}
+
+impl Certificates {
+    /// Records the things whose certificates could not be looked up.
+    pub(crate) fn with_failures(mut self, failures: BTreeMap<String, String>) -> Self {
+        self.failures = failures;
+        self
+    }
+
+    /// Returns the certificates.
+    pub fn as_slice(&self) -> &[Certificate] {
+        &self.inner
+    }
+
+    /// Why the certificates of a thing could not be looked up, by thing name.
+    pub fn failures(&self) -> &BTreeMap<String, String> {
+        &self.failures
+    }
+
+    /// Reports the active certificates expiring within `days` of `now`.
+    pub fn expiry_report(&self, days: i64, now: DateTime) -> ExpiryReport {
+        let mut certificates: Vec<ExpiringCertificate> = self
+            .inner
+            .iter()
+            .filter(|certificate| certificate.expires_within(days, now))
+            .filter_map(|certificate| {
+                Some(ExpiringCertificate {
+                    certificate: certificate.clone(),
+                    days_left: certificate.days_left(now)?,
+                })
+            })
+            .collect();
+        certificates.sort_by(|a, b| {
+            (a.days_left, &a.certificate.thing_name).cmp(&(b.days_left, &b.certificate.thing_name))
+        });
+
+        ExpiryReport {
+            generated_at: now.fmt(DateTimeFormat::DateTime).unwrap_or_default(),
+            within_days: days,
+            certificates,
+            failures: self.failures.clone(),
+        }
+    }
+}
+
+impl<'a> Property<'a> for Certificates {
+    fn items(&self) -> Vec<Vec<String>> {
+        let now = DateTime::from(std::time::SystemTime::now());
+        self.inner
+            .iter()
+            .map(|item| {
+                vec![
+                    item.thing_name.to_string(),
+                    item.status.to_string(),
+                    item.valid_to.to_string(),
+                    item.days_left(now)
+                        .map(|days| days.to_string())
+                        .unwrap_or_default(),
+                    item.creation_date.to_string(),
+                    item.certificate_id.to_string(),
+                ]
+            })
+            .collect()
+    }
+}
@@ -0,0 +1,109 @@
+use crate::Property;
+use serde::{Deserialize, Serialize};
+
+/// Greengrass component in the account's private catalog.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Component {
+    /// The ARN of the component, without a version.
+    pub arn: String,
+    /// The name of the component.
+    pub component_name: String,
+    /// The latest version of the component.
+    pub latest_version: String,
+    /// The publisher of the latest version.
+    pub publisher: String,
+    /// The description of the latest version.
+    pub description: String,
+    /// The time at which the latest version was created, expressed in ISO 8601 format.
+    pub creation_timestamp: String,
+}
+
+impl Component {
+    /// The ARN of the latest version, used to fetch its recipe.
+    pub fn latest_version_arn(&self) -> String {
+        format!("{}:versions:{}", self.arn, self.latest_version)
+    }
+}
+
+/// Greengrass components.
+#[derive(Clone, Debug, Default)]
+pub struct Components {
+    /// Inner type.
+    inner: Vec<Component>,
+}
+
+impl From<Vec<Component>> for Components {
+    fn from(inner: Vec<Component>) -> Self {
+        Self { inner }
+    }
+}
+
+impl Components {
+    /// Returns the components.
+    pub fn as_slice(&self) -> &[Component] {
+        &self.inner
+    }
+
+    /// Returns the component with the given ARN, if it is listed.
+    pub fn get(&self, arn: &str) -> Option<&Component> {
+        self.inner.iter().find(|component| component.arn == arn)
+    }
+}
+
+impl<'a> Property<'a> for Components {
+    fn items(&self) -> Vec<Vec<String>> {
+        self.inner
+            .iter()
+            .map(|item| {
+                vec![
+                    item.component_name.to_string(),
+                    item.latest_version.to_string(),
+                    item.publisher.to_string(),
+                    item.description.to_string(),
+                ]
+            })
+            .collect()
+    }
+}
+
+/// Published version of a Greengrass component.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ComponentVersion {
+    /// The name of the component.
+    pub component_name: String,
+    /// The version of the component.
+    pub component_version: String,
+    /// The ARN of the component version.
+    pub arn: String,
+}
+
+/// Versions of a Greengrass component.
+#[derive(Clone, Debug, Default)]
+pub struct ComponentVersions {
+    /// Inner type.
+    inner: Vec<ComponentVersion>,
+}
+
+impl From<Vec<ComponentVersion>> for ComponentVersions {
+    fn from(inner: Vec<ComponentVersion>) -> Self {
+        Self { inner }
+    }
+}
+
+impl ComponentVersions {
+    /// Returns the component versions.
+    pub fn as_slice(&self) -> &[ComponentVersion] {
+        &self.inner
+    }
+}
+
+impl<'a> Property<'a> for ComponentVersions {
+    fn items(&self) -> Vec<Vec<String>> {
+        self.inner
+            .iter()
+            .map(|item| vec![item.component_version.to_string(), item.arn.to_string()])
+            .collect()
+    }
+}
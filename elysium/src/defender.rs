@@ -0,0 +1,257 @@
+use std::{
+    collections::{BTreeMap, HashMap},
+    fmt,
+    str::FromStr,
+};
+
+use serde::{Deserialize, Serialize};
+
+use crate::{Certificate, Error, Property, Result};
+
+/// Severity of a Device Defender audit finding, lowest first.
+#[derive(
+    Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize,
+)]
+#[serde(rename_all = "UPPERCASE")]
+pub enum Severity {
+    #[default]
+    Low,
+    Medium,
+    High,
+    Critical,
+}
+
+impl fmt::Display for Severity {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Severity::Low => write!(f, "LOW"),
+            Severity::Medium => write!(f, "MEDIUM"),
+            Severity::High => write!(f, "HIGH"),
+            Severity::Critical => write!(f, "CRITICAL"),
+        }
+    }
+}
+
+impl FromStr for Severity {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s.to_ascii_uppercase().as_str() {
+            "LOW" => Ok(Severity::Low),
+            "MEDIUM" => Ok(Severity::Medium),
+            "HIGH" => Ok(Severity::High),
+            "CRITICAL" => Ok(Severity::Critical),
+            _ => Err(Error::InvalidRequest(format!("Unknown severity {s}"))),
+        }
+    }
+}
+
+/// Non-compliant resource found by a Device Defender audit.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct AuditFinding {
+    /// The ID of the finding.
+    pub finding_id: String,
+    /// The audit check that produced the finding, e.g. DEVICE_CERTIFICATE_EXPIRING_CHECK.
+    pub check_name: String,
+    /// How severe the finding is.
+    pub severity: Severity,
+    /// The time at which the finding was made, expressed in ISO 8601 format.
+    pub finding_time: String,
+    /// The type of the non-compliant resource, e.g. DEVICE_CERTIFICATE or CLIENT_ID.
+    pub resource_type: String,
+    /// The client ID of the non-compliant resource, if it is a client.
+    pub client_id: Option<String>,
+    /// The ID of the non-compliant device certificate, if it is one.
+    pub certificate_id: Option<String>,
+    /// Why the resource is not compliant.
+    pub reason: String,
+    /// Machine-readable code for the reason, e.g. CERTIFICATE_EXPIRING.
+    pub reason_code: String,
+}
+
+/// Behavior of a Device Defender security profile that a thing currently violates.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ActiveViolation {
+    /// The ID of the violation.
+    pub violation_id: String,
+    /// The name of the violating thing.
+    pub thing_name: String,
+    /// The security profile whose behavior is violated.
+    pub security_profile_name: String,
+    /// The name of the violated behavior.
+    pub behavior_name: String,
+    /// The metric the behavior watches, e.g. aws:num-messages-sent.
+    pub metric: Option<String>,
+    /// The value of the metric that last violated the behavior.
+    pub last_value: Option<String>,
+    /// How sure ML Detect is that this is a violation, e.g. HIGH. Rule-based behaviors have none.
+    pub confidence_level: Option<String>,
+    /// The time at which the violation started, expressed in ISO 8601 format.
+    pub violation_start_time: String,
+    /// The time at which the behavior was last violated, expressed in ISO 8601 format.
+    pub last_violation_time: String,
+}
+
+impl ActiveViolation {
+    /// Which behavior is violated and how, e.g. `MessagesSent (aws:num-messages-sent) = 9000`.
+    pub fn reason(&self) -> String {
+        let mut reason = self.behavior_name.clone();
+        if let Some(metric) = &self.metric {
+            reason.push_str(&format!(" ({metric})"));
+        }
+        if let Some(value) = &self.last_value {
+            reason.push_str(&format!(" = {value}"));
+        }
+        reason
+    }
+}
+
+/// Audit findings and active violations of a single thing.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct ThingSecurity {
+    /// The name of the thing.
+    pub thing_name: String,
+    /// Audit findings about the thing or its certificates, most severe first.
+    pub findings: Vec<AuditFinding>,
+    /// Behaviors the thing currently violates.
+    pub violations: Vec<ActiveViolation>,
+}
+
+impl ThingSecurity {
+    /// Whether there is nothing to report about the thing.
+    pub fn is_clean(&self) -> bool {
+        self.findings.is_empty() && self.violations.is_empty()
+    }
+
+    /// The severity of the worst audit finding, `None` without findings.
+    pub fn worst_severity(&self) -> Option<Severity> {
+        self.findings.iter().map(|finding| finding.severity).max()
+    }
+
+    /// Short summary for a table cell, e.g. `2 HIGH, 1 violation` or `OK`.
+    pub fn badge(&self) -> String {
+        let mut parts = Vec::new();
+        if let Some(severity) = self.worst_severity() {
+            parts.push(format!("{} {severity}", self.findings.len()));
+        }
+        match self.violations.len() {
+            0 => {}
+            1 => parts.push(String::from("1 violation")),
+            count => parts.push(format!("{count} violations")),
+        }
+        if parts.is_empty() { String::from("OK") } else { parts.join(", ") }
+    }
+}
+
+impl<'a> Property<'a> for ThingSecurity {
+    /// The audit findings followed by the active violations.
+    fn items(&self) -> Vec<Vec<String>> {
+        let findings = self.findings.iter().map(|item| {
+            vec![
+                item.severity.to_string(),
+                String::from("Audit"),
+                item.check_name.to_string(),
+                item.reason.to_string(),
+                item.finding_time.to_string(),
+            ]
+        });
+        let violations = self.violations.iter().map(|item| {
+            vec![
+                item.confidence_level.clone().unwrap_or_default(),
+                String::from("Violation"),
+                item.security_profile_name.to_string(),
+                item.reason(),
+                item.violation_start_time.to_string(),
+            ]
+        });
+        findings.chain(violations).collect()
+    }
+}
+
+/// Device Defender audit findings and active violations, grouped by thing.
+#[derive(Clone, Debug, Default)]
+pub struct Security {
+    /// Inner type, keyed by thing name.
+    inner: BTreeMap<String, ThingSecurity>,
+}
+
+impl Security {
+    /// Groups findings and violations by thing.
+    ///
+    /// Findings about a device certificate are attributed to every thing the certificate is
+    /// attached to, and other findings about a client ID to the thing of that name. Findings
+    /// about other resources, such as policies or the account, are left out.
+    pub fn group(
+        findings: Vec<AuditFinding>,
+        violations: Vec<ActiveViolation>,
+        certificates: &[Certificate],
+    ) -> Self {
+        let mut certificate_things: HashMap<&str, Vec<&str>> = HashMap::new();
+        for certificate in certificates {
+            certificate_things
+                .entry(certificate.certificate_id.as_str())
+                .or_default()
+                .push(certificate.thing_name.as_str());
+        }
+
+        let mut security = Self::default();
+        for finding in findings {
+            let mut things = finding
+                .certificate_id
+                .as_deref()
+                .and_then(|id| certificate_things.get(id))
+                .cloned()
+                .unwrap_or_default();
+            if things.is_empty() {
+                things.extend(finding.client_id.as_deref());
+            }
+            for thing_name in things {
+                security
+                    .thing_mut(thing_name)
+                    .findings
+                    .push(finding.clone());
+            }
+        }
+        for violation in violations {
+            let thing_name = violation.thing_name.clone();
+            security.thing_mut(&thing_name).violations.push(violation);
+        }
+
+        for thing in security.inner.values_mut() {
+            thing
+                .findings
+                .sort_by(|a, b| (b.severity, &b.finding_time).cmp(&(a.severity, &a.finding_time)));
+            thing
+                .violations
+                .sort_by(|a, b| b.last_violation_time.cmp(&a.last_violation_time));
+        }
+        security
+    }
+
+    fn thing_mut(&mut self, thing_name: &str) -> &mut ThingSecurity {
+        self.inner
+            .entry(thing_name.to_string())
+            .or_insert_with(|| ThingSecurity {
+                thing_name: thing_name.to_string(),
+                ..Default::default()
+            })
+    }
+
+    /// Returns the findings and violations of a thing, `None` if there are none.
+    pub fn get(&self, thing_name: &str) -> Option<&ThingSecurity> {
+        self.inner.get(thing_name)
+    }
+
+    /// Short summary of a thing's security for a table cell, `OK` if there is nothing to report.
+    pub fn badge(&self, thing_name: &str) -> String {
+        self.get(thing_name)
+            .map_or_else(|| String::from("OK"), ThingSecurity::badge)
+    }
+
+    /// Returns the things with findings or violations.
+    pub fn things(&self) -> impl Iterator<Item = &ThingSecurity> {
+        self.inner.values()
+    }
+}
@@ -0,0 +1,581 @@
+use std::{
+    collections::{BTreeMap, BTreeSet, HashMap},
+    fmt,
+    sync::Arc,
+};
+
+use crate::{
+    CloudBackend, ComponentDeploymentSpec, DependencyType, Recipe, RecipeFormat, Result, Version,
+    VersionRequirement,
+};
+
+/// Passes over the graph before giving up on the versions settling.
+const MAX_RESOLUTION_PASSES: usize = 8;
+
+/// Component in a resolved dependency graph.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ResolvedComponent {
+    /// The name of the component.
+    pub component_name: String,
+    /// The version that would be deployed, if one could be picked.
+    pub version: Option<String>,
+    /// Whether the deployment lists the component itself.
+    pub is_root: bool,
+    /// The component's own dependencies.
+    pub dependencies: Vec<DependencyEdge>,
+}
+
+/// Dependency of one component on another.
+#[derive(Clone, Debug, PartialEq)]
+pub struct DependencyEdge {
+    /// The name of the component depended on.
+    pub component_name: String,
+    /// The version range from the dependent's recipe.
+    pub version_requirement: String,
+    /// Whether the dependent restarts with the dependency.
+    pub dependency_type: DependencyType,
+}
+
+/// Version requirement placed on a component by one of its dependents.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Requirement {
+    /// The dependent, as `name@version`.
+    pub required_by: String,
+    /// The version range it requires.
+    pub version_requirement: String,
+}
+
+/// Problem that would make the deployment fail.
+#[derive(Clone, Debug, PartialEq)]
+pub enum DependencyIssue {
+    /// No version of the component satisfies every requirement on it.
+    Conflict {
+        component_name: String,
+        /// The version fixed by the deployment, if it lists the component itself.
+        fixed_version: Option<String>,
+        requirements: Vec<Requirement>,
+    },
+    /// Components that depend on each other, the first one repeated at the end.
+    Cycle(Vec<String>),
+    /// The component or its recipe could not be fetched or parsed.
+    Unresolved {
+        component_name: String,
+        message: String,
+    },
+    /// The versions picked for these components kept changing, so the graph shows the last
+    /// versions tried.
+    Unsettled(Vec<String>),
+}
+
+impl fmt::Display for DependencyIssue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DependencyIssue::Conflict {
+                component_name,
+                fixed_version,
+                requirements,
+            } => {
+                match fixed_version {
+                    Some(version) => write!(f, "{component_name} {version} does not satisfy ")?,
+                    None => write!(f, "No version of {component_name} satisfies ")?,
+                }
+                for (idx, requirement) in requirements.iter().enumerate() {
+                    if idx > 0 {
+                        write!(f, ", ")?;
+                    }
+                    write!(
+                        f,
+                        "{} from {}",
+                        requirement.version_requirement, requirement.required_by
+                    )?;
+                }
+                Ok(())
+            }
+            DependencyIssue::Cycle(path) => write!(f, "Dependency cycle {}", path.join(" -> ")),
+            DependencyIssue::Unresolved {
+                component_name,
+                message,
+            } => write!(f, "Could not resolve {component_name}: {message}"),
+            DependencyIssue::Unsettled(names) => write!(
+                f,
+                "Versions of {} did not settle after {MAX_RESOLUTION_PASSES} passes",
+                names.join(", ")
+            ),
+        }
+    }
+}
+
+/// Transitive dependencies of a deployment's components.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct DependencyGraph {
+    /// The components listed in the deployment.
+    pub roots: Vec<String>,
+    /// Every component reached from the roots, keyed by name.
+    pub components: BTreeMap<String, ResolvedComponent>,
+    /// Conflicts, cycles and components that could not be resolved.
+    pub issues: Vec<DependencyIssue>,
+}
+
+impl DependencyGraph {
+    /// Whether the deployment's dependencies resolve without issues.
+    pub fn is_ok(&self) -> bool {
+        self.issues.is_empty()
+    }
+}
+
+/// Fetches recipes and available versions while resolving, caching each lookup.
+struct Resolver<'a> {
+    backend: &'a dyn CloudBackend,
+    component_arn: &'a (dyn Fn(&str) -> String + Sync),
+    recipes: HashMap<(String, String), Result<Recipe, String>>,
+    versions: HashMap<String, Result<Vec<Version>, String>>,
+}
+
+impl Resolver<'_> {
+    async fn recipe(&mut self, name: &str, version: &str) -> Result<Recipe, String> {
+        let key = (name.to_string(), version.to_string());
+        if let Some(recipe) = self.recipes.get(&key) {
+            return recipe.clone();
+        }
+        let arn = format!("{}:versions:{version}", (self.component_arn)(name));
+        let recipe = match self.backend.get_component_recipe(&arn).await {
+            // Lint issues do not stop Greengrass from deploying a published recipe.
+            Ok(source) => {
+                Recipe::deserialize(&source, RecipeFormat::Yaml).map_err(|e| e.to_string())
+            }
+            Err(e) => Err(e.to_string()),
+        };
+        self.recipes.insert(key, recipe.clone());
+        recipe
+    }
+
+    /// Available versions of a component, newest first.
+    async fn versions(&mut self, name: &str) -> Result<Vec<Version>, String> {
+        if let Some(versions) = self.versions.get(name) {
+            return versions.clone();
+        }
+        let versions = match self
+            .backend
+            .list_component_versions(&(self.component_arn)(name))
+            .await
+        {
+            Ok(items) => {
+                let mut versions: Vec<Version> = items
+                    .iter()
+                    .filter_map(|item| item.component_version.parse().ok())
+                    .collect();
+                versions.sort_by(|a, b| b.cmp(a));
+                Ok(versions)
+            }
+            Err(e) => Err(e.to_string()),
+        };
+        self.versions.insert(name.to_string(), versions.clone());
+        versions
+    }
+}
+
+/// Dependency resolution of a deployment's components, see
+/// [`AwsCloud::dependency_resolution`](crate::AwsCloud::dependency_resolution).
+#[derive(Clone, Debug)]
+pub struct DependencyResolution {
+    backend: Arc<dyn CloudBackend>,
+    /// The ARN of the deployment target.
+    target_arn: String,
+    /// ARNs of the components in the loaded catalog, keyed by component name.
+    catalog: HashMap<String, String>,
+    /// The components listed in the deployment.
+    roots: BTreeMap<String, ComponentDeploymentSpec>,
+}
+
+impl DependencyResolution {
+    pub(crate) fn new(
+        backend: Arc<dyn CloudBackend>,
+        target_arn: &str,
+        catalog: HashMap<String, String>,
+        roots: BTreeMap<String, ComponentDeploymentSpec>,
+    ) -> Self {
+        Self {
+            backend,
+            target_arn: target_arn.to_string(),
+            catalog,
+            roots,
+        }
+    }
+
+    /// Fetches the recipes of every component reached and resolves their versions.
+    pub async fn resolve(&self) -> Result<DependencyGraph> {
+        let component_arn = |name: &str| self.component_arn(name);
+        resolve(self.backend.as_ref(), &component_arn, &self.roots).await
+    }
+
+    /// The ARN of a component, without a version.
+    ///
+    /// Components missing from the catalog are assumed to be in the target's account and
+    /// region, except `aws.greengrass` components, which AWS publishes.
+    fn component_arn(&self, component_name: &str) -> String {
+        if let Some(arn) = self.catalog.get(component_name) {
+            return arn.clone();
+        }
+        // arn:aws:iot:<region>:<account>:thinggroup/<name>
+        let mut parts = self.target_arn.split(':').skip(3);
+        let region = parts.next().unwrap_or_default();
+        let account = if component_name.starts_with("aws.greengrass.") {
+            "aws"
+        } else {
+            parts.next().unwrap_or_default()
+        };
+        format!("arn:aws:greengrass:{region}:{account}:components:{component_name}")
+    }
+}
+
+/// Resolves the dependencies of `roots`, which map component names to deployed versions.
+///
+/// Non-root dependencies get the newest version satisfying every requirement on them, as the
+/// nucleus picks them. Versions are re-picked until they settle because a newly picked version
+/// may bring its own requirements; if they do not, the last graph is returned with an
+/// [`DependencyIssue::Unsettled`] issue.
+pub(crate) async fn resolve(
+    backend: &dyn CloudBackend,
+    component_arn: &(dyn Fn(&str) -> String + Sync),
+    roots: &BTreeMap<String, ComponentDeploymentSpec>,
+) -> Result<DependencyGraph> {
+    let mut resolver = Resolver {
+        backend,
+        component_arn,
+        recipes: HashMap::new(),
+        versions: HashMap::new(),
+    };
+    let mut picked: BTreeMap<String, Option<String>> = BTreeMap::new();
+    let mut pass = 0;
+
+    loop {
+        let mut graph = resolve_pass(&mut resolver, roots, &picked).await?;
+        let next: BTreeMap<String, Option<String>> = graph
+            .components
+            .iter()
+            .filter(|(_, component)| !component.is_root)
+            .map(|(name, component)| (name.clone(), component.version.clone()))
+            .collect();
+        if next == picked {
+            return Ok(graph);
+        }
+        pass += 1;
+        if pass == MAX_RESOLUTION_PASSES {
+            let unsettled = next
+                .iter()
+                .filter(|(name, version)| picked.get(*name) != Some(version))
+                .map(|(name, _)| name.clone())
+                .collect();
+            graph.issues.push(DependencyIssue::Unsettled(unsettled));
+            return Ok(graph);
+        }
+        picked = next;
+    }
+}
+
+/// Walks the graph from the roots with the versions picked by the previous pass.
+async fn resolve_pass(
+    resolver: &mut Resolver<'_>,
+    roots: &BTreeMap<String, ComponentDeploymentSpec>,
+    picked: &BTreeMap<String, Option<String>>,
+) -> Result<DependencyGraph> {
+    let mut graph = DependencyGraph {
+        roots: roots.keys().cloned().collect(),
+        ..Default::default()
+    };
+    let mut requirements: BTreeMap<String, Vec<Requirement>> = BTreeMap::new();
+    let mut queue: Vec<(String, Option<String>, bool)> = roots
+        .iter()
+        .map(|(name, spec)| (name.clone(), Some(spec.component_version.clone()), true))
+        .collect();
+
+    while let Some((name, version, is_root)) = queue.pop() {
+        if graph.components.contains_key(&name) {
+            continue;
+        }
+        let mut component = ResolvedComponent {
+            component_name: name.clone(),
+            version: version.clone(),
+            is_root,
+            dependencies: vec![],
+        };
+        let Some(version) = version else {
+            graph.components.insert(name, component);
+            continue;
+        };
+
+        match resolver.recipe(&name, &version).await {
+            Ok(recipe) => {
+                for (dependency, spec) in &recipe.component_dependencies {
+                    component.dependencies.push(DependencyEdge {
+                        component_name: dependency.clone(),
+                        version_requirement: spec.version_requirement.clone(),
+                        dependency_type: spec.dependency_type,
+                    });
+                    requirements
+                        .entry(dependency.clone())
+                        .or_default()
+                        .push(Requirement {
+                            required_by: format!("{name}@{version}"),
+                            version_requirement: spec.version_requirement.clone(),
+                        });
+                    if !roots.contains_key(dependency) {
+                        let version = match picked.get(dependency) {
+                            Some(version) => version.clone(),
+                            None => {
+                                newest_matching(resolver, dependency, &requirements[dependency])
+                                    .await
+                            }
+                        };
+                        queue.push((dependency.clone(), version, false));
+                    }
+                }
+            }
+            Err(message) => graph.issues.push(DependencyIssue::Unresolved {
+                component_name: name.clone(),
+                message,
+            }),
+        }
+        graph.components.insert(name, component);
+    }
+
+    // Re-pick with every requirement now known, which the next pass compares against.
+    for (name, requirements) in &requirements {
+        let Some(component) = graph.components.get_mut(name) else {
+            continue;
+        };
+        if component.is_root {
+            let satisfied = component
+                .version
+                .as_deref()
+                .and_then(|version| version.parse::<Version>().ok())
+                .is_some_and(|version| satisfies_all(&version, requirements));
+            if !satisfied {
+                graph.issues.push(DependencyIssue::Conflict {
+                    component_name: name.clone(),
+                    fixed_version: component.version.clone(),
+                    requirements: requirements.clone(),
+                });
+            }
+            continue;
+        }
+        component.version = newest_matching(resolver, name, requirements).await;
+        if component.version.is_none() {
+            match resolver.versions(name).await {
+                Ok(_) => graph.issues.push(DependencyIssue::Conflict {
+                    component_name: name.clone(),
+                    fixed_version: None,
+                    requirements: requirements.clone(),
+                }),
+                Err(message) => graph.issues.push(DependencyIssue::Unresolved {
+                    component_name: name.clone(),
+                    message,
+                }),
+            }
+        }
+    }
+
+    graph
+        .issues
+        .extend(find_cycles(&graph).into_iter().map(DependencyIssue::Cycle));
+    Ok(graph)
+}
+
+async fn newest_matching(
+    resolver: &mut Resolver<'_>,
+    name: &str,
+    requirements: &[Requirement],
+) -> Option<String> {
+    resolver
+        .versions(name)
+        .await
+        .ok()?
+        .into_iter()
+        .find(|version| satisfies_all(version, requirements))
+        .map(|version| version.to_string())
+}
+
+/// Whether `version` satisfies every requirement. Unparseable requirements never match.
+fn satisfies_all(version: &Version, requirements: &[Requirement]) -> bool {
+    requirements.iter().all(|requirement| {
+        requirement
+            .version_requirement
+            .parse::<VersionRequirement>()
+            .is_ok_and(|range| range.matches(version))
+    })
+}
+
+/// Finds each dependency cycle once, starting from its alphabetically first component.
+fn find_cycles(graph: &DependencyGraph) -> Vec<Vec<String>> {
+    fn visit(
+        graph: &DependencyGraph,
+        name: &str,
+        path: &mut Vec<String>,
+        done: &mut BTreeSet<String>,
+        cycles: &mut Vec<Vec<String>>,
+    ) {
+        if let Some(start) = path.iter().position(|visited| visited == name) {
+            let mut cycle = path[start..].to_vec();
+            let first = cycle
+                .iter()
+                .enumerate()
+                .min_by_key(|(_, name)| *name)
+                .map_or(0, |(idx, _)| idx);
+            cycle.rotate_left(first);
+            cycle.push(cycle[0].clone());
+            if !cycles.contains(&cycle) {
+                cycles.push(cycle);
+            }
+            return;
+        }
+        if done.contains(name) {
+            return;
+        }
+        path.push(name.to_string());
+        if let Some(component) = graph.components.get(name) {
+            for dependency in &component.dependencies {
+                visit(graph, &dependency.component_name, path, done, cycles);
+            }
+        }
+        path.pop();
+        done.insert(name.to_string());
+    }
+
+    let mut cycles = Vec::new();
+    let mut done = BTreeSet::new();
+    for name in graph.components.keys() {
+        visit(graph, name, &mut Vec::new(), &mut done, &mut cycles);
+    }
+    cycles
+}
+
+#[cfg(test)]
+mod tests {
+    use futures::executor::block_on;
+
+    use super::*;
+    use crate::FixtureBackend;
+
+    const LONDON: &str = "arn:aws:iot:eu-west-1:123456789012:thinggroup/london";
+
+    /// Two components of the same account that depend on each other.
+    const CYCLE: &str = "
+component_versions:
+  com.example.Ping:
+    - component_name: com.example.Ping
+      component_version: 1.0.0
+  com.example.Pong:
+    - component_name: com.example.Pong
+      component_version: 1.0.0
+recipes:
+  arn:aws:greengrass:eu-west-1:123456789012:components:com.example.Ping:versions:1.0.0: |
+    RecipeFormatVersion: '2020-01-25'
+    ComponentName: com.example.Ping
+    ComponentVersion: '1.0.0'
+    ComponentDependencies:
+      com.example.Pong:
+        VersionRequirement: ^1.0.0
+  arn:aws:greengrass:eu-west-1:123456789012:components:com.example.Pong:versions:1.0.0: |
+    RecipeFormatVersion: '2020-01-25'
+    ComponentName: com.example.Pong
+    ComponentVersion: '1.0.0'
+    ComponentDependencies:
+      com.example.Ping:
+        VersionRequirement: ^1.0.0
+";
+
+    fn resolve(fixture: &str, roots: &[(&str, &str)]) -> DependencyGraph {
+        let backend = Arc::new(FixtureBackend::from_yaml(fixture).unwrap());
+        let roots = roots
+            .iter()
+            .map(|(name, version)| {
+                let spec = ComponentDeploymentSpec {
+                    component_version: version.to_string(),
+                    ..Default::default()
+                };
+                (name.to_string(), spec)
+            })
+            .collect();
+        let resolution = DependencyResolution::new(backend, LONDON, HashMap::new(), roots);
+        block_on(resolution.resolve()).unwrap()
+    }
+
+    fn fleet(roots: &[(&str, &str)]) -> DependencyGraph {
+        resolve(include_str!("../fixtures/fleet.yaml"), roots)
+    }
+
+    fn version<'a>(graph: &'a DependencyGraph, name: &str) -> Option<&'a str> {
+        graph.components[name].version.as_deref()
+    }
+
+    #[test]
+    fn picks_the_newest_version_satisfying_every_requirement() {
+        let graph = fleet(&[("com.example.SensorBridge", "1.4.2")]);
+        assert!(graph.is_ok(), "{:?}", graph.issues);
+        assert_eq!(graph.roots, ["com.example.SensorBridge"]);
+        assert_eq!(version(&graph, "aws.greengrass.Nucleus"), Some("2.14.0"));
+        assert_eq!(version(&graph, "com.example.LogForwarder"), Some("0.3.0"));
+        // 2.3.7 is the newest ^2.3.0, and its own requirement on the nucleus still holds.
+        assert_eq!(version(&graph, "aws.greengrass.LogManager"), Some("2.3.7"));
+        assert!(!graph.components["aws.greengrass.LogManager"].is_root);
+
+        let edges = &graph.components["com.example.SensorBridge"].dependencies;
+        assert_eq!(edges.len(), 2);
+        assert_eq!(edges[0].dependency_type, DependencyType::Soft);
+    }
+
+    #[test]
+    fn reports_roots_that_break_requirements() {
+        let graph = fleet(&[
+            ("aws.greengrass.LogManager", "2.3.5"),
+            ("aws.greengrass.Nucleus", "2.14.0"),
+        ]);
+        let messages: Vec<String> = graph.issues.iter().map(ToString::to_string).collect();
+        assert_eq!(
+            messages,
+            [
+                "aws.greengrass.Nucleus 2.14.0 does not satisfy >=2.0.0 <2.14.0 from aws.greengrass.LogManager@2.3.5"
+            ]
+        );
+    }
+
+    #[test]
+    fn reports_requirements_no_version_satisfies() {
+        let graph = fleet(&[("aws.greengrass.LogManager", "3.0.0")]);
+        assert_eq!(version(&graph, "aws.greengrass.Nucleus"), None);
+        assert_eq!(
+            graph.issues,
+            [DependencyIssue::Conflict {
+                component_name: String::from("aws.greengrass.Nucleus"),
+                fixed_version: None,
+                requirements: vec![Requirement {
+                    required_by: String::from("aws.greengrass.LogManager@3.0.0"),
+                    version_requirement: String::from(">=2.15.0"),
+                }],
+            }]
+        );
+    }
+
+    #[test]
+    fn reports_components_that_cannot_be_fetched() {
+        let graph = fleet(&[("com.example.Missing", "1.0.0")]);
+        assert!(matches!(
+            graph.issues.as_slice(),
+            [DependencyIssue::Unresolved { component_name, .. }] if component_name == "com.example.Missing"
+        ));
+    }
+
+    #[test]
+    fn reports_cycles_once() {
+        let graph = resolve(CYCLE, &[("com.example.Pong", "1.0.0")]);
+        assert_eq!(version(&graph, "com.example.Ping"), Some("1.0.0"));
+        assert_eq!(
+            graph.issues,
+            [DependencyIssue::Cycle(vec![
+                String::from("com.example.Ping"),
+                String::from("com.example.Pong"),
+                String::from("com.example.Ping"),
+            ])]
+        );
+    }
+}
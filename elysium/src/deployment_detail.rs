@@ -0,0 +1,207 @@
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Serialize};
+
+use crate::Deployment;
+
+/// Full description of a Greengrass deployment revision.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct DeploymentDetail {
+    /// The ID of the deployment.
+    pub deployment_id: String,
+    /// The name of the deployment.
+    pub deployment_name: String,
+    /// The ARN of the target IoT thing or thing group.
+    pub target_arn: String,
+    /// The revision number of the deployment.
+    pub revision_id: String,
+    /// The status of the deployment.
+    pub deployment_status: String,
+    /// The time at which the deployment was created, expressed in ISO 8601 format.
+    pub creation_timestamp: String,
+    /// Whether or not the deployment is the latest revision for its target.
+    pub is_latest_for_target: bool,
+    /// The parent deployment's target ARN within a subdeployment.
+    pub parent_target_arn: Option<String>,
+    /// The ID of the IoT job that applies the deployment to target devices.
+    pub iot_job_id: Option<String>,
+    /// The components to deploy, keyed by component name.
+    pub components: BTreeMap<String, ComponentDeploymentSpec>,
+    /// The deployment policies for the deployment.
+    pub deployment_policies: Option<DeploymentPolicies>,
+    /// The job configuration for the deployment.
+    pub iot_job_configuration: Option<IotJobConfiguration>,
+    /// The tags attached to the deployment.
+    pub tags: BTreeMap<String, String>,
+}
+
+impl DeploymentDetail {
+    /// Returns the specification needed to deploy this revision again.
+    pub fn spec(&self) -> DeploymentSpec {
+        DeploymentSpec {
+            target_arn: self.target_arn.clone(),
+            deployment_name: self.deployment_name.clone(),
+            parent_target_arn: self.parent_target_arn.clone(),
+            components: self.components.clone(),
+            deployment_policies: self.deployment_policies.clone(),
+            iot_job_configuration: self.iot_job_configuration.clone(),
+            tags: self.tags.clone(),
+        }
+    }
+}
+
+/// Everything needed to create a deployment.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct DeploymentSpec {
+    /// The ARN of the target IoT thing or thing group.
+    pub target_arn: String,
+    /// The name of the deployment.
+    pub deployment_name: String,
+    /// The parent deployment's target ARN within a subdeployment.
+    pub parent_target_arn: Option<String>,
+    /// The components to deploy, keyed by component name.
+    pub components: BTreeMap<String, ComponentDeploymentSpec>,
+    /// The deployment policies for the deployment.
+    pub deployment_policies: Option<DeploymentPolicies>,
+    /// The job configuration for the deployment.
+    pub iot_job_configuration: Option<IotJobConfiguration>,
+    /// The tags to attach to the deployment.
+    pub tags: BTreeMap<String, String>,
+}
+
+impl From<&DeploymentDetail> for Deployment {
+    fn from(detail: &DeploymentDetail) -> Self {
+        Self {
+            deployment_id: detail.deployment_id.clone(),
+            deployment_name: detail.deployment_name.clone(),
+            target_arn: detail.target_arn.clone(),
+            revision_id: detail.revision_id.clone(),
+            deployment_status: detail.deployment_status.clone(),
+            creation_timestamp: detail.creation_timestamp.clone(),
+            is_latest_for_target: detail.is_latest_for_target,
+            tags: detail.tags.clone(),
+        }
+    }
+}
+
+/// How a single component is deployed.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ComponentDeploymentSpec {
+    /// The version of the component.
+    pub component_version: String,
+    /// The configuration updates to apply to the component.
+    pub configuration_update: Option<ConfigurationUpdate>,
+    /// The system user and group to run the component as.
+    pub run_with: Option<RunWith>,
+}
+
+/// Configuration update applied to a component.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ConfigurationUpdate {
+    /// A serialized JSON string that contains the configuration object to merge.
+    pub merge: Option<String>,
+    /// JSON pointers to the configuration values to reset to their defaults.
+    pub reset: Vec<String>,
+}
+
+impl ConfigurationUpdate {
+    /// Returns the merge document pretty-printed, or as-is if it isn't valid JSON.
+    pub fn merge_pretty(&self) -> Option<String> {
+        let merge = self.merge.as_ref()?;
+        Some(
+            serde_json::from_str::<serde_json::Value>(merge)
+                .and_then(|value| serde_json::to_string_pretty(&value))
+                .unwrap_or_else(|_| merge.clone()),
+        )
+    }
+}
+
+/// System user, group and resource limits for a component.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct RunWith {
+    /// The POSIX system user and, optionally, group to use to run the component.
+    pub posix_user: Option<String>,
+    /// The Windows user to use to run the component.
+    pub windows_user: Option<String>,
+    /// The system resource limits to apply to the component's processes.
+    pub system_resource_limits: Option<SystemResourceLimits>,
+}
+
+/// Resource limits for a component's processes.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SystemResourceLimits {
+    /// The maximum amount of RAM, expressed in kilobytes.
+    pub memory: i64,
+    /// The maximum amount of CPU time, expressed in CPU cores.
+    pub cpus: f64,
+}
+
+/// Policies applied while rolling out a deployment.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct DeploymentPolicies {
+    /// ROLLBACK or DO_NOTHING when a deployment fails.
+    pub failure_handling_policy: Option<String>,
+    /// How components are notified before they are updated.
+    pub component_update_policy: Option<ComponentUpdatePolicy>,
+    /// How long components may take to validate configuration updates, in seconds.
+    pub configuration_validation_timeout_in_seconds: Option<i32>,
+}
+
+/// How components are notified before they are updated.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ComponentUpdatePolicy {
+    /// How long each component has to report it is safe to update, in seconds.
+    pub timeout_in_seconds: Option<i32>,
+    /// NOTIFY_COMPONENTS or SKIP_NOTIFY_COMPONENTS.
+    pub action: Option<String>,
+}
+
+/// IoT job settings used to roll out a deployment.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct IotJobConfiguration {
+    /// The maximum number of devices that receive the deployment per minute.
+    pub maximum_per_minute: Option<i32>,
+    /// Exponential rollout rate, if any.
+    pub exponential_rate: Option<ExponentialRolloutRate>,
+    /// Conditions that stop the rollout.
+    pub abort_criteria: Vec<AbortCriteria>,
+    /// How long a device can take to apply the deployment, in minutes.
+    pub in_progress_timeout_in_minutes: Option<i64>,
+}
+
+/// Exponential rollout rate for a deployment job.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ExponentialRolloutRate {
+    /// The minimum number of devices that receive the deployment per minute at the start.
+    pub base_rate_per_minute: i32,
+    /// The rate of increase for the rollout.
+    pub increment_factor: f64,
+    /// Number of notified devices that triggers a rate increase.
+    pub number_of_notified_things: Option<i32>,
+    /// Number of succeeded devices that triggers a rate increase.
+    pub number_of_succeeded_things: Option<i32>,
+}
+
+/// Condition that cancels a deployment job.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct AbortCriteria {
+    /// FAILED, REJECTED, TIMED_OUT or ALL.
+    pub failure_type: String,
+    /// The action to perform, CANCEL.
+    pub action: String,
+    /// The minimum percentage of failures that triggers the action.
+    pub threshold_percentage: f64,
+    /// The minimum number of devices that must receive the deployment first.
+    pub min_number_of_executed_things: i32,
+}
@@ -1,5 +1,78 @@
+use std::collections::BTreeMap;
+
 use crate::Property;
-use aws_sdk_greengrassv2::types::Deployment;
+use serde::{Deserialize, Serialize};
+
+/// Greengrass Deployment.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct Deployment {
+    /// The ID of the deployment.
+    pub deployment_id: String,
+    /// The name of the deployment.
+    pub deployment_name: String,
+    /// The ARN of the target IoT thing or thing group.
+    pub target_arn: String,
+    /// The revision number of the deployment.
+    #[serde(default)]
+    pub revision_id: String,
+    /// The status of the deployment.
+    pub deployment_status: String,
+    /// The time at which the deployment was created, expressed in ISO 8601 format.
+    pub creation_timestamp: String,
+    /// Whether or not the deployment is the latest revision for its target.
+    #[serde(default)]
+    pub is_latest_for_target: bool,
+    /// The tags attached to the deployment, such as its environment and owner.
+    #[serde(default)]
+    pub tags: BTreeMap<String, String>,
+}
+
+/// Which revisions of each deployment to return.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum DeploymentHistory {
+    /// Only the latest revision for each target.
+    #[default]
+    LatestOnly,
+    /// Every revision for each target.
+    All,
+}
+
+/// Filter for listing deployments.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct DeploymentQuery {
+    /// Only return deployments for this target ARN.
+    pub target_arn: Option<String>,
+    /// Which revisions to return.
+    pub history: DeploymentHistory,
+}
+
+impl DeploymentQuery {
+    /// Latest revision of every deployment.
+    pub fn latest() -> Self {
+        Self::default()
+    }
+
+    /// Every revision of the deployments for a single target.
+    pub fn history(target_arn: impl Into<String>) -> Self {
+        Self {
+            target_arn: Some(target_arn.into()),
+            history: DeploymentHistory::All,
+        }
+    }
+
+    /// Whether a deployment matches this query.
+    pub fn matches(&self, deployment: &Deployment) -> bool {
+        let target_matches = self
+            .target_arn
+            .as_ref()
+            .is_none_or(|target_arn| *target_arn == deployment.target_arn);
+        let history_matches = match self.history {
+            DeploymentHistory::LatestOnly => deployment.is_latest_for_target,
+            DeploymentHistory::All => true,
+        };
+        target_matches && history_matches
+    }
+}
 
 /// Greengrass Deployments.
 #[derive(Clone, Debug)]
@@ -14,15 +87,111 @@ impl From<Vec<Deployment>> for Deployments {
     }
 }
 
+impl Deployments {
+    /// Returns the deployments.
+    pub fn as_slice(&self) -> &[Deployment] {
+        &self.inner
+    }
+
+    /// Returns the deployment with the given ID, if it is listed.
+    pub fn get(&self, deployment_id: &str) -> Option<&Deployment> {
+        self.inner
+            .iter()
+            .find(|deployment| deployment.deployment_id == deployment_id)
+    }
+}
+
 impl<'a> Property<'a> for Deployments {
     fn items(&self) -> Vec<Vec<String>> {
         self.inner
             .iter()
             .map(|item| {
                 vec![
-                    item.deployment_name.as_ref().unwrap().to_string(),
-                    item.deployment_status.as_ref().unwrap().to_string(),
-                    item.creation_timestamp.as_ref().unwrap().to_string(),
+                    item.deployment_name.to_string(),
+                    item.deployment_status.to_string(),
+                    item.creation_timestamp.to_string(),
+                ]
+            })
+            .collect()
+    }
+}
+
+/// Deployment as applied to a single core device.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct EffectiveDeployment {
+    /// The ID of the deployment.
+    pub deployment_id: String,
+    /// The name of the deployment.
+    pub deployment_name: String,
+    /// The ARN of the target IoT thing or thing group.
+    pub target_arn: String,
+    /// The status of the deployment job on the core device, e.g. SUCCEEDED or FAILED.
+    pub core_device_execution_status: String,
+    /// The reason code for the execution status.
+    pub reason: String,
+    /// Error types reported by the core device for a failed deployment.
+    pub error_types: Vec<String>,
+    /// Error stack reported by the core device for a failed deployment.
+    pub error_stack: Vec<String>,
+    /// The time at which the deployment was created, expressed in ISO 8601 format.
+    pub creation_timestamp: String,
+    /// The time at which the deployment job was last modified, expressed in ISO 8601 format.
+    pub modified_timestamp: String,
+}
+
+impl EffectiveDeployment {
+    /// Whether the deployment failed on the core device.
+    pub fn is_failed(&self) -> bool {
+        matches!(
+            self.core_device_execution_status.as_str(),
+            "FAILED" | "TIMED_OUT" | "REJECTED"
+        )
+    }
+
+    /// Reason for the execution status followed by any reported error types.
+    pub fn failure_reason(&self) -> String {
+        if self.error_types.is_empty() {
+            self.reason.clone()
+        } else {
+            format!("{} ({})", self.reason, self.error_types.join(", "))
+        }
+    }
+}
+
+/// Deployments applied to a core device.
+#[derive(Clone, Debug, Default)]
+pub struct EffectiveDeployments {
+    /// Inner type.
+    inner: Vec<EffectiveDeployment>,
+}
+
+impl From<Vec<EffectiveDeployment>> for EffectiveDeployments {
+    fn from(inner: Vec<EffectiveDeployment>) -> Self {
+        Self { inner }
+    }
+}
+
+impl EffectiveDeployments {
+    /// Returns the effective deployments.
+    pub fn as_slice(&self) -> &[EffectiveDeployment] {
+        &self.inner
+    }
+}
+
+impl<'a> Property<'a> for EffectiveDeployments {
+    fn items(&self) -> Vec<Vec<String>> {
+        self.inner
+            .iter()
+            .map(|item| {
+                vec![
+                    item.deployment_name.to_string(),
+                    item.deployment_id.to_string(),
+                    item.target_arn.to_string(),
+                    item.core_device_execution_status.to_string(),
+                    item.creation_timestamp.to_string(),
+                    item.modified_timestamp.to_string(),
+                    item.failure_reason(),
                 ]
             })
             .collect()
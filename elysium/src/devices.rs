@@ -1,4 +1,32 @@
-use crate::Property;
+use std::collections::{BTreeMap, BTreeSet};
+
+use crate::{Property, Security};
+use serde::{Deserialize, Serialize};
+
+/// Greengrass core device as reported by the backend.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct CoreDevice {
+    /// Name of the IoT thing backing the core device.
+    pub thing_name: String,
+    /// Device status HEALTHY or UNHEALTHY.
+    pub status: String,
+    /// The time at which the core device's status last updated, expressed in ISO 8601 format.
+    pub last_status_update_timestamp: String,
+}
+
+/// IoT registry entry of a thing, as returned by `DescribeThing`.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Thing {
+    /// The name of the thing.
+    pub thing_name: String,
+    /// The thing type, if the thing has one.
+    pub thing_type_name: Option<String>,
+    /// The billing group the thing belongs to, if any.
+    pub billing_group_name: Option<String>,
+    /// Searchable attributes such as site, hardware revision or customer.
+    pub attributes: BTreeMap<String, String>,
+}
 
 /// Representation of a Device.
 #[derive(Clone, Debug, Default)]
@@ -7,10 +35,20 @@ pub struct Device {
     pub name: String,
     /// Whether the IoT Core things is currently connected.
     pub is_connected: bool,
+    /// Why the connectivity lookup failed, if it did.
+    pub connectivity_error: Option<String>,
     /// Device status HEALTHY or UNHEALTHY.
     pub status: String,
     /// The time at which the core device's status last updated, expressed in ISO 8601 format.
     pub last_status_update_timestamp: String,
+    /// The thing type, if the thing has one.
+    pub thing_type: Option<String>,
+    /// The billing group the thing belongs to, if any.
+    pub billing_group: Option<String>,
+    /// Attributes of the thing in the IoT registry.
+    pub attributes: BTreeMap<String, String>,
+    /// The tags attached to the core device, such as its environment and owner.
+    pub tags: BTreeMap<String, String>,
 }
 
 /// Greengrass Core devices wrapper.
@@ -26,6 +64,41 @@ impl From<Vec<Device>> for Devices {
     }
 }
 
+impl Devices {
+    /// Returns the devices.
+    pub fn as_slice(&self) -> &[Device] {
+        &self.inner
+    }
+
+    /// Names of the attributes set on any of the devices, sorted.
+    pub fn attribute_names(&self) -> Vec<String> {
+        self.inner
+            .iter()
+            .flat_map(|device| device.attributes.keys().cloned())
+            .collect::<BTreeSet<_>>()
+            .into_iter()
+            .collect()
+    }
+
+    /// Returns the items with a security badge after the status, followed by the value of each
+    /// of the given attributes.
+    pub fn items_with(&self, security: &Security, attributes: &[String]) -> Vec<Vec<String>> {
+        self.items()
+            .into_iter()
+            .zip(&self.inner)
+            .map(|(mut row, device)| {
+                row.insert(2, security.badge(&device.name));
+                row.extend(
+                    attributes
+                        .iter()
+                        .map(|name| device.attributes.get(name).cloned().unwrap_or_default()),
+                );
+                row
+            })
+            .collect()
+    }
+}
+
 impl<'a> Property<'a> for Devices {
     fn items(&self) -> Vec<Vec<String>> {
         self.inner
@@ -35,6 +108,8 @@ impl<'a> Property<'a> for Devices {
                     item.name.to_string(),
                     item.status.to_string(),
                     item.last_status_update_timestamp.to_string(),
+                    item.thing_type.clone().unwrap_or_default(),
+                    item.billing_group.clone().unwrap_or_default(),
                 ]
             })
             .collect()
@@ -0,0 +1,533 @@
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    fmt,
+};
+
+use serde::Serialize;
+use serde_json::Value;
+
+use crate::{
+    ComponentDeploymentSpec, ConfigurationUpdate, DeploymentDetail, DeploymentPolicies, Error,
+    IotJobConfiguration, Result,
+};
+
+/// Change to a single component between two deployments.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ComponentDiff {
+    /// The component is only in the new deployment.
+    Added {
+        /// The name of the component.
+        name: String,
+        /// The version of the component.
+        version: String,
+    },
+    /// The component is only in the old deployment.
+    Removed {
+        /// The name of the component.
+        name: String,
+        /// The version of the component.
+        version: String,
+    },
+    /// The component is in both deployments with different versions.
+    VersionChanged {
+        /// The name of the component.
+        name: String,
+        /// The version in the old deployment.
+        from: String,
+        /// The version in the new deployment.
+        to: String,
+    },
+}
+
+impl fmt::Display for ComponentDiff {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ComponentDiff::Added { name, version } => write!(f, "+ {name} {version}"),
+            ComponentDiff::Removed { name, version } => write!(f, "- {name} {version}"),
+            ComponentDiff::VersionChanged { name, from, to } => {
+                write!(f, "~ {name} {from} -> {to}")
+            }
+        }
+    }
+}
+
+/// Compares the component versions of two deployments, ordered by component name.
+pub fn diff_components(
+    old: &BTreeMap<String, ComponentDeploymentSpec>,
+    new: &BTreeMap<String, ComponentDeploymentSpec>,
+) -> Vec<ComponentDiff> {
+    let mut diffs = Vec::new();
+
+    for (name, old_spec) in old {
+        match new.get(name) {
+            None => diffs.push(ComponentDiff::Removed {
+                name: name.clone(),
+                version: old_spec.component_version.clone(),
+            }),
+            Some(new_spec) if new_spec.component_version != old_spec.component_version => diffs
+                .push(ComponentDiff::VersionChanged {
+                    name: name.clone(),
+                    from: old_spec.component_version.clone(),
+                    to: new_spec.component_version.clone(),
+                }),
+            Some(_) => {}
+        }
+    }
+    for (name, new_spec) in new {
+        if !old.contains_key(name) {
+            diffs.push(ComponentDiff::Added {
+                name: name.clone(),
+                version: new_spec.component_version.clone(),
+            });
+        }
+    }
+
+    diffs.sort_by(|a, b| a.name().cmp(b.name()));
+    diffs
+}
+
+impl ComponentDiff {
+    /// The name of the changed component.
+    pub fn name(&self) -> &str {
+        match self {
+            ComponentDiff::Added { name, .. }
+            | ComponentDiff::Removed { name, .. }
+            | ComponentDiff::VersionChanged { name, .. } => name,
+        }
+    }
+}
+
+/// Change to a single value between two JSON documents.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ValueDiff {
+    /// JSON pointer to the value, e.g. `/sensors/0`. Empty for the whole document.
+    pub path: String,
+    /// The old value, or `None` if it was added.
+    pub old: Option<Value>,
+    /// The new value, or `None` if it was removed.
+    pub new: Option<Value>,
+}
+
+impl fmt::Display for ValueDiff {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let path = if self.path.is_empty() { "/" } else { &self.path };
+        match (&self.old, &self.new) {
+            (None, Some(new)) => write!(f, "+ {path}: {new}"),
+            (Some(old), None) => write!(f, "- {path}: {old}"),
+            (Some(old), Some(new)) => write!(f, "~ {path}: {old} -> {new}"),
+            (None, None) => write!(f, "  {path}"),
+        }
+    }
+}
+
+/// Compares two JSON documents value by value, ordered by path.
+///
+/// Objects are compared key by key and arrays index by index, so only the leaves that changed
+/// are reported. `null` is treated the same as a missing value.
+pub fn diff_json(old: &Value, new: &Value) -> Vec<ValueDiff> {
+    let mut diffs = Vec::new();
+    diff_values(String::new(), present(old), present(new), &mut diffs);
+    diffs
+}
+
+fn present(value: &Value) -> Option<&Value> {
+    (!value.is_null()).then_some(value)
+}
+
+fn diff_values(path: String, old: Option<&Value>, new: Option<&Value>, diffs: &mut Vec<ValueDiff>) {
+    match (old, new) {
+        (None, None) => {}
+        (Some(old), Some(new)) if old == new => {}
+        (Some(Value::Object(old)), Some(Value::Object(new))) => {
+            let keys: BTreeSet<&String> = old.keys().chain(new.keys()).collect();
+            for key in keys {
+                diff_values(
+                    child_path(&path, key),
+                    old.get(key).and_then(present),
+                    new.get(key).and_then(present),
+                    diffs,
+                );
+            }
+        }
+        (Some(Value::Array(old)), Some(Value::Array(new))) => {
+            for idx in 0..old.len().max(new.len()) {
+                diff_values(
+                    child_path(&path, &idx.to_string()),
+                    old.get(idx).and_then(present),
+                    new.get(idx).and_then(present),
+                    diffs,
+                );
+            }
+        }
+        // Spell out added and removed containers leaf by leaf, like the ones that changed.
+        (Some(value), None) | (None, Some(value)) if !children(value).is_empty() => {
+            let removed = old.is_some();
+            for (key, child) in children(value) {
+                let child = present(child);
+                let (old, new) = if removed { (child, None) } else { (None, child) };
+                diff_values(child_path(&path, &key), old, new, diffs);
+            }
+        }
+        (old, new) => diffs.push(ValueDiff {
+            path,
+            old: old.cloned(),
+            new: new.cloned(),
+        }),
+    }
+}
+
+fn children(value: &Value) -> Vec<(String, &Value)> {
+    match value {
+        Value::Object(map) => map
+            .iter()
+            .map(|(key, child)| (key.clone(), child))
+            .collect(),
+        Value::Array(items) => items
+            .iter()
+            .enumerate()
+            .map(|(idx, child)| (idx.to_string(), child))
+            .collect(),
+        _ => Vec::new(),
+    }
+}
+
+/// Appends a reference token to a JSON pointer, escaping `~` and `/` as RFC 6901 requires.
+fn child_path(path: &str, key: &str) -> String {
+    format!("{path}/{}", key.replace('~', "~0").replace('/', "~1"))
+}
+
+/// Change to the configuration update of a single component between two deployments.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct ConfigurationDiff {
+    /// The name of the component.
+    pub component_name: String,
+    /// Changes to the merge document, with paths into the document.
+    pub merge: Vec<ValueDiff>,
+    /// Paths that are only reset in the new deployment.
+    pub reset_added: Vec<String>,
+    /// Paths that are only reset in the old deployment.
+    pub reset_removed: Vec<String>,
+}
+
+impl ConfigurationDiff {
+    /// Whether the configuration update is the same in both deployments.
+    pub fn is_empty(&self) -> bool {
+        self.merge.is_empty() && self.reset_added.is_empty() && self.reset_removed.is_empty()
+    }
+}
+
+/// Compares the configuration updates of the components in two deployments, ordered by
+/// component name. Components without any change are left out.
+pub fn diff_configuration(
+    old: &BTreeMap<String, ComponentDeploymentSpec>,
+    new: &BTreeMap<String, ComponentDeploymentSpec>,
+) -> Vec<ConfigurationDiff> {
+    let names: BTreeSet<&String> = old.keys().chain(new.keys()).collect();
+    names
+        .into_iter()
+        .map(|name| {
+            let old = old
+                .get(name)
+                .and_then(|spec| spec.configuration_update.as_ref());
+            let new = new
+                .get(name)
+                .and_then(|spec| spec.configuration_update.as_ref());
+            let merge = |update: Option<&ConfigurationUpdate>| {
+                update
+                    .and_then(|update| update.merge.as_deref())
+                    // A merge document that isn't valid JSON is compared as a whole.
+                    .map(|merge| {
+                        serde_json::from_str(merge)
+                            .unwrap_or_else(|_| Value::String(merge.to_string()))
+                    })
+                    .unwrap_or(Value::Null)
+            };
+            let reset = |update: Option<&ConfigurationUpdate>| -> BTreeSet<String> {
+                update
+                    .map(|update| update.reset.iter().cloned().collect())
+                    .unwrap_or_default()
+            };
+            let (old_reset, new_reset) = (reset(old), reset(new));
+
+            ConfigurationDiff {
+                component_name: name.clone(),
+                merge: diff_json(&merge(old), &merge(new)),
+                reset_added: new_reset.difference(&old_reset).cloned().collect(),
+                reset_removed: old_reset.difference(&new_reset).cloned().collect(),
+            }
+        })
+        .filter(|diff| !diff.is_empty())
+        .collect()
+}
+
+/// Change to the system user and resource limits of a single component between two
+/// deployments.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct RunWithDiff {
+    /// The name of the component.
+    pub component_name: String,
+    /// Changes to the run-with settings, with paths such as `/posix_user`.
+    pub changes: Vec<ValueDiff>,
+}
+
+/// Compares the run-with settings of the components in two deployments, ordered by component
+/// name. Components without any change are left out.
+pub fn diff_run_with(
+    old: &BTreeMap<String, ComponentDeploymentSpec>,
+    new: &BTreeMap<String, ComponentDeploymentSpec>,
+) -> Vec<RunWithDiff> {
+    let names: BTreeSet<&String> = old.keys().chain(new.keys()).collect();
+    names
+        .into_iter()
+        .map(|name| RunWithDiff {
+            component_name: name.clone(),
+            changes: diff_serialized(
+                old.get(name).and_then(|spec| spec.run_with.as_ref()),
+                new.get(name).and_then(|spec| spec.run_with.as_ref()),
+            ),
+        })
+        .filter(|diff| !diff.changes.is_empty())
+        .collect()
+}
+
+/// Compares the deployment policies of two deployments, with paths such as
+/// `/component_update_policy/action`.
+pub fn diff_policies(
+    old: Option<&DeploymentPolicies>,
+    new: Option<&DeploymentPolicies>,
+) -> Vec<ValueDiff> {
+    diff_serialized(old, new)
+}
+
+/// Compares the IoT job configuration of two deployments, with paths such as
+/// `/exponential_rate/base_rate_per_minute`.
+pub fn diff_job_configuration(
+    old: Option<&IotJobConfiguration>,
+    new: Option<&IotJobConfiguration>,
+) -> Vec<ValueDiff> {
+    diff_serialized(old, new)
+}
+
+/// Compares two settings by their JSON form, a missing setting being `null`.
+fn diff_serialized<T: Serialize>(old: Option<&T>, new: Option<&T>) -> Vec<ValueDiff> {
+    let value = |settings: Option<&T>| {
+        settings
+            .and_then(|settings| serde_json::to_value(settings).ok())
+            .unwrap_or(Value::Null)
+    };
+    diff_json(&value(old), &value(new))
+}
+
+/// Everything that changed between two revisions of the deployment for a target.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct RevisionDiff {
+    /// The ARN of the target both revisions deploy to.
+    pub target_arn: String,
+    /// The older revision's ID.
+    pub old_revision: String,
+    /// The newer revision's ID.
+    pub new_revision: String,
+    /// Components added, removed or deployed at another version.
+    pub components: Vec<ComponentDiff>,
+    /// Changes to the components' configuration updates.
+    pub configuration: Vec<ConfigurationDiff>,
+    /// Changes to the components' system users and resource limits.
+    pub run_with: Vec<RunWithDiff>,
+    /// Changes to the deployment policies.
+    pub deployment_policies: Vec<ValueDiff>,
+    /// Changes to the IoT job configuration used to roll out the deployment.
+    pub iot_job_configuration: Vec<ValueDiff>,
+}
+
+impl RevisionDiff {
+    /// Compares two revisions of the deployment for the same target.
+    pub fn new(old: &DeploymentDetail, new: &DeploymentDetail) -> Result<Self> {
+        if old.target_arn != new.target_arn {
+            return Err(Error::InvalidRequest(format!(
+                "Revisions target {} and {}, not the same target",
+                old.target_arn, new.target_arn
+            )));
+        }
+        Ok(Self {
+            target_arn: new.target_arn.clone(),
+            old_revision: old.revision_id.clone(),
+            new_revision: new.revision_id.clone(),
+            components: diff_components(&old.components, &new.components),
+            configuration: diff_configuration(&old.components, &new.components),
+            run_with: diff_run_with(&old.components, &new.components),
+            deployment_policies: diff_policies(
+                old.deployment_policies.as_ref(),
+                new.deployment_policies.as_ref(),
+            ),
+            iot_job_configuration: diff_job_configuration(
+                old.iot_job_configuration.as_ref(),
+                new.iot_job_configuration.as_ref(),
+            ),
+        })
+    }
+
+    /// Whether both revisions deploy the same components, configuration, run-with settings,
+    /// policies and job configuration.
+    pub fn is_empty(&self) -> bool {
+        self.components.is_empty()
+            && self.configuration.is_empty()
+            && self.run_with.is_empty()
+            && self.deployment_policies.is_empty()
+            && self.iot_job_configuration.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+    use crate::{ComponentUpdatePolicy, ExponentialRolloutRate, RunWith};
+
+    fn spec(version: &str) -> ComponentDeploymentSpec {
+        ComponentDeploymentSpec {
+            component_version: version.to_string(),
+            ..Default::default()
+        }
+    }
+
+    fn revision(
+        revision_id: &str,
+        components: &[(&str, ComponentDeploymentSpec)],
+    ) -> DeploymentDetail {
+        DeploymentDetail {
+            target_arn: String::from("arn:aws:iot:eu-west-1:123456789012:thinggroup/london"),
+            revision_id: revision_id.to_string(),
+            components: components
+                .iter()
+                .map(|(name, spec)| (name.to_string(), spec.clone()))
+                .collect(),
+            ..Default::default()
+        }
+    }
+
+    fn paths(diffs: &[ValueDiff]) -> Vec<&str> {
+        diffs.iter().map(|diff| diff.path.as_str()).collect()
+    }
+
+    #[test]
+    fn diffs_json_leaf_by_leaf() {
+        let old =
+            json!({ "rate": 5, "sensors": ["a", "b"], "a/b": 1, "gone": { "x": 1 }, "none": null });
+        let new = json!({ "rate": 10, "sensors": ["a"], "a/b": 2, "added": { "y": [true] } });
+        let diffs = diff_json(&old, &new);
+        assert_eq!(
+            paths(&diffs),
+            ["/a~1b", "/added/y/0", "/gone/x", "/rate", "/sensors/1"]
+        );
+        assert_eq!(diffs[1].to_string(), "+ /added/y/0: true");
+        assert_eq!(diffs[2].to_string(), "- /gone/x: 1");
+        assert_eq!(diffs[3].to_string(), "~ /rate: 5 -> 10");
+
+        assert!(diff_json(&json!({ "a": null }), &json!({})).is_empty());
+        assert_eq!(
+            diff_json(&json!(1), &json!("1"))[0].to_string(),
+            "~ /: 1 -> \"1\""
+        );
+    }
+
+    #[test]
+    fn diffs_components_and_configuration() {
+        let mut forwarder = spec("0.3.0");
+        forwarder.configuration_update = Some(ConfigurationUpdate {
+            merge: Some(String::from(r#"{"level":"info"}"#)),
+            reset: vec![String::from("/buffer")],
+        });
+        let old = revision(
+            "1",
+            &[
+                ("com.example.LogForwarder", spec("0.2.0")),
+                ("aws.greengrass.Nucleus", spec("2.13.0")),
+            ],
+        );
+        let new = revision(
+            "2",
+            &[
+                ("com.example.LogForwarder", forwarder),
+                ("com.example.SensorBridge", spec("1.4.2")),
+            ],
+        );
+
+        let diff = RevisionDiff::new(&old, &new).unwrap();
+        let components: Vec<String> = diff.components.iter().map(ToString::to_string).collect();
+        assert_eq!(
+            components,
+            [
+                "- aws.greengrass.Nucleus 2.13.0",
+                "~ com.example.LogForwarder 0.2.0 -> 0.3.0",
+                "+ com.example.SensorBridge 1.4.2",
+            ]
+        );
+        assert_eq!(diff.configuration.len(), 1);
+        assert_eq!(
+            diff.configuration[0].component_name,
+            "com.example.LogForwarder"
+        );
+        assert_eq!(paths(&diff.configuration[0].merge), ["/level"]);
+        assert_eq!(diff.configuration[0].reset_added, ["/buffer"]);
+        assert!(!diff.is_empty());
+
+        assert!(RevisionDiff::new(&old, &old).unwrap().is_empty());
+    }
+
+    #[test]
+    fn diffs_run_with_policies_and_job_configuration() {
+        let mut bridge = spec("1.4.2");
+        bridge.run_with = Some(RunWith {
+            posix_user: Some(String::from("ggc_user:ggc_group")),
+            ..Default::default()
+        });
+        let mut old = revision("1", &[("com.example.SensorBridge", spec("1.4.2"))]);
+        let mut new = revision("2", &[("com.example.SensorBridge", bridge)]);
+        new.deployment_policies = Some(DeploymentPolicies {
+            component_update_policy: Some(ComponentUpdatePolicy {
+                action: Some(String::from("SKIP_NOTIFY_COMPONENTS")),
+                ..Default::default()
+            }),
+            ..Default::default()
+        });
+        old.iot_job_configuration = Some(IotJobConfiguration {
+            exponential_rate: Some(ExponentialRolloutRate {
+                base_rate_per_minute: 5,
+                increment_factor: 2.0,
+                ..Default::default()
+            }),
+            ..Default::default()
+        });
+        new.iot_job_configuration = Some(IotJobConfiguration {
+            exponential_rate: Some(ExponentialRolloutRate {
+                base_rate_per_minute: 10,
+                increment_factor: 2.0,
+                ..Default::default()
+            }),
+            ..Default::default()
+        });
+
+        let diff = RevisionDiff::new(&old, &new).unwrap();
+        assert!(diff.components.is_empty());
+        assert_eq!(diff.run_with.len(), 1);
+        assert_eq!(paths(&diff.run_with[0].changes), ["/posix_user"]);
+        assert_eq!(
+            paths(&diff.deployment_policies),
+            ["/component_update_policy/action"]
+        );
+        assert_eq!(
+            paths(&diff.iot_job_configuration),
+            ["/exponential_rate/base_rate_per_minute"]
+        );
+        assert!(!diff.is_empty());
+    }
+
+    #[test]
+    fn rejects_revisions_of_other_targets() {
+        let old = revision("1", &[]);
+        let mut new = revision("2", &[]);
+        new.target_arn = String::from("arn:aws:iot:eu-west-1:123456789012:thinggroup/manchester");
+        assert!(RevisionDiff::new(&old, &new).is_err());
+    }
+}
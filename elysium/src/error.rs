@@ -0,0 +1,285 @@
+use std::{error::Error as StdError, fmt};
+
+use aws_sdk_greengrassv2::error::{ProvideErrorMetadata, SdkError};
+
+use crate::RecipeIssue;
+
+/// Result type used throughout the library.
+pub type Result<T, E = Error> = std::result::Result<T, E>;
+
+/// Errors returned while constructing [`AwsCloud`](crate::AwsCloud) or loading data.
+#[derive(Debug, thiserror::Error)]
+pub enum Error {
+    /// The credentials for the profile have expired and need refreshing, e.g. with `aws sso login`.
+    #[error("AWS credentials have expired")]
+    ExpiredCredentials,
+    /// The named profile does not exist in the local AWS config.
+    #[error("AWS profile `{0}` was not found")]
+    MissingProfile(String),
+    /// The caller is not allowed to perform the IAM action.
+    #[error("Access denied for {action}: {message}")]
+    AccessDenied {
+        /// IAM action that was denied, e.g. `greengrass:ListCoreDevices`.
+        action: String,
+        /// Message returned by the service.
+        message: String,
+    },
+    /// The request was throttled by the service.
+    #[error("Request throttled while calling {0}")]
+    Throttling(String),
+    /// The account has not enabled the opt-in region.
+    #[error("Region `{0}` is not enabled for this account")]
+    RegionNotEnabled(String),
+    /// The service did not recognize the credentials, usually because the access keys are invalid.
+    #[error("AWS credentials were not recognized in region `{0}`")]
+    InvalidCredentials(String),
+    /// Some of the resources failed to load, the others are still available.
+    #[error("Failed to load {}", LoadFailures(.0))]
+    PartialLoad(Vec<LoadFailure>),
+    /// The request was rejected before reaching the service.
+    #[error("Invalid request: {0}")]
+    InvalidRequest(String),
+    /// A component recipe failed to parse or validate.
+    #[error("Invalid recipe: {}", RecipeIssues(.0))]
+    InvalidRecipe(Vec<RecipeIssue>),
+    /// The requested resource does not exist.
+    #[error("Not found: {0}")]
+    NotFound(String),
+    /// An optional service, such as fleet indexing or Device Defender, is not enabled for the account.
+    #[error("{0} is not enabled for this account")]
+    NotEnabled(String),
+    /// The service could not be reached.
+    #[error("Network error: {0}")]
+    Network(String),
+    /// A fixture could not be read or parsed.
+    #[error("Fixture error: {0}")]
+    Fixture(String),
+    /// A thing list or resume file could not be read or written.
+    #[error("File error: {0}")]
+    File(String),
+    /// Any other error returned by the service.
+    #[error("{code}: {message}")]
+    Service {
+        /// Error code returned by the service.
+        code: String,
+        /// Message returned by the service.
+        message: String,
+    },
+}
+
+impl Error {
+    /// Whether the error means an optional service, such as fleet indexing or Device Defender,
+    /// is not enabled for the account.
+    pub fn is_not_enabled(&self) -> bool {
+        matches!(self, Error::NotEnabled(_))
+    }
+}
+
+/// Resource loaded by [`AwsCloud::load`](crate::AwsCloud::load).
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Resource {
+    /// Greengrass core devices.
+    Devices,
+    /// IoT thing groups.
+    ThingGroups,
+    /// Greengrass deployments.
+    Deployments,
+    /// Greengrass components.
+    Components,
+    /// IoT jobs.
+    Jobs,
+    /// Certificates attached to the core devices.
+    Certificates,
+    /// Device Defender audit findings and active violations.
+    Security,
+    /// Fleet-indexing aggregations over all things.
+    FleetSummary,
+}
+
+impl fmt::Display for Resource {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Resource::Devices => write!(f, "core devices"),
+            Resource::ThingGroups => write!(f, "thing groups"),
+            Resource::Deployments => write!(f, "deployments"),
+            Resource::Components => write!(f, "components"),
+            Resource::Jobs => write!(f, "jobs"),
+            Resource::Certificates => write!(f, "certificates"),
+            Resource::Security => write!(f, "security findings"),
+            Resource::FleetSummary => write!(f, "fleet summary"),
+        }
+    }
+}
+
+/// Failure to load a single resource.
+#[derive(Debug)]
+pub struct LoadFailure {
+    /// The resource that failed to load.
+    pub resource: Resource,
+    /// Why it failed.
+    pub error: Error,
+}
+
+struct RecipeIssues<'a>(&'a [RecipeIssue]);
+
+impl fmt::Display for RecipeIssues<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (idx, issue) in self.0.iter().enumerate() {
+            if idx > 0 {
+                write!(f, "; ")?;
+            }
+            write!(f, "{issue}")?;
+        }
+        Ok(())
+    }
+}
+
+struct LoadFailures<'a>(&'a [LoadFailure]);
+
+impl fmt::Display for LoadFailures<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (idx, failure) in self.0.iter().enumerate() {
+            if idx > 0 {
+                write!(f, ", ")?;
+            }
+            write!(f, "{} ({})", failure.resource, failure.error)?;
+        }
+        Ok(())
+    }
+}
+
+impl Error {
+    /// Maps an SDK error into a typed error.
+    ///
+    /// `action` is the IAM action of the failed call and is used for [`Error::AccessDenied`]
+    /// and [`Error::Throttling`] when the service does not name it.
+    pub(crate) fn from_sdk<E, R>(
+        err: SdkError<E, R>,
+        action: &str,
+        profile: &str,
+        region: &str,
+    ) -> Self
+    where
+        E: ProvideErrorMetadata + StdError + 'static,
+        R: fmt::Debug + 'static,
+    {
+        match &err {
+            SdkError::ServiceError(context) => {
+                let service_err = context.err();
+                let code = service_err.code().unwrap_or_default();
+                let message = service_err.message().unwrap_or_default().to_string();
+                match code {
+                    "ExpiredTokenException" | "ExpiredToken" | "RequestExpired" => {
+                        Error::ExpiredCredentials
+                    }
+                    "UnrecognizedClientException" | "InvalidClientTokenId" => {
+                        rejected_credentials(region)
+                    }
+                    "AccessDeniedException" | "AccessDenied" | "UnauthorizedException" => {
+                        Error::AccessDenied {
+                            action: denied_action(&message).unwrap_or(action).to_string(),
+                            message,
+                        }
+                    }
+                    "ThrottlingException" | "Throttling" | "TooManyRequestsException" => {
+                        Error::Throttling(action.to_string())
+                    }
+                    "ResourceNotFoundException" => Error::NotFound(message),
+                    _ => Error::Service {
+                        code: code.to_string(),
+                        message,
+                    },
+                }
+            }
+            SdkError::DispatchFailure(failure) => {
+                // Credential provider failures surface as dispatch failures, so look through
+                // the source chain to tell them apart from real connection problems.
+                let chain = error_chain(&err).to_lowercase();
+                if chain.contains("profile")
+                    && (chain.contains("not found") || chain.contains("could not find"))
+                {
+                    Error::MissingProfile(profile.to_string())
+                } else if chain.contains("invalidclienttokenid")
+                    || chain.contains("unrecognizedclient")
+                {
+                    rejected_credentials(region)
+                } else if EXPIRED_TOKEN_ERRORS
+                    .iter()
+                    .any(|expired| chain.contains(expired))
+                {
+                    Error::ExpiredCredentials
+                } else {
+                    // DNS and connection failures, usually because the machine is offline.
+                    if !(failure.is_io() || failure.is_timeout()) {
+                        tracing::debug!("Unrecognized dispatch failure: {}", chain);
+                    }
+                    Error::Network(error_chain(&err))
+                }
+            }
+            SdkError::TimeoutError(_) => Error::Network(error_chain(&err)),
+            _ => Error::Service {
+                code: String::from("Unknown"),
+                message: error_chain(&err),
+            },
+        }
+    }
+}
+
+/// Opt-in regions, which reject every token until the account enables them.
+const OPT_IN_REGIONS: &[&str] = &[
+    "af-south-1",
+    "ap-east-1",
+    "ap-east-2",
+    "ap-south-2",
+    "ap-southeast-3",
+    "ap-southeast-4",
+    "ap-southeast-5",
+    "ap-southeast-6",
+    "ap-southeast-7",
+    "ca-west-1",
+    "eu-central-2",
+    "eu-south-1",
+    "eu-south-2",
+    "il-central-1",
+    "me-central-1",
+    "me-south-1",
+    "mx-central-1",
+];
+
+/// Lowercase messages and codes of the token-expiry errors returned by the credential providers.
+const EXPIRED_TOKEN_ERRORS: &[&str] = &[
+    // SSO token provider, when the cached token can no longer be refreshed.
+    "the sso token has expired",
+    // SSO GetRoleCredentials, when the legacy SSO session has ended.
+    "session token not found or invalid",
+    // STS, when the source credentials of an assumed role have expired.
+    "expiredtoken",
+];
+
+/// Maps rejected credentials to [`Error::RegionNotEnabled`] in opt-in regions and
+/// [`Error::InvalidCredentials`] elsewhere.
+fn rejected_credentials(region: &str) -> Error {
+    if OPT_IN_REGIONS.contains(&region) {
+        Error::RegionNotEnabled(region.to_string())
+    } else {
+        Error::InvalidCredentials(region.to_string())
+    }
+}
+
+/// Extracts the IAM action from an "is not authorized to perform: <action>" message.
+fn denied_action(message: &str) -> Option<&str> {
+    let (_, rest) = message.split_once("perform: ")?;
+    rest.split_whitespace().next()
+}
+
+/// Joins an error and all of its sources into a single string.
+fn error_chain(err: &(dyn StdError + 'static)) -> String {
+    let mut chain = err.to_string();
+    let mut source = err.source();
+    while let Some(inner) = source {
+        chain.push_str(": ");
+        chain.push_str(&inner.to_string());
+        source = inner.source();
+    }
+    chain
+}
@@ -0,0 +1,130 @@
+use std::collections::BTreeMap;
+
+/// Prefix of the filter terms that match thing attributes.
+const ATTRIBUTE_PREFIX: &str = "attr:";
+/// Prefix of the filter terms that match resource tags.
+const TAG_PREFIX: &str = "tag:";
+
+/// Key that must be set, with the value it must have if one is given.
+pub type KeyFilter = (String, Option<String>);
+
+/// Filter over names, thing attributes and tags, parsed from the text typed into the filter box.
+///
+/// Terms of the form `attr:key=value` keep things whose attribute `key` is `value`, and
+/// `attr:key` alone keeps things that have the attribute. `tag:key=value` and `tag:key` do the
+/// same for tags. The rest of the text must appear in the name.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Filter {
+    /// Text that must appear in the name.
+    pub text: String,
+    /// Attributes that must be set, with the value they must have if one is given.
+    pub attributes: Vec<KeyFilter>,
+    /// Tags that must be set, with the value they must have if one is given.
+    pub tags: Vec<KeyFilter>,
+}
+
+impl Filter {
+    /// Parses a filter. Text that is not an attribute or tag term is kept as typed.
+    pub fn parse(filter: &str) -> Self {
+        let mut text = Vec::new();
+        let mut attributes = Vec::new();
+        let mut tags = Vec::new();
+        for term in filter.split_whitespace() {
+            if let Some(attribute) = key_term(term, ATTRIBUTE_PREFIX) {
+                attributes.push(attribute);
+            } else if let Some(tag) = key_term(term, TAG_PREFIX) {
+                tags.push(tag);
+            } else {
+                text.push(term);
+            }
+        }
+        Self {
+            text: text.join(" "),
+            attributes,
+            tags,
+        }
+    }
+
+    /// Whether the filter keeps everything.
+    pub fn is_empty(&self) -> bool {
+        self.text.is_empty() && self.attributes.is_empty() && self.tags.is_empty()
+    }
+
+    /// Whether an item with the given name, attributes and tags passes the filter.
+    pub fn matches(
+        &self,
+        name: &str,
+        attributes: &BTreeMap<String, String>,
+        tags: &BTreeMap<String, String>,
+    ) -> bool {
+        name.contains(&self.text)
+            && matches_keys(&self.attributes, attributes)
+            && matches_keys(&self.tags, tags)
+    }
+}
+
+/// Parses `prefix` followed by `key=value` or `key`, `None` for other terms.
+fn key_term(term: &str, prefix: &str) -> Option<KeyFilter> {
+    let term = term.strip_prefix(prefix).filter(|term| !term.is_empty())?;
+    Some(match term.split_once('=') {
+        Some((key, value)) => (key.to_string(), Some(value.to_string())),
+        None => (term.to_string(), None),
+    })
+}
+
+/// Whether every key is set in `values`, with the expected value if one is given.
+fn matches_keys(filters: &[KeyFilter], values: &BTreeMap<String, String>) -> bool {
+    filters
+        .iter()
+        .all(|(key, value)| match (values.get(key), value) {
+            (Some(actual), Some(expected)) => actual == expected,
+            (Some(_), None) => true,
+            (None, _) => false,
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn map(pairs: &[(&str, &str)]) -> BTreeMap<String, String> {
+        pairs
+            .iter()
+            .map(|(key, value)| (key.to_string(), value.to_string()))
+            .collect()
+    }
+
+    #[test]
+    fn parses_attribute_and_tag_terms() {
+        let filter = Filter::parse("gw attr:site=london tag:team  lon");
+        assert_eq!(filter.text, "gw lon");
+        assert_eq!(
+            filter.attributes,
+            vec![(String::from("site"), Some(String::from("london")))]
+        );
+        assert_eq!(filter.tags, vec![(String::from("team"), None)]);
+
+        // A bare prefix is text.
+        assert_eq!(Filter::parse("attr:").text, "attr:");
+        assert!(Filter::parse("   ").is_empty());
+    }
+
+    #[test]
+    fn matches_name_attributes_and_tags() {
+        let attributes = map(&[("site", "london"), ("firmware_version", "2.4.1")]);
+        let tags = map(&[("team", "retail")]);
+
+        assert!(Filter::parse("").matches("lon-gw-001", &attributes, &tags));
+        assert!(Filter::parse("gw-001 attr:site=london").matches("lon-gw-001", &attributes, &tags));
+        assert!(
+            Filter::parse("attr:firmware_version tag:team=retail").matches(
+                "lon-gw-001",
+                &attributes,
+                &tags
+            )
+        );
+        assert!(!Filter::parse("attr:site=manchester").matches("lon-gw-001", &attributes, &tags));
+        assert!(!Filter::parse("tag:owner").matches("lon-gw-001", &attributes, &tags));
+        assert!(!Filter::parse("man").matches("lon-gw-001", &attributes, &tags));
+    }
+}
@@ -1,28 +1,176 @@
-use crate::Property;
-use aws_sdk_iot::types::GroupNameAndArn;
+use std::collections::{BTreeMap, BTreeSet};
 
-/// Greengrass Deployments.
+use crate::{Device, Property};
+use serde::{Deserialize, Serialize};
+
+/// IoT Thing Group.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct ThingGroup {
+    /// The thing group name.
+    pub name: String,
+    /// The thing group ARN.
+    pub arn: String,
+    /// The name of the parent group, `None` for a root group.
+    #[serde(default)]
+    pub parent_group_name: Option<String>,
+    /// The names of the groups above this one, root first and parent last.
+    #[serde(default)]
+    pub root_to_parent: Vec<String>,
+    /// The fleet-indexing query that selects the members of a dynamic group.
+    #[serde(default)]
+    pub query_string: Option<String>,
+    /// The tags attached to the thing group.
+    #[serde(default)]
+    pub tags: BTreeMap<String, String>,
+}
+
+/// Thing group in a depth-first walk of the group hierarchy.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct GroupTreeItem<'a> {
+    /// The thing group.
+    pub group: &'a ThingGroup,
+    /// How many groups are above it, 0 for a root group.
+    pub depth: usize,
+    /// How many child groups it has, whether or not they are shown.
+    pub children: usize,
+    /// Whether its children are hidden.
+    pub is_collapsed: bool,
+}
+
+/// IoT Thing Groups.
 #[derive(Clone, Debug)]
 pub struct ThingGroups {
     /// Inner type.
-    inner: Vec<GroupNameAndArn>,
+    inner: Vec<ThingGroup>,
 }
 
-impl From<Vec<GroupNameAndArn>> for ThingGroups {
-    fn from(inner: Vec<GroupNameAndArn>) -> Self {
+impl From<Vec<ThingGroup>> for ThingGroups {
+    fn from(inner: Vec<ThingGroup>) -> Self {
         Self { inner }
     }
 }
 
+impl ThingGroups {
+    /// Returns the thing groups.
+    pub fn as_slice(&self) -> &[ThingGroup] {
+        &self.inner
+    }
+
+    /// Returns the thing group with the given name.
+    pub fn get(&self, name: &str) -> Option<&ThingGroup> {
+        self.inner.iter().find(|group| group.name == name)
+    }
+
+    /// Returns the direct child groups of a group.
+    pub fn children<'a>(&'a self, parent: &'a str) -> impl Iterator<Item = &'a ThingGroup> + 'a {
+        self.inner
+            .iter()
+            .filter(move |group| group.parent_group_name.as_deref() == Some(parent))
+    }
+
+    /// Walks the hierarchy depth-first, leaving out the descendants of collapsed groups.
+    ///
+    /// Groups whose parent is not loaded are shown as roots.
+    pub fn tree(&self, collapsed: &BTreeSet<String>) -> Vec<GroupTreeItem<'_>> {
+        let mut items = Vec::new();
+        let roots = self.inner.iter().filter(|group| {
+            group
+                .parent_group_name
+                .as_deref()
+                .is_none_or(|parent| self.get(parent).is_none())
+        });
+        for root in roots {
+            self.walk(root, 0, collapsed, &mut items);
+        }
+        items
+    }
+
+    fn walk<'a>(
+        &'a self,
+        group: &'a ThingGroup,
+        depth: usize,
+        collapsed: &BTreeSet<String>,
+        items: &mut Vec<GroupTreeItem<'a>>,
+    ) {
+        let is_collapsed = collapsed.contains(&group.name);
+        items.push(GroupTreeItem {
+            group,
+            depth,
+            children: self.children(&group.name).count(),
+            is_collapsed,
+        });
+        if !is_collapsed {
+            for child in self.children(&group.name) {
+                self.walk(child, depth + 1, collapsed, items);
+            }
+        }
+    }
+}
+
 impl<'a> Property<'a> for ThingGroups {
     fn items(&self) -> Vec<Vec<String>> {
         self.inner
             .iter()
-            .map(|item| {
-                vec![
-                    item.group_name.as_ref().unwrap().to_string(),
-                    item.group_arn.as_ref().unwrap().to_string(),
-                ]
+            .map(|item| vec![item.name.to_string(), item.arn.to_string()])
+            .collect()
+    }
+}
+
+/// Thing in a thing group, with its health if it is a core device.
+#[derive(Clone, Debug, Default)]
+pub struct GroupMember {
+    /// The name of the thing.
+    pub thing_name: String,
+    /// The core device backed by the thing, `None` for other things.
+    pub device: Option<Device>,
+}
+
+impl GroupMember {
+    /// Whether the thing is a core device that is unhealthy or disconnected.
+    pub fn is_unhealthy(&self) -> bool {
+        self.device
+            .as_ref()
+            .is_some_and(|device| device.status != "HEALTHY" || !device.is_connected)
+    }
+}
+
+/// Things in a thing group.
+#[derive(Clone, Debug, Default)]
+pub struct GroupMembers {
+    /// Inner type.
+    inner: Vec<GroupMember>,
+}
+
+impl From<Vec<GroupMember>> for GroupMembers {
+    fn from(inner: Vec<GroupMember>) -> Self {
+        Self { inner }
+    }
+}
+
+impl GroupMembers {
+    /// Returns the members.
+    pub fn as_slice(&self) -> &[GroupMember] {
+        &self.inner
+    }
+}
+
+impl<'a> Property<'a> for GroupMembers {
+    fn items(&self) -> Vec<Vec<String>> {
+        self.inner
+            .iter()
+            .map(|item| match &item.device {
+                Some(device) => vec![
+                    item.thing_name.to_string(),
+                    device.status.to_string(),
+                    String::from(if device.is_connected { "yes" } else { "no" }),
+                    device.last_status_update_timestamp.to_string(),
+                ],
+                None => vec![
+                    item.thing_name.to_string(),
+                    String::from("not a core device"),
+                    String::new(),
+                    String::new(),
+                ],
             })
             .collect()
     }
@@ -0,0 +1,81 @@
+use crate::Property;
+use serde::{Deserialize, Serialize};
+
+/// Component installed on a Greengrass core device.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct InstalledComponent {
+    /// The name of the component.
+    pub component_name: String,
+    /// The version of the component.
+    pub component_version: String,
+    /// The lifecycle state of the component, e.g. RUNNING or BROKEN.
+    pub lifecycle_state: String,
+    /// A detailed response about the lifecycle state of the component.
+    pub lifecycle_state_details: String,
+    /// Status codes explaining the lifecycle state.
+    pub lifecycle_status_codes: Vec<String>,
+    /// Whether or not the component is a root component.
+    pub is_root: bool,
+    /// The time at which the component's status last changed, expressed in ISO 8601 format.
+    pub last_status_change_timestamp: String,
+}
+
+impl InstalledComponent {
+    /// Whether the component is in a failed lifecycle state.
+    pub fn is_failed(&self) -> bool {
+        matches!(self.lifecycle_state.as_str(), "BROKEN" | "ERRORED")
+    }
+
+    /// Lifecycle state details followed by any status codes.
+    pub fn status_details(&self) -> String {
+        if self.lifecycle_status_codes.is_empty() {
+            self.lifecycle_state_details.clone()
+        } else if self.lifecycle_state_details.is_empty() {
+            self.lifecycle_status_codes.join(", ")
+        } else {
+            format!(
+                "{} ({})",
+                self.lifecycle_state_details,
+                self.lifecycle_status_codes.join(", ")
+            )
+        }
+    }
+}
+
+/// Components installed on a core device.
+#[derive(Clone, Debug, Default)]
+pub struct InstalledComponents {
+    /// Inner type.
+    inner: Vec<InstalledComponent>,
+}
+
+impl From<Vec<InstalledComponent>> for InstalledComponents {
+    fn from(inner: Vec<InstalledComponent>) -> Self {
+        Self { inner }
+    }
+}
+
+impl InstalledComponents {
+    /// Returns the installed components.
+    pub fn as_slice(&self) -> &[InstalledComponent] {
+        &self.inner
+    }
+}
+
+impl<'a> Property<'a> for InstalledComponents {
+    fn items(&self) -> Vec<Vec<String>> {
+        self.inner
+            .iter()
+            .map(|item| {
+                vec![
+                    item.component_name.to_string(),
+                    item.component_version.to_string(),
+                    item.lifecycle_state.to_string(),
+                    if item.is_root { String::from("yes") } else { String::from("no") },
+                    item.status_details(),
+                ]
+            })
+            .collect()
+    }
+}
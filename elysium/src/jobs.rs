@@ -0,0 +1,174 @@
+use crate::{Property, target_name};
+use serde::{Deserialize, Serialize};
+
+/// AWS IoT job, such as a firmware update or maintenance task.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Job {
+    /// The ID of the job.
+    pub job_id: String,
+    /// The ARN of the job.
+    pub job_arn: String,
+    /// The status of the job, e.g. IN_PROGRESS or COMPLETED.
+    pub status: String,
+    /// CONTINUOUS if the job also runs on things added to its targets later, else SNAPSHOT.
+    pub target_selection: String,
+    /// The ARNs of the things and thing groups the job runs on.
+    pub targets: Vec<String>,
+    /// The description of the job.
+    pub description: String,
+    /// The time at which the job was created, expressed in ISO 8601 format.
+    pub created_at: String,
+    /// The time at which the job was last updated, expressed in ISO 8601 format.
+    pub last_updated_at: String,
+    /// The time at which the job completed, expressed in ISO 8601 format.
+    pub completed_at: String,
+    /// How many things are in each execution state.
+    pub process_details: JobProcessDetails,
+}
+
+impl Job {
+    /// The names of the targets, without the rest of their ARNs.
+    pub fn target_names(&self) -> Vec<&str> {
+        self.targets
+            .iter()
+            .map(|target| target_name(target))
+            .collect()
+    }
+}
+
+/// Number of things in each execution state of a job.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct JobProcessDetails {
+    pub succeeded: i32,
+    pub failed: i32,
+    pub in_progress: i32,
+    pub queued: i32,
+    pub canceled: i32,
+    pub rejected: i32,
+    pub removed: i32,
+    pub timed_out: i32,
+}
+
+impl JobProcessDetails {
+    /// Things the job did not complete on, whether it failed, timed out or was rejected.
+    pub fn unsuccessful(&self) -> i32 {
+        self.failed + self.timed_out + self.rejected
+    }
+}
+
+/// AWS IoT jobs.
+#[derive(Clone, Debug, Default)]
+pub struct Jobs {
+    /// Inner type.
+    inner: Vec<Job>,
+}
+
+impl From<Vec<Job>> for Jobs {
+    fn from(inner: Vec<Job>) -> Self {
+        Self { inner }
+    }
+}
+
+impl Jobs {
+    /// Returns the jobs.
+    pub fn as_slice(&self) -> &[Job] {
+        &self.inner
+    }
+
+    /// Returns the job with the given ID, if it is listed.
+    pub fn get(&self, job_id: &str) -> Option<&Job> {
+        self.inner.iter().find(|job| job.job_id == job_id)
+    }
+}
+
+impl<'a> Property<'a> for Jobs {
+    fn items(&self) -> Vec<Vec<String>> {
+        self.inner
+            .iter()
+            .map(|item| {
+                vec![
+                    item.job_id.to_string(),
+                    item.status.to_string(),
+                    item.target_selection.to_string(),
+                    item.process_details.succeeded.to_string(),
+                    item.process_details.unsuccessful().to_string(),
+                    item.process_details.in_progress.to_string(),
+                    item.target_names().join(", "),
+                ]
+            })
+            .collect()
+    }
+}
+
+/// Execution of a job on a single thing.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct JobExecution {
+    /// The ARN of the thing the job runs on.
+    pub thing_arn: String,
+    /// The status of the execution, e.g. QUEUED, IN_PROGRESS, SUCCEEDED or FAILED.
+    pub status: String,
+    /// Identifies the execution among the job's executions on the thing.
+    pub execution_number: i64,
+    /// The number of times the execution has been retried.
+    pub retry_attempt: i32,
+    /// The time at which the execution was queued, expressed in ISO 8601 format.
+    pub queued_at: String,
+    /// The time at which the execution started, expressed in ISO 8601 format.
+    pub started_at: String,
+    /// The time at which the execution was last updated, expressed in ISO 8601 format.
+    pub last_updated_at: String,
+}
+
+impl JobExecution {
+    /// The name of the thing the job runs on.
+    pub fn thing_name(&self) -> &str {
+        target_name(&self.thing_arn)
+    }
+
+    /// Whether the job did not complete on the thing.
+    pub fn is_failed(&self) -> bool {
+        matches!(self.status.as_str(), "FAILED" | "TIMED_OUT" | "REJECTED")
+    }
+}
+
+/// Executions of a job, one per thing.
+#[derive(Clone, Debug, Default)]
+pub struct JobExecutions {
+    /// Inner type.
+    inner: Vec<JobExecution>,
+}
+
+impl From<Vec<JobExecution>> for JobExecutions {
+    fn from(inner: Vec<JobExecution>) -> Self {
+        Self { inner }
+    }
+}
+
+impl JobExecutions {
+    /// Returns the job executions.
+    pub fn as_slice(&self) -> &[JobExecution] {
+        &self.inner
+    }
+}
+
+impl<'a> Property<'a> for JobExecutions {
+    fn items(&self) -> Vec<Vec<String>> {
+        self.inner
+            .iter()
+            .map(|item| {
+                vec![
+                    item.thing_name().to_string(),
+                    item.status.to_string(),
+                    item.execution_number.to_string(),
+                    item.retry_attempt.to_string(),
+                    item.queued_at.to_string(),
+                    item.started_at.to_string(),
+                    item.last_updated_at.to_string(),
+                ]
+            })
+            .collect()
+    }
+}
@@ -1,24 +1,88 @@
-use std::{error::Error, time::Duration};
-
-use anyhow::Result;
-use aws_config::{
-    BehaviorVersion,
-    meta::region::RegionProviderChain,
-    stalled_stream_protection::StalledStreamProtectionConfig
+use std::{
+    collections::{BTreeMap, BTreeSet, HashSet},
+    path::Path,
+    sync::Arc,
+    time::Duration,
 };
-use aws_sdk_greengrassv2::{self, error::SdkError, types::Deployment};
-use aws_sdk_iot::{self, types::GroupNameAndArn};
-use aws_types::{region::Region, sdk_config::SdkConfig};
+
+use futures::stream::{self, StreamExt};
+
+use certificates::certificate_id;
 
 pub use {
-    deployments::Deployments,
-    devices::{Device, Devices},
-    groups::ThingGroups,
+    aggregation::{
+        Bucket, DEFAULT_SUMMARY_BUCKET_FIELD, FieldStatistics, FleetSummary, Percentile, Statistics,
+    },
+    backend::{AwsBackend, CloudBackend, FixtureBackend},
+    bulk::{
+        BulkItem, BulkMembership, BulkOptions, BulkOutcome, BulkReport, DEFAULT_BULK_RATE,
+        MembershipChange, parse_thing_names, read_thing_names, run_bulk_membership,
+    },
+    certificates::{Certificate, Certificates, ExpiringCertificate, ExpiryReport},
+    components::{Component, ComponentVersion, ComponentVersions, Components},
+    defender::{ActiveViolation, AuditFinding, Security, Severity, ThingSecurity},
+    dependencies::{
+        DependencyEdge, DependencyGraph, DependencyIssue, DependencyResolution, Requirement,
+        ResolvedComponent,
+    },
+    deployment_detail::{
+        AbortCriteria, ComponentDeploymentSpec, ComponentUpdatePolicy, ConfigurationUpdate,
+        DeploymentDetail, DeploymentPolicies, DeploymentSpec, ExponentialRolloutRate,
+        IotJobConfiguration, RunWith, SystemResourceLimits,
+    },
+    deployments::{
+        Deployment, DeploymentHistory, DeploymentQuery, Deployments, EffectiveDeployment,
+        EffectiveDeployments,
+    },
+    devices::{CoreDevice, Device, Devices, Thing},
+    diff::{
+        ComponentDiff, ConfigurationDiff, RevisionDiff, RunWithDiff, ValueDiff, diff_components,
+        diff_configuration, diff_job_configuration, diff_json, diff_policies, diff_run_with,
+    },
+    error::{Error, LoadFailure, Resource, Result},
+    filter::{Filter, KeyFilter},
+    groups::{GroupMember, GroupMembers, GroupTreeItem, ThingGroup, ThingGroups},
+    installed::{InstalledComponent, InstalledComponents},
+    jobs::{Job, JobExecution, JobExecutions, JobProcessDetails, Jobs},
+    policy::{
+        Decision, Effect, Evaluation, IotAction, PermissionRequest, Policy, PolicyContext,
+        PolicyDocument, PrincipalPolicies, Statement, StatementRef, evaluate,
+    },
+    recipe::{
+        Artifact, ComponentConfiguration, ComponentDependency, DependencyType, IssueSeverity,
+        Lifecycle, LifecycleScript, LifecycleStep, Manifest, Permission, PermissionLevel, Platform,
+        Position, Recipe, RecipeFormat, RecipeIssue, Unarchive,
+    },
+    revision::{
+        ComponentVersionChange, RevisionPlan, RollbackPlan, parse_component_changes, target_name,
+    },
+    search::{DEFAULT_SEARCH_LIMIT, SearchPage, SearchResults, ThingDocument},
+    shadow::{CLASSIC_SHADOW, Shadow, ShadowUpdate},
+    version::{Version, VersionRequirement},
 };
 
+pub mod aggregation;
+pub mod backend;
+pub mod bulk;
+pub mod certificates;
+pub mod components;
+pub mod defender;
+pub mod dependencies;
+pub mod deployment_detail;
 pub mod devices;
 pub mod deployments;
+pub mod diff;
+pub mod error;
+pub mod filter;
 pub mod groups;
+pub mod installed;
+pub mod jobs;
+pub mod policy;
+pub mod recipe;
+pub mod revision;
+pub mod search;
+pub mod shadow;
+pub mod version;
 
 /// Property for receiving information.
 pub trait Property<'a> {
@@ -26,156 +90,976 @@ pub trait Property<'a> {
     fn items(&self) -> Vec<Vec<String>>;
 }
 
+/// Default number of connectivity lookups in flight at once.
+pub const DEFAULT_CONNECTIVITY_CONCURRENCY: usize = 16;
+/// Default timeout for a single connectivity lookup.
+pub const DEFAULT_CONNECTIVITY_TIMEOUT: Duration = Duration::from_secs(5);
+/// Number of jobs described at once while loading.
+const JOB_DESCRIBE_CONCURRENCY: usize = 8;
+/// Number of things or certificates looked up at once while loading certificates.
+const CERTIFICATE_LOOKUP_CONCURRENCY: usize = 8;
+/// Maximum number of thing group and deployment lookups in flight at once.
+const RESOURCE_LOOKUP_CONCURRENCY: usize = 8;
+/// How many days back to list Device Defender audit findings.
+const AUDIT_FINDING_DAYS: i64 = 30;
+/// Fleet-indexing query matching every thing.
+const ALL_THINGS: &str = "thingName:*";
+/// Number of values of the bucketed field kept in the fleet summary.
+const SUMMARY_BUCKETS: usize = 5;
+/// Percentiles of the numeric field in the fleet summary.
+const SUMMARY_PERCENTS: [f64; 3] = [50.0, 90.0, 99.0];
+
 /// AWS information.
-#[derive(Debug)]
+#[derive(Clone, Debug)]
 pub struct AwsCloud {
-    /// Local AWS config.
-    #[allow(dead_code)]
-    shared_config: SdkConfig,
-    /// Greengrass connection client.
-    gg_client: aws_sdk_greengrassv2::Client,
-    /// IoT Core connection client.
-    iot_client: aws_sdk_iot::Client,
+    /// Source of the cloud data.
+    backend: Arc<dyn CloudBackend>,
+    /// Maximum number of connectivity lookups in flight at once.
+    connectivity_concurrency: usize,
+    /// Timeout for a single connectivity lookup.
+    connectivity_timeout: Duration,
+    /// Field the fleet summary counts things by.
+    summary_bucket_field: String,
+    /// Numeric field the fleet summary computes statistics of, if any.
+    summary_metric_field: Option<String>,
+    /// Optional services found not to be enabled for the account, which later loads skip.
+    disabled: HashSet<Resource>,
     /// Greengrass Core Devices.
     pub devices: Devices,
     /// Thing Groups.
     pub groups: ThingGroups,
     /// Greengrass Deployments.
     pub deployments: Deployments,
+    /// Greengrass Components.
+    pub components: Components,
+    /// IoT Jobs.
+    pub jobs: Jobs,
+    /// Certificates attached to the core devices.
+    pub certificates: Certificates,
+    /// Device Defender audit findings and active violations, grouped by thing.
+    pub security: Security,
+    /// Aggregations over all things, `None` until loaded or if fleet indexing is not enabled.
+    pub summary: Option<FleetSummary>,
 }
 
 impl AwsCloud {
-    /// Constructs a new instance.
-    pub async fn new(
-        profile: &str,
-        region: &str,
-    ) -> Result<Self, String> {
-        let region_provider = RegionProviderChain::first_try(Region::new(region.to_owned()))
-            .or_default_provider()
-            .or_else(Region::new("eu-west-1"));
-
-        let shared_config = aws_config::defaults(BehaviorVersion::latest())
-            .stalled_stream_protection(
-            StalledStreamProtectionConfig::enabled()
-                .upload_enabled(false)
-                .grace_period(Duration::from_secs(10))
-                .build()
-            )
-            .profile_name(profile)
-            .region(region_provider)
-            .load()
-            .await;
-
-        let client = aws_sdk_greengrassv2::Client::new(&shared_config);
-
-        // Test to see if we need to authenicate
-        let result = client.list_components().max_results(1).send().await;
-        if result.is_err() {
-            let sdk_error = &result.as_ref().unwrap_err();
-            match sdk_error {
-                SdkError::DispatchFailure(e) => {
-                    tracing::error!("DispatchFailure: {:?}", e.as_connector_error());
-                    return Err(String::from("Please authenticate with aws-cli: aws login."));
-                }
-                SdkError::ServiceError(e) => {
-                    tracing::error!("Service Error: {:?}", e);
-                    return Err(format!("Service Error: {:?}", e.err().source()));
-                }
-                _ => {
-                    return Err(sdk_error.to_string());
-                }
-            }            
-        }
+    /// Constructs a new instance backed by the AWS APIs.
+    pub async fn new(profile: &str, region: &str) -> Result<Self> {
+        let backend = AwsBackend::new(profile, region).await?;
+        Ok(Self::with_backend(backend))
+    }
 
-        let iot_client = aws_sdk_iot::Client::new(&shared_config);
+    /// Constructs a new instance backed by a fixture file.
+    pub fn from_fixture(path: impl AsRef<Path>) -> Result<Self> {
+        Ok(Self::with_backend(FixtureBackend::from_path(path)?))
+    }
 
-        Ok(Self {
-            shared_config: shared_config,
-            gg_client: client,
-            iot_client: iot_client,
+    /// Constructs a new instance using the given backend.
+    pub fn with_backend(backend: impl CloudBackend + 'static) -> Self {
+        Self {
+            backend: Arc::new(backend),
+            connectivity_concurrency: DEFAULT_CONNECTIVITY_CONCURRENCY,
+            connectivity_timeout: DEFAULT_CONNECTIVITY_TIMEOUT,
+            summary_bucket_field: String::from(DEFAULT_SUMMARY_BUCKET_FIELD),
+            summary_metric_field: None,
+            disabled: HashSet::new(),
             devices: Devices::from(vec![]),
             groups: ThingGroups::from(vec![]),
             deployments: Deployments::from(vec![]),
-        })
+            components: Components::from(vec![]),
+            jobs: Jobs::from(vec![]),
+            certificates: Certificates::from(vec![]),
+            security: Security::default(),
+            summary: None,
+        }
+    }
+
+    /// Sets the maximum number of connectivity lookups in flight at once.
+    pub fn with_connectivity_concurrency(mut self, concurrency: usize) -> Self {
+        self.connectivity_concurrency = concurrency.max(1);
+        self
+    }
+
+    /// Sets the timeout for a single connectivity lookup.
+    pub fn with_connectivity_timeout(mut self, timeout: Duration) -> Self {
+        self.connectivity_timeout = timeout;
+        self
+    }
+
+    /// Sets the field the fleet summary counts things by, e.g. `attributes.firmware_version`.
+    pub fn with_summary_bucket_field(mut self, field: impl Into<String>) -> Self {
+        self.summary_bucket_field = field.into();
+        self
     }
 
+    /// Sets a numeric field for the fleet summary to compute statistics and percentiles of.
+    pub fn with_summary_metric_field(mut self, field: Option<String>) -> Self {
+        self.summary_metric_field = field;
+        self
+    }
+
+    /// Loads all resources.
+    ///
+    /// Resources that fail to load keep their previous value and are reported together in
+    /// [`Error::PartialLoad`], so callers can still use whatever did load. Device Defender and
+    /// fleet indexing are skipped from then on if the account has not enabled them, see
+    /// [`AwsCloud::retry_disabled_services`].
     pub async fn load(&mut self) -> Result<()> {
+        let mut failures = Vec::new();
+
+        // Everything but the certificates, which are looked up per core device, loads at once.
+        let (devices, groups, deployments, components, jobs, security, summary) = futures::join!(
+            self.get_core_devices(),
+            self.get_thing_groups(),
+            self.get_deployments(),
+            self.get_components(),
+            self.get_jobs(),
+            self.if_enabled(Resource::Security, self.get_security_findings()),
+            self.if_enabled(Resource::FleetSummary, self.get_fleet_summary()),
+        );
+
+        match devices {
+            Ok(devices) => self.devices = devices,
+            Err(error) => failures.push(LoadFailure {
+                resource: Resource::Devices,
+                error,
+            }),
+        }
+        match groups {
+            Ok(groups) => self.groups = groups,
+            Err(error) => failures.push(LoadFailure {
+                resource: Resource::ThingGroups,
+                error,
+            }),
+        }
+        match deployments {
+            Ok(deployments) => self.deployments = deployments,
+            Err(error) => failures.push(LoadFailure {
+                resource: Resource::Deployments,
+                error,
+            }),
+        }
+        match components {
+            Ok(components) => self.components = components,
+            Err(error) => failures.push(LoadFailure {
+                resource: Resource::Components,
+                error,
+            }),
+        }
+        match jobs {
+            Ok(jobs) => self.jobs = jobs,
+            Err(error) => failures.push(LoadFailure {
+                resource: Resource::Jobs,
+                error,
+            }),
+        }
+        match self.get_certificates().await {
+            Ok(certificates) => self.certificates = certificates,
+            Err(error) => failures.push(LoadFailure {
+                resource: Resource::Certificates,
+                error,
+            }),
+        }
+        // Certificate findings are attributed to things through the certificates.
+        if let Some((findings, violations)) =
+            self.optional_loaded(Resource::Security, security, &mut failures)
+        {
+            self.security = Security::group(findings, violations, self.certificates.as_slice());
+        }
+        if let Some(summary) = self.optional_loaded(Resource::FleetSummary, summary, &mut failures)
+        {
+            self.summary = Some(summary);
+        }
+        if !self.is_enabled(Resource::Security) {
+            self.security = Security::default();
+        }
+        if !self.is_enabled(Resource::FleetSummary) {
+            self.summary = None;
+        }
+
+        if failures.is_empty() { Ok(()) } else { Err(Error::PartialLoad(failures)) }
+    }
+
+    /// Loads only the core devices and their certificates, which is all
+    /// [`AwsCloud::expiring_certificates`] needs.
+    pub async fn load_certificates(&mut self) -> Result<()> {
         self.devices = self.get_core_devices().await?;
-        self.groups = self.get_thing_groups().await?;
-        self.deployments = self.get_deployments().await?;
+        self.certificates = self.get_certificates().await?;
         Ok(())
     }
 
     async fn get_core_devices(&self) -> Result<Devices> {
-        let mut items: Vec<Device> = Vec::new();
-    
-        let resp = self.gg_client.list_core_devices()
-            .into_paginator()
-            .send()
-            .try_collect()
-            .await?;
-        
-        for device in resp.into_iter().flat_map(|x| x.core_devices.unwrap_or_default()) {
-            let thing_name =  device.core_device_thing_name().unwrap_or_default().to_string();
-            // let connectivity = self.gg_client.get_connectivity_info().thing_name(&thing_name).send().await?;
-            let connectivity = self.iot_client.get_thing_connectivity_data().thing_name(&thing_name).send().await;
-            let mut is_connected = false;
-            match connectivity {
-                Ok(connectivity) => {
-                    is_connected = connectivity.connected().unwrap_or_default();
-                },
-                Err(e) => {
-                    tracing::error!("Error getting connectivity info: {:?}", e);
+        let core_devices = self.backend.list_core_devices().await?;
+
+        let lookups: Vec<_> = core_devices
+            .iter()
+            .map(|device| async {
+                futures::join!(
+                    self.get_connectivity(&device.thing_name),
+                    self.get_thing(&device.thing_name),
+                    self.get_core_device_tags(&device.thing_name),
+                )
+            })
+            .collect();
+        // `buffered` keeps the results in the same order as the core devices.
+        let lookups: Vec<_> = stream::iter(lookups)
+            .buffered(self.connectivity_concurrency)
+            .collect()
+            .await;
+
+        let mut items: Vec<Device> = core_devices
+            .into_iter()
+            .zip(lookups)
+            .map(|(device, (connectivity, thing, tags))| {
+                let (is_connected, connectivity_error) = match connectivity {
+                    Ok(connected) => (connected, None),
+                    Err(e) => (false, Some(e)),
+                };
+                Device {
+                    name: device.thing_name,
+                    is_connected,
+                    connectivity_error,
+                    status: device.status,
+                    last_status_update_timestamp: device.last_status_update_timestamp,
+                    thing_type: thing.thing_type_name,
+                    billing_group: thing.billing_group_name,
+                    attributes: thing.attributes,
+                    tags,
                 }
+            })
+            .collect();
+
+        items.sort_by_key(|a| a.name.to_lowercase());
+
+        Ok(Devices::from(items))
+    }
+
+    async fn get_connectivity(&self, thing_name: &str) -> Result<bool, String> {
+        let lookup = self.backend.get_thing_connectivity(thing_name);
+        match tokio::time::timeout(self.connectivity_timeout, lookup).await {
+            Ok(Ok(connected)) => Ok(connected),
+            Ok(Err(e)) => {
+                tracing::error!("Error getting connectivity info for {}: {}", thing_name, e);
+                Err(e.to_string())
             }
+            Err(_) => {
+                tracing::error!("Timed out getting connectivity info for {}", thing_name);
+                Err(format!("Timed out after {:?}", self.connectivity_timeout))
+            }
+        }
+    }
 
-            items.push(Device {
-                name: thing_name,
-                is_connected: is_connected,
-                status: device.status().unwrap().to_string(),
-                last_status_update_timestamp: device.last_status_update_timestamp().unwrap().to_string(),
-            });
+    /// Returns the registry entry of a thing, or an empty one if it cannot be described.
+    async fn get_thing(&self, thing_name: &str) -> Thing {
+        match self.backend.describe_thing(thing_name).await {
+            Ok(thing) => thing,
+            Err(e) => {
+                tracing::error!("Error describing thing {}: {}", thing_name, e);
+                Thing {
+                    thing_name: thing_name.to_string(),
+                    ..Default::default()
+                }
+            }
         }
-    
-        items.sort_by(|a, b| a.name.to_lowercase().cmp(&b.name.to_lowercase()));
+    }
 
-        Ok(Devices::from(items))
+    /// Returns the tags of a core device, or none if they cannot be listed.
+    async fn get_core_device_tags(&self, thing_name: &str) -> BTreeMap<String, String> {
+        self.backend
+            .get_core_device_tags(thing_name)
+            .await
+            .unwrap_or_else(|e| {
+                tracing::error!("Error listing tags of core device {}: {}", thing_name, e);
+                BTreeMap::new()
+            })
     }
 
     async fn get_thing_groups(&self) -> Result<ThingGroups> {
-        let mut items: Vec<GroupNameAndArn> = Vec::new();
+        let groups = self.backend.list_thing_groups().await?;
+
+        let lookups: Vec<_> = groups
+            .into_iter()
+            .map(|group| async move {
+                // Only `DescribeThingGroup` returns where the group sits in the hierarchy.
+                let (described, tags) = futures::join!(
+                    self.backend.describe_thing_group(&group.name),
+                    self.backend.list_tags_for_resource(&group.arn),
+                );
+                let tags = tags.unwrap_or_else(|e| {
+                    tracing::error!("Error listing tags of thing group {}: {}", group.name, e);
+                    BTreeMap::new()
+                });
+                Ok::<_, Error>(ThingGroup { tags, ..described? })
+            })
+            .collect();
+        let mut items: Vec<ThingGroup> = stream::iter(lookups)
+            .buffered(RESOURCE_LOOKUP_CONCURRENCY)
+            .collect::<Vec<_>>()
+            .await
+            .into_iter()
+            .collect::<Result<_>>()?;
+
+        items.sort_by_key(|a| a.name.to_lowercase());
+
+        Ok(ThingGroups::from(items))
+    }
+
+    async fn get_components(&self) -> Result<Components> {
+        let mut items = self.backend.list_components().await?;
+
+        items.sort_by_key(|a| a.component_name.to_lowercase());
+
+        Ok(Components::from(items))
+    }
+
+    /// Returns every version of the component with the given ARN, newest first.
+    pub async fn component_versions(&self, arn: &str) -> Result<ComponentVersions> {
+        let mut items = self.backend.list_component_versions(arn).await?;
+
+        items.sort_by(|a, b| compare_versions(&b.component_version, &a.component_version));
+
+        Ok(ComponentVersions::from(items))
+    }
+
+    /// Fetches and parses the recipe of the component version with the given ARN.
+    pub async fn component_recipe(&self, arn: &str) -> Result<Recipe> {
+        let source = self.backend.get_component_recipe(arn).await?;
+        Recipe::parse_as(&source, RecipeFormat::Yaml)
+    }
+
+    /// Lists every deployment matching the query.
+    pub async fn list_deployments(&self, query: &DeploymentQuery) -> Result<Vec<Deployment>> {
+        self.backend.list_deployments(query).await
+    }
 
-        let resp = self.iot_client.list_thing_groups()
-            .into_paginator()
-            .send()
-            .try_collect()
+    /// Returns every revision of the deployment for a target, newest first.
+    pub async fn deployment_history(&self, target_arn: &str) -> Result<Vec<Deployment>> {
+        let mut items = self
+            .list_deployments(&DeploymentQuery::history(target_arn))
             .await?;
 
-        for group in resp.into_iter().flat_map(|x| x.thing_groups.unwrap_or_default()) {
-            items.push(group);
+        items.sort_by_key(|a| std::cmp::Reverse(revision_number(&a.revision_id)));
+
+        Ok(items)
+    }
+
+    /// Returns the components installed on a core device, failed components first.
+    pub async fn installed_components(&self, thing_name: &str) -> Result<InstalledComponents> {
+        let mut items = self.backend.list_installed_components(thing_name).await?;
+
+        items.sort_by_key(|a| (!a.is_failed(), a.component_name.to_lowercase()));
+
+        Ok(InstalledComponents::from(items))
+    }
+
+    /// Returns the deployments applied to a core device, most recently modified first.
+    pub async fn effective_deployments(&self, thing_name: &str) -> Result<EffectiveDeployments> {
+        let mut items = self.backend.list_effective_deployments(thing_name).await?;
+
+        items.sort_by(|a, b| b.modified_timestamp.cmp(&a.modified_timestamp));
+
+        Ok(EffectiveDeployments::from(items))
+    }
+
+    /// Returns the full description of a deployment revision.
+    pub async fn deployment_detail(&self, deployment_id: &str) -> Result<DeploymentDetail> {
+        self.backend.get_deployment(deployment_id).await
+    }
+
+    /// Compares two revisions of the deployment for the same target.
+    pub async fn compare_revisions(
+        &self,
+        old_deployment_id: &str,
+        new_deployment_id: &str,
+    ) -> Result<RevisionDiff> {
+        let old = self.deployment_detail(old_deployment_id).await?;
+        let new = self.deployment_detail(new_deployment_id).await?;
+        RevisionDiff::new(&old, &new)
+    }
+
+    /// Compares the latest revision of the deployment for a target with the one before it.
+    ///
+    /// Returns `None` if the target has been deployed to only once.
+    pub async fn latest_changes(&self, target_arn: &str) -> Result<Option<RevisionDiff>> {
+        match self.deployment_history(target_arn).await?.as_slice() {
+            [new, old, ..] => Ok(Some(
+                self.compare_revisions(&old.deployment_id, &new.deployment_id)
+                    .await?,
+            )),
+            _ => Ok(None),
         }
+    }
+
+    /// Resolves the transitive dependencies of a deployment's components without deploying.
+    ///
+    /// Recipes are fetched for every component reached, and conflicting version requirements,
+    /// cycles and components that cannot be fetched are reported in
+    /// [`DependencyGraph::issues`].
+    pub async fn resolve_dependencies(
+        &self,
+        target_arn: &str,
+        components: &BTreeMap<String, ComponentDeploymentSpec>,
+    ) -> Result<DependencyGraph> {
+        self.dependency_resolution(target_arn, components)
+            .resolve()
+            .await
+    }
 
-        items.sort_by(|a, b| a.group_name.as_ref().unwrap().to_lowercase().cmp(&b.group_name.as_ref().unwrap().to_lowercase()));
+    /// Prepares resolving the dependencies of a deployment's components, see
+    /// [`AwsCloud::resolve_dependencies`].
+    ///
+    /// The resolution takes what it needs from the loaded catalog, so it can run without
+    /// borrowing this instance while it fetches recipes.
+    pub fn dependency_resolution(
+        &self,
+        target_arn: &str,
+        components: &BTreeMap<String, ComponentDeploymentSpec>,
+    ) -> DependencyResolution {
+        let catalog = self
+            .components
+            .as_slice()
+            .iter()
+            .map(|component| (component.component_name.clone(), component.arn.clone()))
+            .collect();
+        DependencyResolution::new(
+            self.backend.clone(),
+            target_arn,
+            catalog,
+            components.clone(),
+        )
+    }
 
-        Ok(ThingGroups::from(items))
+    /// Returns the latest deployment for a target, if it has one.
+    pub async fn latest_deployment(&self, target_arn: &str) -> Result<Option<DeploymentDetail>> {
+        let query = DeploymentQuery {
+            target_arn: Some(target_arn.to_string()),
+            history: DeploymentHistory::LatestOnly,
+        };
+        match self.list_deployments(&query).await?.first() {
+            Some(deployment) => Ok(Some(
+                self.deployment_detail(&deployment.deployment_id).await?,
+            )),
+            None => Ok(None),
+        }
     }
 
-    async fn get_deployments(&self) -> Result<Deployments> {
-        let mut items: Vec<Deployment> = Vec::new();
+    /// Plans a new revision of the deployment for a target without deploying anything.
+    ///
+    /// The returned plan holds the diff against the latest deployment for the target and is
+    /// deployed with [`AwsCloud::create_deployment`] once confirmed.
+    pub async fn revise_deployment(
+        &self,
+        target_arn: &str,
+        changes: &[ComponentVersionChange],
+    ) -> Result<RevisionPlan> {
+        let current = self.latest_deployment(target_arn).await?;
+        Ok(RevisionPlan::new(target_arn, current, changes))
+    }
 
-        let resp = self.gg_client.list_deployments()
-            .history_filter(aws_sdk_greengrassv2::types::DeploymentHistoryFilter::LatestOnly)
-            .send()
+    /// Plans re-issuing an earlier revision of the deployment for a target without deploying
+    /// anything.
+    ///
+    /// The returned plan holds the diff from the latest revision to the earlier one and is
+    /// deployed with [`AwsCloud::create_deployment`] once confirmed.
+    pub async fn rollback_deployment(
+        &self,
+        target_arn: &str,
+        revision_id: &str,
+    ) -> Result<RollbackPlan> {
+        let history = self.deployment_history(target_arn).await?;
+        let current = history
+            .iter()
+            .find(|deployment| deployment.is_latest_for_target)
+            .or(history.first())
+            .ok_or_else(|| Error::NotFound(format!("No deployments for {target_arn}")))?;
+        let revision = history
+            .iter()
+            .find(|deployment| deployment.revision_id == revision_id)
+            .ok_or_else(|| {
+                Error::NotFound(format!(
+                    "Revision {revision_id} of the deployment for {target_arn}"
+                ))
+            })?;
+        if revision.deployment_id == current.deployment_id {
+            return Err(Error::InvalidRequest(format!(
+                "Revision {revision_id} is already the latest for {target_arn}"
+            )));
+        }
+
+        let current = self.deployment_detail(&current.deployment_id).await?;
+        let revision = self.deployment_detail(&revision.deployment_id).await?;
+        RollbackPlan::new(current, revision)
+    }
+
+    /// Creates a deployment, returning its ID.
+    pub async fn create_deployment(&self, spec: &DeploymentSpec) -> Result<String> {
+        let deployment_id = self.backend.create_deployment(spec).await?;
+        tracing::info!(
+            "Created deployment {} for {}",
+            deployment_id,
+            spec.target_arn
+        );
+        Ok(deployment_id)
+    }
+
+    /// Cancels a deployment. It shows as CANCELED once the deployments are reloaded.
+    pub async fn cancel_deployment(&self, deployment_id: &str) -> Result<()> {
+        self.backend.cancel_deployment(deployment_id).await?;
+        tracing::info!("Canceled deployment {}", deployment_id);
+        Ok(())
+    }
+
+    /// Lists the jobs, newest first, and describes each for its execution counts.
+    ///
+    /// Jobs that cannot be described are kept as listed, without counts.
+    async fn get_jobs(&self) -> Result<Jobs> {
+        let summaries = self.backend.list_jobs().await?;
+
+        let lookups: Vec<_> = summaries
+            .into_iter()
+            .map(|summary| async move {
+                match self.backend.describe_job(&summary.job_id).await {
+                    Ok(job) => job,
+                    Err(e) => {
+                        tracing::error!("Error describing job {}: {}", summary.job_id, e);
+                        summary
+                    }
+                }
+            })
+            .collect();
+        let mut items: Vec<Job> = stream::iter(lookups)
+            .buffered(JOB_DESCRIBE_CONCURRENCY)
+            .collect()
+            .await;
+
+        items.sort_by(|a, b| b.created_at.cmp(&a.created_at));
+
+        Ok(Jobs::from(items))
+    }
+
+    /// Returns the executions of a job, failed ones first.
+    pub async fn job_executions(&self, job_id: &str) -> Result<JobExecutions> {
+        let mut items = self.backend.list_job_executions_for_job(job_id).await?;
+
+        items.sort_by(|a, b| {
+            (!a.is_failed(), a.thing_name().to_lowercase())
+                .cmp(&(!b.is_failed(), b.thing_name().to_lowercase()))
+        });
+
+        Ok(JobExecutions::from(items))
+    }
+
+    /// Returns the shadows of a thing, the classic one first if it exists.
+    pub async fn shadows(&self, thing_name: &str) -> Result<Vec<Shadow>> {
+        let mut items = Vec::new();
+        match self.backend.get_shadow(thing_name, None).await {
+            Ok(shadow) => items.push(shadow),
+            Err(Error::NotFound(_)) => {}
+            Err(e) => return Err(e),
+        }
+
+        let mut names = self.backend.list_named_shadows(thing_name).await?;
+        names.sort_by_key(|name| name.to_lowercase());
+        for name in names {
+            items.push(self.backend.get_shadow(thing_name, Some(&name)).await?);
+        }
+
+        Ok(items)
+    }
+
+    /// Returns a thing's classic shadow, or the named one if `shadow_name` is given.
+    pub async fn shadow(&self, thing_name: &str, shadow_name: Option<&str>) -> Result<Shadow> {
+        self.backend.get_shadow(thing_name, shadow_name).await
+    }
+
+    /// Plans merging `desired`, a JSON object, into the desired state of a shadow.
+    ///
+    /// A shadow that does not exist yet is planned as empty; the update creates it.
+    pub async fn plan_shadow_update(
+        &self,
+        thing_name: &str,
+        shadow_name: Option<&str>,
+        desired: &str,
+    ) -> Result<ShadowUpdate> {
+        let desired = serde_json::from_str(desired)
+            .map_err(|e| Error::InvalidRequest(format!("Invalid desired state: {e}")))?;
+        let current = match self.backend.get_shadow(thing_name, shadow_name).await {
+            Ok(shadow) => shadow,
+            Err(Error::NotFound(_)) => Shadow {
+                thing_name: thing_name.to_string(),
+                shadow_name: shadow_name.map(str::to_string),
+                ..Default::default()
+            },
+            Err(e) => return Err(e),
+        };
+
+        ShadowUpdate::new(current, desired)
+    }
+
+    /// Publishes a planned shadow update and returns the shadow as it is afterwards.
+    ///
+    /// Fails if the shadow was updated since the plan was made.
+    pub async fn update_shadow(&self, update: &ShadowUpdate) -> Result<Shadow> {
+        let shadow = &update.current;
+        self.backend
+            .update_shadow(
+                &shadow.thing_name,
+                shadow.shadow_name.as_deref(),
+                &update.payload(),
+            )
+            .await?;
+        tracing::info!("Updated {} shadow of {}", shadow.name(), shadow.thing_name);
+        self.shadow(&shadow.thing_name, shadow.shadow_name.as_deref())
+            .await
+    }
+
+    /// Lists the certificates attached to each core device, those expiring first.
+    ///
+    /// Principals that are not certificates, such as Cognito identities, are left out.
+    async fn get_certificates(&self) -> Result<Certificates> {
+        let lookups: Vec<_> = self
+            .devices
+            .as_slice()
+            .iter()
+            .map(|device| async move {
+                (
+                    device.name.clone(),
+                    self.backend.list_thing_principals(&device.name).await,
+                )
+            })
+            .collect();
+        // A thing whose principals cannot be listed is recorded, the others are still checked.
+        let mut principals: Vec<(String, Vec<String>)> = Vec::new();
+        let mut failures = BTreeMap::new();
+        for (thing_name, lookup) in stream::iter(lookups)
+            .buffered(CERTIFICATE_LOOKUP_CONCURRENCY)
+            .collect::<Vec<_>>()
+            .await
+        {
+            match lookup {
+                Ok(thing_principals) => principals.push((thing_name, thing_principals)),
+                Err(e) => {
+                    tracing::error!("Error listing principals of {}: {}", thing_name, e);
+                    failures.insert(thing_name, e.to_string());
+                }
+            }
+        }
+
+        // A certificate can be attached to several things, so describe each only once.
+        let certificate_ids: BTreeSet<&str> = principals
+            .iter()
+            .flat_map(|(_, principals)| principals.iter().filter_map(|arn| certificate_id(arn)))
+            .collect();
+        let lookups: Vec<_> = certificate_ids
+            .into_iter()
+            .map(|id| self.backend.describe_certificate(id))
+            .collect();
+        let described: BTreeMap<String, Certificate> = stream::iter(lookups)
+            .buffered(CERTIFICATE_LOOKUP_CONCURRENCY)
+            .collect::<Vec<_>>()
+            .await
+            .into_iter()
+            .map(|certificate| certificate.map(|c| (c.certificate_id.clone(), c)))
+            .collect::<Result<_>>()?;
+
+        let mut items: Vec<Certificate> = principals
+            .iter()
+            .flat_map(|(thing_name, principals)| {
+                principals
+                    .iter()
+                    .filter_map(|arn| described.get(certificate_id(arn)?))
+                    .map(|certificate| Certificate {
+                        thing_name: thing_name.clone(),
+                        ..certificate.clone()
+                    })
+            })
+            .collect();
+
+        items.sort_by(|a, b| (&a.valid_to, &a.thing_name).cmp(&(&b.valid_to, &b.thing_name)));
+
+        Ok(Certificates::from(items).with_failures(failures))
+    }
+
+    /// Lists the things in a thing group, with the health of those that are core devices.
+    ///
+    /// With `recursive` set, the things in the group's descendants are listed too.
+    pub async fn thing_group_members(
+        &self,
+        group_name: &str,
+        recursive: bool,
+    ) -> Result<GroupMembers> {
+        let things = self
+            .backend
+            .list_things_in_thing_group(group_name, recursive)
+            .await?;
+
+        let mut items: Vec<GroupMember> = things
+            .into_iter()
+            .map(|thing_name| GroupMember {
+                device: self
+                    .devices
+                    .as_slice()
+                    .iter()
+                    .find(|device| device.name == thing_name)
+                    .cloned(),
+                thing_name,
+            })
+            .collect();
+        items.sort_by_key(|a| a.thing_name.to_lowercase());
+
+        Ok(GroupMembers::from(items))
+    }
+
+    /// Searches the fleet index, e.g. `connectivity.connected:false AND attributes.site:LON*`.
+    ///
+    /// Follows the pages of results until `limit` things are found.
+    pub async fn search(&self, query: &str, limit: usize) -> Result<SearchResults> {
+        let mut things = Vec::new();
+        let mut next_token = None;
+        loop {
+            let page = self
+                .backend
+                .search_index(query, next_token.as_deref())
+                .await?;
+            things.extend(page.things);
+            next_token = page.next_token;
+            if next_token.is_none() || things.len() >= limit {
+                break;
+            }
+        }
+        let truncated = next_token.is_some() || things.len() > limit;
+        things.truncate(limit);
+
+        Ok(SearchResults::new(query, things, truncated))
+    }
+
+    /// Creates a dynamic thing group whose members are the things matching a query.
+    pub async fn create_dynamic_group(&self, group_name: &str, query: &str) -> Result<ThingGroup> {
+        let group = self
+            .backend
+            .create_dynamic_thing_group(group_name, query)
             .await?;
+        tracing::info!("Created dynamic thing group {} for {}", group.name, query);
+        Ok(group)
+    }
+
+    /// Adds things to, or removes them from, a static thing group, one at a time.
+    ///
+    /// See [`run_bulk_membership`], which runs the change without borrowing this instance.
+    pub async fn bulk_membership(
+        &self,
+        membership: &BulkMembership,
+        options: &BulkOptions,
+    ) -> Result<BulkReport> {
+        bulk::run_bulk_membership(self.backend.as_ref(), membership, options).await
+    }
+
+    /// Shared handle to the backend, for long-running work such as bulk changes that should
+    /// not hold on to this instance.
+    pub fn backend(&self) -> Arc<dyn CloudBackend> {
+        self.backend.clone()
+    }
+
+    /// Whether an optional service such as fleet indexing is enabled, as far as loading found.
+    pub fn is_enabled(&self, resource: Resource) -> bool {
+        !self.disabled.contains(&resource)
+    }
+
+    /// Tries the services found not to be enabled again on the next load, e.g. once the user
+    /// has enabled them.
+    pub fn retry_disabled_services(&mut self) {
+        self.disabled.clear();
+    }
+
+    /// Runs `fetch` unless the service it uses was found not to be enabled.
+    async fn if_enabled<T>(
+        &self,
+        resource: Resource,
+        fetch: impl Future<Output = Result<T>>,
+    ) -> Option<Result<T>> {
+        if self.is_enabled(resource) { Some(fetch.await) } else { None }
+    }
+
+    /// Returns what an optional service loaded, disabling the service instead of reporting a
+    /// failure if the account has not enabled it.
+    fn optional_loaded<T>(
+        &mut self,
+        resource: Resource,
+        result: Option<Result<T>>,
+        failures: &mut Vec<LoadFailure>,
+    ) -> Option<T> {
+        match result? {
+            Ok(value) => Some(value),
+            Err(error) if error.is_not_enabled() => {
+                tracing::info!("Skipping {} until the next reload: {}", resource, error);
+                self.disabled.insert(resource);
+                None
+            }
+            Err(error) => {
+                failures.push(LoadFailure { resource, error });
+                None
+            }
+        }
+    }
+
+    /// Lists recent audit findings and active violations.
+    async fn get_security_findings(&self) -> Result<(Vec<AuditFinding>, Vec<ActiveViolation>)> {
+        let now = aws_sdk_iot::primitives::DateTime::from(std::time::SystemTime::now());
+        let since = aws_sdk_iot::primitives::DateTime::from_secs(
+            now.secs() - AUDIT_FINDING_DAYS * 24 * 60 * 60,
+        );
+        let (findings, violations) = futures::join!(
+            self.backend.list_audit_findings(since),
+            self.backend.list_active_violations(),
+        );
+        Ok((findings?, violations?))
+    }
+
+    /// Aggregates the whole fleet with fleet indexing, without listing the things.
+    async fn get_fleet_summary(&self) -> Result<FleetSummary> {
+        let (things, connected, disconnected, thing_types, buckets) = futures::join!(
+            self.backend.get_statistics(ALL_THINGS, None),
+            self.backend
+                .get_statistics("connectivity.connected:true", None),
+            self.backend
+                .get_statistics("connectivity.connected:false", None),
+            self.backend.get_cardinality(ALL_THINGS, "thingTypeName"),
+            self.backend
+                .get_buckets(ALL_THINGS, &self.summary_bucket_field, SUMMARY_BUCKETS),
+        );
+        let metric = match &self.summary_metric_field {
+            Some(field) => {
+                let (statistics, percentiles) = futures::join!(
+                    self.backend.get_statistics(ALL_THINGS, Some(field)),
+                    self.backend
+                        .get_percentiles(ALL_THINGS, field, &SUMMARY_PERCENTS),
+                );
+                Some(FieldStatistics {
+                    field: field.clone(),
+                    statistics: statistics?,
+                    percentiles: percentiles?,
+                })
+            }
+            None => None,
+        };
+
+        Ok(FleetSummary {
+            things: things?.count,
+            connected: connected?.count,
+            disconnected: disconnected?.count,
+            thing_types: thing_types?,
+            bucket_field: self.summary_bucket_field.clone(),
+            buckets: buckets?,
+            metric,
+        })
+    }
 
-        for deployment in resp.deployments.unwrap() {
-            if deployment.deployment_name.is_some() {
-                items.push(deployment);
+    /// Reports the active certificates that expire within `days` from now, or already have.
+    pub fn expiring_certificates(&self, days: i64) -> ExpiryReport {
+        let now = aws_sdk_iot::primitives::DateTime::from(std::time::SystemTime::now());
+        let now = aws_sdk_iot::primitives::DateTime::from_secs(now.secs());
+        self.certificates.expiry_report(days, now)
+    }
+
+    /// Returns the policies attached to each certificate of a thing.
+    pub async fn thing_policies(&self, thing_name: &str) -> Result<Vec<PrincipalPolicies>> {
+        let mut items = Vec::new();
+        for principal in self.backend.list_thing_principals(thing_name).await? {
+            if certificate_id(&principal).is_none() {
+                continue;
+            }
+            let mut policies = Vec::new();
+            for policy_name in self.backend.list_attached_policies(&principal).await? {
+                policies.push(self.backend.get_policy(&policy_name).await?);
             }
+            items.push(PrincipalPolicies {
+                principal,
+                policies,
+            });
         }
+        Ok(items)
+    }
+
+    /// Evaluates a request for each certificate of a thing, connecting as the thing with its
+    /// name as client ID.
+    ///
+    /// Returns the certificate ID and evaluation for each certificate.
+    pub async fn check_permission(
+        &self,
+        thing_name: &str,
+        request: &PermissionRequest,
+    ) -> Result<Vec<(String, Evaluation)>> {
+        let device = self
+            .devices
+            .as_slice()
+            .iter()
+            .find(|device| device.name == thing_name);
+        self.thing_policies(thing_name)
+            .await?
+            .into_iter()
+            .map(|principal| {
+                // Certificate ARNs are `arn:aws:iot:<region>:<account>:cert/<id>`.
+                let mut arn = principal.principal.split(':').skip(3);
+                let context = PolicyContext {
+                    region: arn.next().unwrap_or_default().to_string(),
+                    account_id: arn.next().unwrap_or_default().to_string(),
+                    client_id: thing_name.to_string(),
+                    thing_name: Some(thing_name.to_string()),
+                    thing_type_name: device.and_then(|device| device.thing_type.clone()),
+                    attributes: device
+                        .map(|device| device.attributes.clone())
+                        .unwrap_or_default(),
+                };
+                let evaluation = evaluate(&principal.policies, &context, request)?;
+                Ok((principal.name().to_string(), evaluation))
+            })
+            .collect()
+    }
+
+    async fn get_deployments(&self) -> Result<Deployments> {
+        let deployments = self.list_deployments(&DeploymentQuery::latest()).await?;
+
+        // Only `GetDeployment` returns the tags of a deployment.
+        let lookups: Vec<_> = deployments
+            .into_iter()
+            .map(|deployment| async move {
+                match self.backend.get_deployment(&deployment.deployment_id).await {
+                    Ok(detail) => Deployment {
+                        tags: detail.tags,
+                        ..deployment
+                    },
+                    Err(e) => {
+                        tracing::error!(
+                            "Error getting tags of deployment {}: {}",
+                            deployment.deployment_id,
+                            e
+                        );
+                        deployment
+                    }
+                }
+            })
+            .collect();
+        let mut items: Vec<Deployment> = stream::iter(lookups)
+            .buffered(RESOURCE_LOOKUP_CONCURRENCY)
+            .collect()
+            .await;
 
-        items.sort_by(|a, b| a.deployment_name.as_ref().unwrap().to_lowercase().cmp(&b.deployment_name.as_ref().unwrap().to_lowercase()));
+        items.sort_by_key(|a| a.deployment_name.to_lowercase());
 
         Ok(Deployments::from(items))
     }
-}
\ No newline at end of file
+}
+
+/// Parses a deployment revision ID, which the service returns as a decimal string.
+fn revision_number(revision_id: &str) -> u64 {
+    revision_id.parse().unwrap_or_default()
+}
+
+/// Orders component versions such as `1.10.0` after `1.9.2`, falling back to text for
+/// versions that are not semantic.
+fn compare_versions(a: &str, b: &str) -> std::cmp::Ordering {
+    match (a.parse::<Version>(), b.parse::<Version>()) {
+        (Ok(a), Ok(b)) => a.cmp(&b),
+        _ => a.cmp(b),
+    }
+}
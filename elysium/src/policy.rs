@@ -0,0 +1,688 @@
+use std::{collections::BTreeMap, fmt, str::FromStr};
+
+use serde::{Deserialize, Deserializer, Serialize};
+use serde_json::Value;
+
+use crate::{Error, Result, certificates::certificate_id};
+
+/// IoT policy, with the document of its default version.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Policy {
+    /// The name of the policy.
+    pub policy_name: String,
+    /// The ARN of the policy.
+    pub policy_arn: String,
+    /// The JSON policy document.
+    pub policy_document: String,
+}
+
+impl Policy {
+    /// Parses the policy document.
+    pub fn document(&self) -> Result<PolicyDocument> {
+        serde_json::from_str(&self.policy_document).map_err(|e| Error::Service {
+            code: String::from("MalformedPolicy"),
+            message: format!(
+                "Policy {} is not a valid policy document: {e}",
+                self.policy_name
+            ),
+        })
+    }
+
+    /// The policy document, pretty-printed if it is valid JSON.
+    pub fn document_pretty(&self) -> String {
+        serde_json::from_str::<Value>(&self.policy_document)
+            .ok()
+            .and_then(|document| serde_json::to_string_pretty(&document).ok())
+            .unwrap_or_else(|| self.policy_document.clone())
+    }
+}
+
+/// Parsed IoT policy document.
+#[derive(Clone, Debug, Default, PartialEq, Deserialize)]
+pub struct PolicyDocument {
+    /// The policy language version.
+    #[serde(rename = "Version", default)]
+    pub version: String,
+    /// The statements, in document order.
+    #[serde(rename = "Statement", deserialize_with = "one_or_many")]
+    pub statements: Vec<Statement>,
+}
+
+/// Whether a statement allows or denies what it matches.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Deserialize)]
+pub enum Effect {
+    Allow,
+    Deny,
+}
+
+/// Statement of an IoT policy document.
+#[derive(Clone, Debug, PartialEq, Deserialize)]
+pub struct Statement {
+    /// Whether the statement allows or denies.
+    #[serde(rename = "Effect")]
+    pub effect: Effect,
+    /// Action patterns, such as `iot:Publish` or `iot:*`.
+    #[serde(rename = "Action", default, deserialize_with = "one_or_many")]
+    pub actions: Vec<String>,
+    /// Action patterns the statement does not apply to, in place of `Action`.
+    #[serde(rename = "NotAction", default, deserialize_with = "one_or_many")]
+    pub not_actions: Vec<String>,
+    /// Resource ARN patterns, which may contain policy variables.
+    #[serde(rename = "Resource", default, deserialize_with = "one_or_many")]
+    pub resources: Vec<String>,
+    /// Resource ARN patterns the statement does not apply to, in place of `Resource`.
+    #[serde(rename = "NotResource", default, deserialize_with = "one_or_many")]
+    pub not_resources: Vec<String>,
+    /// Conditions, which are not evaluated locally.
+    #[serde(rename = "Condition", default)]
+    pub condition: Option<Value>,
+}
+
+impl Statement {
+    /// Whether the statement applies to an action on a resource, `None` if that cannot be
+    /// determined because a `NotAction` or `NotResource` pattern has a policy variable the
+    /// context cannot resolve.
+    fn applies_to(&self, action: &str, resource: &str, context: &PolicyContext) -> Option<bool> {
+        let action = applies(&self.actions, &self.not_actions, action, context, true);
+        let resource = applies(
+            &self.resources,
+            &self.not_resources,
+            resource,
+            context,
+            false,
+        );
+        match (action, resource) {
+            (Some(false), _) | (_, Some(false)) => Some(false),
+            (Some(true), Some(true)) => Some(true),
+            _ => None,
+        }
+    }
+}
+
+/// Whether a statement element matches `value`: one of `patterns` must match it, or, when the
+/// element is negated, none of `not_patterns` may.
+fn applies(
+    patterns: &[String],
+    not_patterns: &[String],
+    value: &str,
+    context: &PolicyContext,
+    ignore_case: bool,
+) -> Option<bool> {
+    if !patterns.is_empty() || not_patterns.is_empty() {
+        return Some(
+            patterns
+                .iter()
+                .any(|pattern| matches_pattern(pattern, value, context, ignore_case)),
+        );
+    }
+    if not_patterns
+        .iter()
+        .any(|pattern| matches_pattern(pattern, value, context, ignore_case))
+    {
+        return Some(false);
+    }
+    // A pattern that cannot be resolved matches nothing, which would make the statement apply.
+    not_patterns
+        .iter()
+        .all(|pattern| tokenize(pattern, context).is_some())
+        .then_some(true)
+}
+
+/// Accepts either a single value or an array of them, as policy documents do.
+fn one_or_many<'de, D, T>(deserializer: D) -> std::result::Result<Vec<T>, D::Error>
+where
+    D: Deserializer<'de>,
+    T: Deserialize<'de>,
+{
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum OneOrMany<T> {
+        One(T),
+        Many(Vec<T>),
+    }
+    Ok(match OneOrMany::deserialize(deserializer)? {
+        OneOrMany::One(value) => vec![value],
+        OneOrMany::Many(values) => values,
+    })
+}
+
+/// MQTT operation a certificate can be allowed to perform.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum IotAction {
+    Connect,
+    Publish,
+    Subscribe,
+    Receive,
+}
+
+impl IotAction {
+    /// The action as written in policies.
+    pub fn action(&self) -> &'static str {
+        match self {
+            IotAction::Connect => "iot:Connect",
+            IotAction::Publish => "iot:Publish",
+            IotAction::Subscribe => "iot:Subscribe",
+            IotAction::Receive => "iot:Receive",
+        }
+    }
+
+    /// The resource type the action applies to, e.g. `topic` in `...:topic/sensors/temp`.
+    fn resource_type(&self) -> &'static str {
+        match self {
+            IotAction::Connect => "client",
+            IotAction::Publish | IotAction::Receive => "topic",
+            IotAction::Subscribe => "topicfilter",
+        }
+    }
+}
+
+impl fmt::Display for IotAction {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.action())
+    }
+}
+
+impl FromStr for IotAction {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s.trim_start_matches("iot:").to_ascii_lowercase().as_str() {
+            "connect" => Ok(IotAction::Connect),
+            "publish" => Ok(IotAction::Publish),
+            "subscribe" => Ok(IotAction::Subscribe),
+            "receive" => Ok(IotAction::Receive),
+            _ => Err(Error::InvalidRequest(format!(
+                "Unknown action {s}, expected connect, publish, subscribe or receive"
+            ))),
+        }
+    }
+}
+
+/// Question to a policy evaluator, such as publishing to a topic.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct PermissionRequest {
+    /// The MQTT operation.
+    pub action: IotAction,
+    /// The topic, topic filter or client ID. Empty for connecting with the thing's client ID.
+    pub target: String,
+}
+
+impl FromStr for PermissionRequest {
+    type Err = Error;
+
+    /// Parses `<action> <target>`, e.g. `publish sensors/temp` or `subscribe alerts/#`.
+    fn from_str(s: &str) -> Result<Self> {
+        let (action, target) = s.trim().split_once(' ').unwrap_or((s.trim(), ""));
+        Ok(Self {
+            action: action.parse()?,
+            target: target.trim().to_string(),
+        })
+    }
+}
+
+/// Connection the policies are evaluated for, which resolves the policy variables.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct PolicyContext {
+    /// The region of the IoT endpoint.
+    pub region: String,
+    /// The AWS account ID.
+    pub account_id: String,
+    /// The MQTT client ID, for `${iot:ClientId}`.
+    pub client_id: String,
+    /// The thing the certificate is attached to, for `${iot:Connection.Thing.ThingName}`.
+    pub thing_name: Option<String>,
+    /// The thing type, for `${iot:Connection.Thing.ThingTypeName}`.
+    pub thing_type_name: Option<String>,
+    /// The thing's attributes, for `${iot:Connection.Thing.Attributes[name]}`.
+    pub attributes: BTreeMap<String, String>,
+}
+
+impl PolicyContext {
+    /// The ARN of the resource a request applies to.
+    pub fn resource_arn(&self, request: &PermissionRequest) -> String {
+        let target = match request.action {
+            IotAction::Connect if request.target.is_empty() => &self.client_id,
+            _ => &request.target,
+        };
+        format!(
+            "arn:aws:iot:{}:{}:{}/{}",
+            self.region,
+            self.account_id,
+            request.action.resource_type(),
+            target
+        )
+    }
+
+    /// The value of a policy variable, `None` if it cannot be resolved for this connection.
+    fn variable(&self, name: &str) -> Option<String> {
+        if let Some(attribute) = name
+            .strip_prefix("iot:Connection.Thing.Attributes[")
+            .and_then(|rest| rest.strip_suffix(']'))
+        {
+            return self.attributes.get(attribute).cloned();
+        }
+        match name {
+            "iot:ClientId" => Some(self.client_id.clone()),
+            "iot:Connection.Thing.ThingName" => self.thing_name.clone(),
+            "iot:Connection.Thing.ThingTypeName" => self.thing_type_name.clone(),
+            "iot:Connection.Thing.IsAttached" => Some(self.thing_name.is_some().to_string()),
+            "aws:Region" => Some(self.region.clone()),
+            "aws:AccountId" => Some(self.account_id.clone()),
+            _ => None,
+        }
+    }
+}
+
+/// Part of a resource or action pattern.
+#[derive(Clone, Debug, PartialEq)]
+enum Token {
+    Literal(char),
+    /// `*`, any run of characters.
+    AnyRun,
+    /// `?`, any single character.
+    AnyChar,
+}
+
+/// Splits a pattern into tokens, resolving policy variables.
+///
+/// `${*}`, `${?}` and `${$}` stand for the literal characters. Returns `None` if a variable
+/// cannot be resolved, in which case the pattern matches nothing.
+fn tokenize(pattern: &str, context: &PolicyContext) -> Option<Vec<Token>> {
+    let mut tokens = Vec::new();
+    let mut rest = pattern;
+    while let Some(c) = rest.chars().next() {
+        if let Some(variable) = rest.strip_prefix("${") {
+            let end = variable.find('}')?;
+            let name = &variable[..end];
+            match name {
+                "*" | "?" | "$" => tokens.extend(name.chars().map(Token::Literal)),
+                _ => tokens.extend(context.variable(name)?.chars().map(Token::Literal)),
+            }
+            rest = &variable[end + 1..];
+            continue;
+        }
+        tokens.push(match c {
+            '*' => Token::AnyRun,
+            '?' => Token::AnyChar,
+            c => Token::Literal(c),
+        });
+        rest = &rest[c.len_utf8()..];
+    }
+    Some(tokens)
+}
+
+/// Whether `value` matches the tokens of a pattern.
+fn matches_tokens(tokens: &[Token], value: &[char], ignore_case: bool) -> bool {
+    // matched[j] is whether the tokens seen so far match the first j characters.
+    let mut matched = vec![false; value.len() + 1];
+    matched[0] = true;
+    for token in tokens {
+        let mut next = vec![false; value.len() + 1];
+        for j in 0..=value.len() {
+            next[j] = match token {
+                Token::AnyRun => matched[j] || (j > 0 && next[j - 1]),
+                Token::AnyChar => j > 0 && matched[j - 1],
+                Token::Literal(c) => {
+                    j > 0
+                        && matched[j - 1]
+                        && if ignore_case {
+                            c.eq_ignore_ascii_case(&value[j - 1])
+                        } else {
+                            *c == value[j - 1]
+                        }
+                }
+            };
+        }
+        matched = next;
+    }
+    matched[value.len()]
+}
+
+/// Whether `value` matches a case-sensitive pattern with `*` and `?` wildcards.
+pub(crate) fn matches_wildcards(pattern: &str, value: &str) -> bool {
+    let tokens: Vec<Token> = pattern
+        .chars()
+        .map(|c| match c {
+            '*' => Token::AnyRun,
+            '?' => Token::AnyChar,
+            c => Token::Literal(c),
+        })
+        .collect();
+    let value: Vec<char> = value.chars().collect();
+    matches_tokens(&tokens, &value, false)
+}
+
+fn matches_pattern(pattern: &str, value: &str, context: &PolicyContext, ignore_case: bool) -> bool {
+    let value: Vec<char> = value.chars().collect();
+    tokenize(pattern, context).is_some_and(|tokens| matches_tokens(&tokens, &value, ignore_case))
+}
+
+/// Statement of a policy, identified by its position in the document.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct StatementRef {
+    /// The name of the policy.
+    pub policy_name: String,
+    /// The index of the statement in the document.
+    pub index: usize,
+}
+
+impl fmt::Display for StatementRef {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} statement {}", self.policy_name, self.index + 1)
+    }
+}
+
+/// Outcome of evaluating a request against a set of policies.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Decision {
+    /// A statement allows the request and none denies it.
+    Allowed(StatementRef),
+    /// A statement explicitly denies the request.
+    Denied(StatementRef),
+    /// No statement allows the request.
+    NotAllowed,
+}
+
+impl Decision {
+    /// Whether the request is allowed.
+    pub fn is_allowed(&self) -> bool {
+        matches!(self, Decision::Allowed(_))
+    }
+}
+
+impl fmt::Display for Decision {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Decision::Allowed(statement) => write!(f, "Allowed by {statement}"),
+            Decision::Denied(statement) => write!(f, "Denied by {statement}"),
+            Decision::NotAllowed => write!(f, "Denied, no statement allows it"),
+        }
+    }
+}
+
+/// Result of evaluating a request against the policies of a principal.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Evaluation {
+    /// The request.
+    pub request: PermissionRequest,
+    /// The resource ARN the request was evaluated for.
+    pub resource: String,
+    /// Whether the request is allowed, and by which statement.
+    pub decision: Decision,
+    /// Statements that may apply but cannot be evaluated locally: those with conditions, and
+    /// those whose `NotAction` or `NotResource` patterns cannot be resolved for the connection.
+    pub unevaluated: Vec<StatementRef>,
+}
+
+/// Evaluates a request like IoT Core does: an explicit deny wins over any allow, and
+/// anything not allowed is denied.
+///
+/// Statements with conditions, or with `NotAction` or `NotResource` patterns that cannot be
+/// resolved, are left out of the decision and listed in [`Evaluation::unevaluated`].
+pub fn evaluate(
+    policies: &[Policy],
+    context: &PolicyContext,
+    request: &PermissionRequest,
+) -> Result<Evaluation> {
+    let resource = context.resource_arn(request);
+    let action = request.action.action();
+    let mut allowed = None;
+    let mut denied = None;
+    let mut unevaluated = Vec::new();
+
+    for policy in policies {
+        for (index, statement) in policy.document()?.statements.iter().enumerate() {
+            let applies = statement.applies_to(action, &resource, context);
+            if applies == Some(false) {
+                continue;
+            }
+            let statement_ref = StatementRef {
+                policy_name: policy.policy_name.clone(),
+                index,
+            };
+            if applies.is_none() || statement.condition.is_some() {
+                unevaluated.push(statement_ref);
+                continue;
+            }
+            match statement.effect {
+                Effect::Deny => {
+                    denied.get_or_insert(statement_ref);
+                }
+                Effect::Allow => {
+                    allowed.get_or_insert(statement_ref);
+                }
+            }
+        }
+    }
+
+    let decision = match (denied, allowed) {
+        (Some(statement), _) => Decision::Denied(statement),
+        (None, Some(statement)) => Decision::Allowed(statement),
+        (None, None) => Decision::NotAllowed,
+    };
+    Ok(Evaluation {
+        request: request.clone(),
+        resource,
+        decision,
+        unevaluated,
+    })
+}
+
+/// Policies attached to a principal such as a certificate.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct PrincipalPolicies {
+    /// The ARN of the principal.
+    pub principal: String,
+    /// The attached policies.
+    pub policies: Vec<Policy>,
+}
+
+impl PrincipalPolicies {
+    /// The ID of the certificate, or the whole ARN for other principals.
+    pub fn name(&self) -> &str {
+        certificate_id(&self.principal).unwrap_or(&self.principal)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde::Deserialize;
+
+    use super::*;
+
+    #[derive(Deserialize)]
+    struct Fixture {
+        policies: Vec<Policy>,
+    }
+
+    fn policies(names: &[&str]) -> Vec<Policy> {
+        let fixture: Fixture =
+            serde_yaml::from_str(include_str!("../fixtures/fleet.yaml")).unwrap();
+        names
+            .iter()
+            .map(|name| {
+                fixture
+                    .policies
+                    .iter()
+                    .find(|policy| policy.policy_name == *name)
+                    .unwrap()
+                    .clone()
+            })
+            .collect()
+    }
+
+    fn context(thing_name: Option<&str>) -> PolicyContext {
+        PolicyContext {
+            region: String::from("eu-west-1"),
+            account_id: String::from("123456789012"),
+            client_id: String::from("lon-gw-002"),
+            thing_name: thing_name.map(str::to_string),
+            thing_type_name: Some(String::from("greengrass-gateway")),
+            attributes: BTreeMap::from([(
+                String::from("site"),
+                String::from("london-kings-cross"),
+            )]),
+        }
+    }
+
+    fn decide(names: &[&str], thing_name: Option<&str>, request: &str) -> Evaluation {
+        evaluate(
+            &policies(names),
+            &context(thing_name),
+            &request.parse().unwrap(),
+        )
+        .unwrap()
+    }
+
+    fn statement(policy_name: &str, index: usize) -> StatementRef {
+        StatementRef {
+            policy_name: policy_name.to_string(),
+            index,
+        }
+    }
+
+    #[test]
+    fn allows_own_topics_and_resolves_variables() {
+        let names = ["GreengrassCoreTokenExchange", "GatewayMqtt"];
+        let evaluation = decide(
+            &names,
+            Some("lon-gw-002"),
+            "publish things/lon-gw-002/telemetry",
+        );
+        assert_eq!(
+            evaluation.decision,
+            Decision::Allowed(statement("GatewayMqtt", 1))
+        );
+        assert_eq!(
+            evaluation.resource,
+            "arn:aws:iot:eu-west-1:123456789012:topic/things/lon-gw-002/telemetry"
+        );
+
+        let evaluation = decide(
+            &names,
+            Some("lon-gw-002"),
+            "subscribe sites/london-kings-cross/#",
+        );
+        assert_eq!(
+            evaluation.decision,
+            Decision::Allowed(statement("GatewayMqtt", 2))
+        );
+
+        let evaluation = decide(&names, Some("lon-gw-002"), "connect");
+        assert!(evaluation.decision.is_allowed());
+    }
+
+    #[test]
+    fn explicit_deny_wins_over_allow() {
+        let names = ["GatewayMqtt"];
+        let evaluation = decide(
+            &names,
+            Some("lon-gw-002"),
+            "publish things/lon-gw-002/commands",
+        );
+        assert_eq!(
+            evaluation.decision,
+            Decision::Denied(statement("GatewayMqtt", 3))
+        );
+    }
+
+    #[test]
+    fn denies_what_no_statement_allows() {
+        let names = ["GatewayMqtt"];
+        let evaluation = decide(
+            &names,
+            Some("lon-gw-002"),
+            "publish things/man-gw-001/telemetry",
+        );
+        assert_eq!(evaluation.decision, Decision::NotAllowed);
+
+        // Without a thing the variables cannot be resolved, so their patterns match nothing.
+        let evaluation = decide(&names, None, "publish things/lon-gw-002/telemetry");
+        assert_eq!(evaluation.decision, Decision::NotAllowed);
+    }
+
+    #[test]
+    fn not_resource_denies_everything_else() {
+        let names = ["LegacyWildcard", "OwnTopicsOnly"];
+        let evaluation = decide(&names, Some("lon-gw-002"), "publish alerts/fire");
+        assert_eq!(
+            evaluation.decision,
+            Decision::Denied(statement("OwnTopicsOnly", 0))
+        );
+
+        let evaluation = decide(
+            &names,
+            Some("lon-gw-002"),
+            "publish things/lon-gw-002/telemetry",
+        );
+        assert_eq!(
+            evaluation.decision,
+            Decision::Allowed(statement("LegacyWildcard", 0))
+        );
+
+        // The deny is on publishing only.
+        let evaluation = decide(&names, Some("lon-gw-002"), "subscribe alerts/#");
+        assert!(evaluation.decision.is_allowed());
+    }
+
+    #[test]
+    fn unresolved_not_resource_cannot_be_determined() {
+        let names = ["LegacyWildcard", "OwnTopicsOnly"];
+        let evaluation = decide(&names, None, "publish alerts/fire");
+        assert_eq!(
+            evaluation.decision,
+            Decision::Allowed(statement("LegacyWildcard", 0))
+        );
+        assert_eq!(evaluation.unevaluated, vec![statement("OwnTopicsOnly", 0)]);
+    }
+
+    #[test]
+    fn not_action_applies_to_other_actions() {
+        let policy = Policy {
+            policy_name: String::from("NoSubscribe"),
+            policy_arn: String::new(),
+            policy_document: String::from(
+                r#"{"Statement": [{"Effect": "Allow", "NotAction": "iot:Subscribe", "Resource": "*"}]}"#,
+            ),
+        };
+        let context = context(Some("lon-gw-002"));
+        let evaluate = |request: &str| {
+            evaluate(
+                std::slice::from_ref(&policy),
+                &context,
+                &request.parse().unwrap(),
+            )
+            .unwrap()
+        };
+        assert!(evaluate("publish alerts/fire").decision.is_allowed());
+        assert_eq!(
+            evaluate("subscribe alerts/#").decision,
+            Decision::NotAllowed
+        );
+    }
+
+    #[test]
+    fn parses_requests() {
+        let request: PermissionRequest = "Subscribe alerts/#".parse().unwrap();
+        assert_eq!(request.action, IotAction::Subscribe);
+        assert_eq!(request.target, "alerts/#");
+        assert!("shout alerts".parse::<PermissionRequest>().is_err());
+    }
+
+    #[test]
+    fn wildcards_escape_and_match() {
+        assert!(matches_wildcards("lon-*", "lon-gw-001"));
+        assert!(matches_wildcards("lon-gw-00?", "lon-gw-001"));
+        assert!(!matches_wildcards("lon-gw-00?", "lon-gw-0010"));
+        let context = context(None);
+        assert!(matches_pattern("topic/${*}", "topic/*", &context, false));
+        assert!(!matches_pattern("topic/${*}", "topic/a", &context, false));
+        assert!(matches_pattern(
+            "IOT:publish",
+            "iot:Publish",
+            &context,
+            true
+        ));
+    }
+}
@@ -0,0 +1,931 @@
+use std::{collections::BTreeMap, fmt};
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::{Error, Result, Version, VersionRequirement};
+
+/// The only recipe format version Greengrass accepts.
+pub const RECIPE_FORMAT_VERSION: &str = "2020-01-25";
+
+const COMPONENT_TYPES: [&str; 4] = [
+    "aws.greengrass.generic",
+    "aws.greengrass.lambda",
+    "aws.greengrass.plugin",
+    "aws.greengrass.nucleus",
+];
+
+/// Greengrass component recipe.
+///
+/// See <https://docs.aws.amazon.com/greengrass/v2/developerguide/component-recipe-reference.html>.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+pub struct Recipe {
+    /// The template version of the recipe, always `2020-01-25`.
+    pub recipe_format_version: String,
+    /// The name of the component.
+    pub component_name: String,
+    /// The semantic version of the component.
+    pub component_version: String,
+    /// The type of the component, e.g. `aws.greengrass.generic`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub component_type: Option<String>,
+    /// The description of the component.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub component_description: Option<String>,
+    /// The publisher of the component.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub component_publisher: Option<String>,
+    /// The ARN of the Lambda function the component was created from.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub component_source: Option<String>,
+    /// The configuration of the component.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub component_configuration: Option<ComponentConfiguration>,
+    /// The components this component depends on, keyed by component name.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub component_dependencies: BTreeMap<String, ComponentDependency>,
+    /// The platform specific instructions of the component.
+    #[serde(default)]
+    pub manifests: Vec<Manifest>,
+    /// Lifecycles keyed by selection, referenced from [`Manifest::selections`].
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub lifecycle: BTreeMap<String, Lifecycle>,
+}
+
+/// Configuration section of a recipe.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+pub struct ComponentConfiguration {
+    /// The configuration deployed when no configuration update replaces it.
+    #[serde(default)]
+    pub default_configuration: Value,
+}
+
+/// Dependency on another component.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+pub struct ComponentDependency {
+    /// npm-style semantic version range, e.g. `>=2.0.0 <3.0.0`.
+    pub version_requirement: String,
+    /// Whether the component restarts when the dependency changes state.
+    #[serde(default)]
+    pub dependency_type: DependencyType,
+}
+
+/// How a dependency affects the component's lifecycle.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "UPPERCASE")]
+pub enum DependencyType {
+    /// The component restarts when the dependency changes state.
+    #[default]
+    Hard,
+    /// The component does not restart when the dependency changes state.
+    Soft,
+}
+
+impl fmt::Display for DependencyType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DependencyType::Hard => write!(f, "HARD"),
+            DependencyType::Soft => write!(f, "SOFT"),
+        }
+    }
+}
+
+/// Instructions for the platforms matching [`Manifest::platform`].
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+pub struct Manifest {
+    /// An optional name for the manifest.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    /// The platforms the manifest applies to. Missing attributes match any platform.
+    #[serde(default)]
+    pub platform: Platform,
+    /// The lifecycle of the component on matching platforms.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub lifecycle: Option<Lifecycle>,
+    /// Keys of the recipe-level [`Recipe::lifecycle`] to run instead of `lifecycle`.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub selections: Vec<String>,
+    /// The artifacts to download on matching platforms.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub artifacts: Vec<Artifact>,
+}
+
+/// Platform selector of a manifest.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct Platform {
+    /// The operating system, e.g. `linux` or `windows`, or `*` for any.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub os: Option<String>,
+    /// The processor architecture, e.g. `amd64` or `aarch64`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub architecture: Option<String>,
+    /// Any other platform attributes, such as `architecture.detail`.
+    #[serde(flatten)]
+    pub attributes: BTreeMap<String, String>,
+}
+
+impl fmt::Display for Platform {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}/{}",
+            self.os.as_deref().unwrap_or("*"),
+            self.architecture.as_deref().unwrap_or("*")
+        )?;
+        for (key, value) in &self.attributes {
+            write!(f, " {key}={value}")?;
+        }
+        Ok(())
+    }
+}
+
+/// Lifecycle scripts of a component.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct Lifecycle {
+    /// Environment variables for all lifecycle scripts.
+    #[serde(
+        rename = "Setenv",
+        alias = "setenv",
+        default,
+        skip_serializing_if = "BTreeMap::is_empty"
+    )]
+    pub setenv: BTreeMap<String, String>,
+    /// Runs before the nucleus installs the component and may restart it.
+    #[serde(
+        rename = "Bootstrap",
+        alias = "bootstrap",
+        default,
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub bootstrap: Option<LifecycleStep>,
+    /// Installs the component.
+    #[serde(
+        rename = "Install",
+        alias = "install",
+        default,
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub install: Option<LifecycleStep>,
+    /// Runs the component as a long-running process.
+    #[serde(
+        rename = "Run",
+        alias = "run",
+        default,
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub run: Option<LifecycleStep>,
+    /// Starts a background process that keeps running after the script exits.
+    #[serde(
+        rename = "Startup",
+        alias = "startup",
+        default,
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub startup: Option<LifecycleStep>,
+    /// Runs when the component shuts down.
+    #[serde(
+        rename = "Shutdown",
+        alias = "shutdown",
+        default,
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub shutdown: Option<LifecycleStep>,
+    /// Runs when the component encounters an error.
+    #[serde(
+        rename = "Recover",
+        alias = "recover",
+        default,
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub recover: Option<LifecycleStep>,
+    /// Unrecognised keys, which [`Recipe::validate`] reports.
+    #[serde(flatten)]
+    pub unknown: BTreeMap<String, Value>,
+}
+
+impl Lifecycle {
+    /// The steps that are set, in the order the nucleus runs them.
+    pub fn steps(&self) -> Vec<(&'static str, &LifecycleStep)> {
+        [
+            ("Bootstrap", &self.bootstrap),
+            ("Install", &self.install),
+            ("Startup", &self.startup),
+            ("Run", &self.run),
+            ("Shutdown", &self.shutdown),
+            ("Recover", &self.recover),
+        ]
+        .into_iter()
+        .filter_map(|(name, step)| step.as_ref().map(|step| (name, step)))
+        .collect()
+    }
+}
+
+/// A lifecycle script, either inline or with options.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum LifecycleStep {
+    /// The script to run.
+    Script(String),
+    /// The script to run along with its options.
+    Detailed(LifecycleScript),
+}
+
+impl LifecycleStep {
+    /// The script to run.
+    pub fn script(&self) -> &str {
+        match self {
+            LifecycleStep::Script(script) => script,
+            LifecycleStep::Detailed(detailed) => &detailed.script,
+        }
+    }
+}
+
+/// Lifecycle script with options.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+pub struct LifecycleScript {
+    /// The script to run.
+    pub script: String,
+    /// Whether the script runs as root or administrator.
+    #[serde(default)]
+    pub requires_privilege: bool,
+    /// Check that skips the script when it passes, e.g. `onpath git`.
+    #[serde(rename = "Skipif", default, skip_serializing_if = "Option::is_none")]
+    pub skip_if: Option<String>,
+    /// The maximum time in seconds the script may run for.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub timeout: Option<u64>,
+    /// Environment variables for the script.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub setenv: BTreeMap<String, String>,
+}
+
+/// Artifact downloaded to core devices.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+pub struct Artifact {
+    /// The location of the artifact, an `s3://`, `docker:` or `greengrass:` URI.
+    #[serde(rename = "URI", alias = "Uri")]
+    pub uri: String,
+    /// The digest of the artifact.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub digest: Option<String>,
+    /// The hash algorithm of the digest, e.g. `SHA-256`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub algorithm: Option<String>,
+    /// Whether to unpack the artifact.
+    #[serde(default)]
+    pub unarchive: Unarchive,
+    /// File permissions of the downloaded artifact.
+    #[serde(default)]
+    pub permission: Permission,
+}
+
+/// Archive type of an artifact.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "UPPERCASE")]
+pub enum Unarchive {
+    /// Keep the artifact as is.
+    #[default]
+    None,
+    /// Unpack a ZIP archive.
+    Zip,
+}
+
+impl fmt::Display for Unarchive {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Unarchive::None => write!(f, "NONE"),
+            Unarchive::Zip => write!(f, "ZIP"),
+        }
+    }
+}
+
+/// File permissions of an artifact.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default, rename_all = "PascalCase")]
+pub struct Permission {
+    /// Who may read the file.
+    pub read: PermissionLevel,
+    /// Who may execute the file.
+    pub execute: PermissionLevel,
+}
+
+impl Default for Permission {
+    fn default() -> Self {
+        Self {
+            read: PermissionLevel::Owner,
+            execute: PermissionLevel::None,
+        }
+    }
+}
+
+/// Who an artifact permission applies to.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "UPPERCASE")]
+pub enum PermissionLevel {
+    /// Nobody.
+    #[default]
+    None,
+    /// The user the component runs as.
+    Owner,
+    /// All users.
+    All,
+}
+
+impl fmt::Display for PermissionLevel {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PermissionLevel::None => write!(f, "NONE"),
+            PermissionLevel::Owner => write!(f, "OWNER"),
+            PermissionLevel::All => write!(f, "ALL"),
+        }
+    }
+}
+
+/// Format of a recipe document.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum RecipeFormat {
+    Json,
+    Yaml,
+}
+
+impl RecipeFormat {
+    /// Guesses the format from the document, JSON recipes being objects.
+    pub fn detect(source: &str) -> Self {
+        if source.trim_start().starts_with('{') { RecipeFormat::Json } else { RecipeFormat::Yaml }
+    }
+}
+
+/// 1-based position in a recipe document.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Position {
+    pub line: usize,
+    pub column: usize,
+}
+
+/// How serious a recipe issue is.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum IssueSeverity {
+    /// Greengrass rejects the recipe.
+    #[default]
+    Error,
+    /// Greengrass accepts the recipe, but it is probably not what was meant.
+    Warning,
+}
+
+/// Problem found while parsing or validating a recipe.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct RecipeIssue {
+    /// Where the problem is, if it could be located.
+    pub position: Option<Position>,
+    /// Whether the problem makes the recipe invalid.
+    pub severity: IssueSeverity,
+    /// What is wrong.
+    pub message: String,
+}
+
+impl RecipeIssue {
+    /// Whether Greengrass rejects the recipe because of this issue.
+    pub fn is_error(&self) -> bool {
+        self.severity == IssueSeverity::Error
+    }
+}
+
+impl fmt::Display for RecipeIssue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if let Some(Position { line, column }) = self.position {
+            write!(f, "{line}:{column}: ")?;
+        }
+        if self.severity == IssueSeverity::Warning {
+            write!(f, "warning: ")?;
+        }
+        write!(f, "{}", self.message)
+    }
+}
+
+/// Step of the path from the top of a recipe document to a key.
+#[derive(Clone, Copy, Debug)]
+enum Segment<'a> {
+    /// A key of a mapping.
+    Key(&'a str),
+    /// An item of a list.
+    Index(usize),
+}
+
+impl Recipe {
+    /// Parses and validates a JSON or YAML recipe, detecting the format.
+    pub fn parse(source: &str) -> Result<Self> {
+        Self::parse_as(source, RecipeFormat::detect(source))
+    }
+
+    /// Parses and validates a recipe in the given format.
+    ///
+    /// Returns [`Error::InvalidRecipe`] with every problem found if any of them is an error.
+    /// Warnings alone do not fail, see [`Recipe::validate`].
+    pub fn parse_as(source: &str, format: RecipeFormat) -> Result<Self> {
+        let recipe = Self::deserialize(source, format)?;
+
+        let issues = recipe.validate(source);
+        if issues.iter().any(RecipeIssue::is_error) {
+            Err(Error::InvalidRecipe(issues))
+        } else {
+            Ok(recipe)
+        }
+    }
+
+    /// Parses a recipe in the given format without validating it.
+    pub(crate) fn deserialize(source: &str, format: RecipeFormat) -> Result<Self> {
+        let parsed = match format {
+            RecipeFormat::Json => serde_json::from_str::<Recipe>(source).map_err(|e| RecipeIssue {
+                position: (e.line() > 0).then(|| Position {
+                    line: e.line(),
+                    column: e.column(),
+                }),
+                severity: IssueSeverity::Error,
+                message: without_location(e.to_string()),
+            }),
+            RecipeFormat::Yaml => serde_yaml::from_str::<Recipe>(source).map_err(|e| RecipeIssue {
+                position: e.location().map(|l| Position {
+                    line: l.line(),
+                    column: l.column(),
+                }),
+                severity: IssueSeverity::Error,
+                message: without_location(e.to_string()),
+            }),
+        };
+        parsed.map_err(|issue| Error::InvalidRecipe(vec![issue]))
+    }
+
+    /// Checks the recipe against the rules Greengrass applies when creating a component.
+    ///
+    /// `source` is the document the recipe was parsed from and is only used to locate issues.
+    /// Each issue is located by its path in the document, so a key repeated in several
+    /// manifests or artifacts points at the one that is wrong.
+    pub fn validate(&self, source: &str) -> Vec<RecipeIssue> {
+        use Segment::{Index, Key};
+
+        let mut issues = Vec::new();
+        let mut warnings = Vec::new();
+        let mut issue = |path: &[Segment], value: Option<&str>, message: String| {
+            issues.push(RecipeIssue {
+                position: locate(source, path, value),
+                severity: IssueSeverity::Error,
+                message,
+            });
+        };
+        let warning = |path: &[Segment], message: String| RecipeIssue {
+            position: locate(source, path, None),
+            severity: IssueSeverity::Warning,
+            message,
+        };
+
+        if self.recipe_format_version != RECIPE_FORMAT_VERSION {
+            issue(
+                &[Key("RecipeFormatVersion")],
+                Some(&self.recipe_format_version),
+                format!(
+                    "RecipeFormatVersion must be {RECIPE_FORMAT_VERSION}, got `{}`",
+                    self.recipe_format_version
+                ),
+            );
+        }
+        if self.component_name.trim().is_empty() {
+            issue(
+                &[Key("ComponentName")],
+                None,
+                String::from("ComponentName must not be empty"),
+            );
+        }
+        if self.component_version.parse::<Version>().is_err() {
+            issue(
+                &[Key("ComponentVersion")],
+                Some(&self.component_version),
+                format!(
+                    "ComponentVersion `{}` is not a semantic version such as 1.0.0",
+                    self.component_version
+                ),
+            );
+        }
+        if let Some(component_type) = &self.component_type {
+            if !COMPONENT_TYPES.contains(&component_type.as_str()) {
+                issue(
+                    &[Key("ComponentType")],
+                    Some(component_type),
+                    format!("Unknown ComponentType `{component_type}`"),
+                );
+            }
+        }
+
+        for (name, dependency) in &self.component_dependencies {
+            if dependency
+                .version_requirement
+                .parse::<VersionRequirement>()
+                .is_err()
+            {
+                issue(
+                    &[Key("ComponentDependencies"), Key(name)],
+                    Some(&dependency.version_requirement),
+                    format!(
+                        "Dependency {name} has an invalid VersionRequirement `{}`",
+                        dependency.version_requirement
+                    ),
+                );
+            }
+        }
+
+        if self.manifests.is_empty() {
+            issue(
+                &[Key("Manifests")],
+                None,
+                String::from("Recipe has no manifests"),
+            );
+        }
+        for (idx, manifest) in self.manifests.iter().enumerate() {
+            let name = manifest
+                .name
+                .clone()
+                .unwrap_or_else(|| format!("Manifests[{idx}]"));
+
+            if manifest.lifecycle.is_some() && !manifest.selections.is_empty() {
+                issue(
+                    &[Key("Manifests"), Index(idx), Key("Selections")],
+                    None,
+                    format!("{name} sets both Lifecycle and Selections"),
+                );
+            }
+            for selection in &manifest.selections {
+                if !self.lifecycle.contains_key(selection) {
+                    issue(
+                        &[Key("Manifests"), Index(idx), Key("Selections")],
+                        Some(selection),
+                        format!(
+                            "{name} selects `{selection}`, which is not in the recipe Lifecycle"
+                        ),
+                    );
+                }
+            }
+            for (jdx, artifact) in manifest.artifacts.iter().enumerate() {
+                let artifact_path = [Key("Manifests"), Index(idx), Key("Artifacts"), Index(jdx)];
+                if !["s3://", "docker:", "greengrass:"]
+                    .iter()
+                    .any(|scheme| artifact.uri.starts_with(scheme))
+                {
+                    issue(
+                        &[artifact_path.as_slice(), &[Key("URI")]].concat(),
+                        Some(&artifact.uri),
+                        format!(
+                            "Artifact `{}` must be an s3://, docker: or greengrass: URI",
+                            artifact.uri
+                        ),
+                    );
+                }
+                if artifact.digest.is_some() && artifact.algorithm.is_none() {
+                    issue(
+                        &[artifact_path.as_slice(), &[Key("Digest")]].concat(),
+                        artifact.digest.as_deref(),
+                        format!("Artifact `{}` has a Digest but no Algorithm", artifact.uri),
+                    );
+                }
+            }
+            // Greengrass ignores keys it does not know, so these are only likely typos.
+            if let Some(lifecycle) = &manifest.lifecycle {
+                for key in lifecycle.unknown.keys() {
+                    warnings.push(warning(
+                        &[Key("Manifests"), Index(idx), Key("Lifecycle"), Key(key)],
+                        format!("{name} has an unknown lifecycle step `{key}`"),
+                    ));
+                }
+            }
+        }
+        for (selection, lifecycle) in &self.lifecycle {
+            for key in lifecycle.unknown.keys() {
+                warnings.push(warning(
+                    &[Key("Lifecycle"), Key(selection), Key(key)],
+                    format!("Unknown lifecycle step `{key}` in `{selection}`"),
+                ));
+            }
+        }
+
+        issues.extend(warnings);
+        issues
+    }
+
+    /// Default configuration of the component, if any.
+    pub fn default_configuration(&self) -> Option<&Value> {
+        self.component_configuration
+            .as_ref()
+            .map(|configuration| &configuration.default_configuration)
+            .filter(|configuration| !configuration.is_null())
+    }
+
+    /// Default configuration pretty-printed as JSON.
+    pub fn default_configuration_pretty(&self) -> Option<String> {
+        self.default_configuration()
+            .and_then(|configuration| serde_json::to_string_pretty(configuration).ok())
+    }
+}
+
+/// Drops the ` at line X column Y` suffix of parser errors, which [`RecipeIssue`] shows itself.
+fn without_location(mut message: String) -> String {
+    if let Some(idx) = message.rfind(" at line ") {
+        message.truncate(idx);
+    }
+    message
+}
+
+/// Finds the line of the key at the end of `path`, preferring a line that also holds `value`.
+///
+/// The search is narrowed to the part of the document `path` leads to. This follows the
+/// layout of block YAML and pretty-printed JSON, and falls back to the closest part it could
+/// narrow down to otherwise, e.g. for flow-style lists.
+fn locate(source: &str, path: &[Segment], value: Option<&str>) -> Option<Position> {
+    let lines: Vec<&str> = source.lines().collect();
+    let (start, end) = scope(&lines, path);
+    let key = match path.last() {
+        Some(Segment::Key(key)) => *key,
+        _ => return None,
+    };
+    locate_in(&lines[start..end], key, value).map(|position| Position {
+        line: position.line + start,
+        ..position
+    })
+}
+
+/// Range of lines holding the key at the end of `path` and its value, or the range of the
+/// last part of `path` that could be found.
+fn scope(lines: &[&str], path: &[Segment]) -> (usize, usize) {
+    // Lines holding the children of the current node; the line of a key holds the key itself.
+    let mut children = (0, lines.len());
+    let mut range = children;
+
+    for segment in path {
+        let found = match *segment {
+            Segment::Key(key) => {
+                find_key(lines, children, key).map(|range| (range, (range.0 + 1, range.1)))
+            }
+            Segment::Index(index) => find_item(lines, children, index).map(|range| (range, range)),
+        };
+        let Some((next, next_children)) = found else {
+            break;
+        };
+        range = next;
+        children = next_children;
+    }
+    range
+}
+
+/// Range of the entry of `key` among the keys directly within `lines[start..end]`.
+fn find_key(lines: &[&str], (start, end): (usize, usize), key: &str) -> Option<(usize, usize)> {
+    // The keys of a mapping line up, so the first key sets the column of its siblings.
+    let column = (start..end).find_map(|idx| key_column(lines[idx]))?;
+    let line = (start..end).find(|&idx| {
+        key_column(lines[idx]) == Some(column) && key_name(lines[idx], column) == Some(key)
+    })?;
+    let stop = (line + 1..end)
+        .find(|&idx| indent(lines[idx]).is_some_and(|indent| indent <= column))
+        .unwrap_or(end);
+    Some((line, stop))
+}
+
+/// Range of the item at `index` of the list within `lines[start..end]`.
+fn find_item(lines: &[&str], (start, end): (usize, usize), index: usize) -> Option<(usize, usize)> {
+    let column = (start..end).filter_map(|idx| indent(lines[idx])).min()?;
+    // Closing brackets of JSON items line up with their opening ones.
+    let starts: Vec<usize> = (start..end)
+        .filter(|&idx| indent(lines[idx]) == Some(column))
+        .filter(|&idx| !lines[idx].trim_start().starts_with(['}', ']']))
+        .collect();
+    let line = *starts.get(index)?;
+    Some((line, starts.get(index + 1).copied().unwrap_or(end)))
+}
+
+/// Column of the first character of a line, `None` for blank lines and comments.
+fn indent(line: &str) -> Option<usize> {
+    let trimmed = line.trim_start();
+    (!trimmed.is_empty() && !trimmed.starts_with('#')).then(|| line.len() - trimmed.len())
+}
+
+/// Column of the mapping key a line starts with, after any YAML list dash.
+fn key_column(line: &str) -> Option<usize> {
+    let mut column = indent(line)?;
+    while let Some(rest) = line[column..].strip_prefix("- ") {
+        column += 2 + (rest.len() - rest.trim_start().len());
+    }
+    key_name(line, column).map(|_| column)
+}
+
+/// The mapping key starting at `column` of a line, without quotes.
+fn key_name(line: &str, column: usize) -> Option<&str> {
+    let rest = line.get(column..)?;
+    let (name, after) = match rest.chars().next()? {
+        quote @ ('"' | '\'') => {
+            let end = rest[1..].find(quote)? + 1;
+            (&rest[1..end], rest[end + 1..].trim_start())
+        }
+        _ => {
+            let end = rest.find(':')?;
+            let name = &rest[..end];
+            if name.is_empty() || name.contains(char::is_whitespace) {
+                return None;
+            }
+            (name, &rest[end..])
+        }
+    };
+    let value = after.strip_prefix(':')?;
+    (value.is_empty() || value.starts_with(char::is_whitespace)).then_some(name)
+}
+
+/// Finds the line of a key among `lines`, preferring a line that also holds `value`.
+fn locate_in(lines: &[&str], key: &str, value: Option<&str>) -> Option<Position> {
+    let key_at = |line: &str| {
+        [format!("\"{key}\""), format!("{key}:"), format!("'{key}'")]
+            .iter()
+            .find_map(|pattern| line.find(pattern.as_str()))
+    };
+    let mut first = None;
+
+    for (idx, line) in lines.iter().enumerate() {
+        let Some(column) = key_at(line) else {
+            continue;
+        };
+        let position = Position {
+            line: idx + 1,
+            column: column + 1,
+        };
+        match value {
+            Some(value) if line.contains(value) => return Some(position),
+            Some(_) => {
+                first.get_or_insert(position);
+            }
+            None => return Some(position),
+        }
+    }
+
+    // Values such as list entries sit on their own line below the key.
+    value
+        .and_then(|value| {
+            lines.iter().enumerate().find_map(|(idx, line)| {
+                line.find(value).map(|column| Position {
+                    line: idx + 1,
+                    column: column + 1,
+                })
+            })
+        })
+        .or(first)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SENSOR_BRIDGE: &str = "\
+RecipeFormatVersion: '2020-01-25'
+ComponentName: com.example.SensorBridge
+ComponentVersion: '1.4.2'
+ComponentConfiguration:
+  DefaultConfiguration:
+    pollIntervalSeconds: 60
+ComponentDependencies:
+  aws.greengrass.Nucleus:
+    VersionRequirement: '>=2.12.0 <2.15.0'
+    DependencyType: SOFT
+Manifests:
+  - Platform:
+      os: linux
+      architecture: aarch64
+    Lifecycle:
+      Install:
+        Script: pip3 install -r requirements.txt
+        RequiresPrivilege: true
+      Run: python3 -u main.py
+    Artifacts:
+      - URI: s3://example-artifacts/sensor-bridge/1.4.2/bridge.zip
+        Unarchive: ZIP
+";
+
+    fn issues(source: &str) -> Vec<RecipeIssue> {
+        match Recipe::parse(source) {
+            Err(Error::InvalidRecipe(issues)) => issues,
+            other => panic!("expected issues, got {other:?}"),
+        }
+    }
+
+    fn at(line: usize, column: usize) -> Option<Position> {
+        Some(Position { line, column })
+    }
+
+    #[test]
+    fn parses_valid_recipes() {
+        let recipe = Recipe::parse(SENSOR_BRIDGE).unwrap();
+        assert_eq!(recipe.component_name, "com.example.SensorBridge");
+        assert_eq!(
+            recipe.component_dependencies["aws.greengrass.Nucleus"].dependency_type,
+            DependencyType::Soft
+        );
+        assert_eq!(recipe.manifests[0].platform.to_string(), "linux/aarch64");
+        let steps: Vec<&str> = recipe.manifests[0]
+            .lifecycle
+            .as_ref()
+            .unwrap()
+            .steps()
+            .iter()
+            .map(|(name, _)| *name)
+            .collect();
+        assert_eq!(steps, ["Install", "Run"]);
+        assert_eq!(
+            recipe.default_configuration(),
+            Some(&serde_json::json!({ "pollIntervalSeconds": 60 }))
+        );
+        assert!(recipe.validate(SENSOR_BRIDGE).is_empty());
+
+        let json = serde_json::to_string_pretty(&recipe).unwrap();
+        assert_eq!(RecipeFormat::detect(&json), RecipeFormat::Json);
+        assert_eq!(Recipe::parse(&json).unwrap(), recipe);
+    }
+
+    #[test]
+    fn reports_parse_errors_with_their_position() {
+        let yaml = issues("RecipeFormatVersion: '2020-01-25'\nComponentName: [a\n");
+        assert_eq!(yaml.len(), 1);
+        assert!(yaml[0].position.is_some());
+        assert!(!yaml[0].message.contains(" at line "));
+
+        let json = issues("{\n  \"ComponentName\": 1\n}");
+        assert_eq!(json[0].position.map(|position| position.line), Some(2));
+    }
+
+    #[test]
+    fn locates_issues_by_their_path() {
+        let source = "\
+RecipeFormatVersion: '2020-01-25'
+ComponentName: com.example.Broken
+ComponentVersion: '1.0'
+Manifests:
+  - Platform:
+      os: linux
+    Artifacts:
+      - URI: s3://bucket/ok.zip
+        Digest: abc
+        Algorithm: SHA-256
+  - Platform:
+      os: windows
+    Artifacts:
+      - URI: s3://bucket/ok.zip
+      - URI: https://example.com/a.zip
+        Digest: abc
+";
+        let messages: Vec<String> = issues(source).iter().map(ToString::to_string).collect();
+        assert_eq!(
+            messages,
+            [
+                "3:1: ComponentVersion `1.0` is not a semantic version such as 1.0.0",
+                "15:9: Artifact `https://example.com/a.zip` must be an s3://, docker: or greengrass: URI",
+                "16:9: Artifact `https://example.com/a.zip` has a Digest but no Algorithm",
+            ]
+        );
+    }
+
+    #[test]
+    fn locates_issues_in_json() {
+        let source = r#"{
+  "RecipeFormatVersion": "2020-01-25",
+  "ComponentName": "com.example.Broken",
+  "ComponentVersion": "1.0.0",
+  "Manifests": [
+    { "Platform": { "os": "linux" }, "Selections": ["missing"] }
+  ]
+}"#;
+        let found = issues(source);
+        assert_eq!(found.len(), 1);
+        assert_eq!(found[0].position.map(|position| position.line), Some(6));
+        assert!(found[0].message.contains("selects `missing`"));
+    }
+
+    #[test]
+    fn warns_on_unknown_lifecycle_steps() {
+        let source = SENSOR_BRIDGE.replace("      Run: python3", "      Runn: python3");
+        let recipe = Recipe::parse(&source).unwrap();
+        let warnings = recipe.validate(&source);
+        assert_eq!(warnings.len(), 1);
+        assert_eq!(warnings[0].severity, IssueSeverity::Warning);
+        assert!(!warnings[0].is_error());
+        assert_eq!(warnings[0].position, at(19, 7));
+        assert_eq!(
+            warnings[0].to_string(),
+            "19:7: warning: Manifests[0] has an unknown lifecycle step `Runn`"
+        );
+    }
+
+    #[test]
+    fn deserializes_without_validating() {
+        let source = SENSOR_BRIDGE.replace("'1.4.2'", "'latest'");
+        assert!(Recipe::parse(&source).is_err());
+        let recipe = Recipe::deserialize(&source, RecipeFormat::Yaml).unwrap();
+        assert_eq!(recipe.component_version, "latest");
+    }
+}
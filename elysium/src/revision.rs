@@ -0,0 +1,151 @@
+use std::str::FromStr;
+
+use crate::{
+    ComponentDiff, DeploymentDetail, DeploymentSpec, Error, RevisionDiff, diff_components,
+};
+
+/// Requested change to a component in a deployment.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ComponentVersionChange {
+    /// The name of the component.
+    pub component_name: String,
+    /// The version to deploy, or `None` to remove the component.
+    pub version: Option<String>,
+}
+
+impl FromStr for ComponentVersionChange {
+    type Err = Error;
+
+    /// Parses `name=version` to set a version or `-name` to remove a component.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+        if let Some(name) = s.strip_prefix('-') {
+            return Ok(Self {
+                component_name: name.trim().to_string(),
+                version: None,
+            });
+        }
+        match s.split_once('=') {
+            Some((name, version)) if !name.trim().is_empty() && !version.trim().is_empty() => {
+                Ok(Self {
+                    component_name: name.trim().to_string(),
+                    version: Some(version.trim().to_string()),
+                })
+            }
+            _ => Err(Error::InvalidRequest(format!(
+                "Expected `name=version` or `-name`, got `{s}`"
+            ))),
+        }
+    }
+}
+
+/// Parses a comma or whitespace separated list of component changes.
+pub fn parse_component_changes(s: &str) -> Result<Vec<ComponentVersionChange>, Error> {
+    s.split(|c: char| c == ',' || c.is_whitespace())
+        .filter(|change| !change.is_empty())
+        .map(str::parse)
+        .collect()
+}
+
+/// Dry run of a new deployment revision for a target.
+#[derive(Clone, Debug, PartialEq)]
+pub struct RevisionPlan {
+    /// The latest deployment for the target, if there is one.
+    pub current: Option<DeploymentDetail>,
+    /// The deployment that would be created.
+    pub spec: DeploymentSpec,
+    /// The component changes compared to the current deployment.
+    pub diff: Vec<ComponentDiff>,
+}
+
+impl RevisionPlan {
+    /// Applies the changes on top of the current deployment for the target.
+    pub fn new(
+        target_arn: &str,
+        current: Option<DeploymentDetail>,
+        changes: &[ComponentVersionChange],
+    ) -> Self {
+        let mut spec = current
+            .as_ref()
+            .map(DeploymentDetail::spec)
+            .unwrap_or_else(|| DeploymentSpec {
+                target_arn: target_arn.to_string(),
+                deployment_name: target_name(target_arn).to_string(),
+                ..Default::default()
+            });
+
+        for change in changes {
+            match &change.version {
+                Some(version) => {
+                    spec.components
+                        .entry(change.component_name.clone())
+                        .or_default()
+                        .component_version = version.clone();
+                }
+                None => {
+                    spec.components.remove(&change.component_name);
+                }
+            }
+        }
+
+        let empty = Default::default();
+        let current_components = current
+            .as_ref()
+            .map_or(&empty, |current| &current.components);
+        let diff = diff_components(current_components, &spec.components);
+
+        Self {
+            current,
+            spec,
+            diff,
+        }
+    }
+
+    /// Whether the plan would deploy the same components as the current deployment.
+    pub fn is_noop(&self) -> bool {
+        self.diff.is_empty()
+    }
+}
+
+/// Dry run of re-issuing an earlier revision of a target's deployment.
+#[derive(Clone, Debug, PartialEq)]
+pub struct RollbackPlan {
+    /// The latest deployment for the target.
+    pub current: DeploymentDetail,
+    /// The earlier revision being rolled back to.
+    pub revision: DeploymentDetail,
+    /// The deployment that would be created.
+    pub spec: DeploymentSpec,
+    /// The changes from the current deployment to the earlier revision.
+    pub diff: RevisionDiff,
+}
+
+impl RollbackPlan {
+    /// Re-issues the earlier revision's components, configuration and policies under the current
+    /// deployment's name and tags.
+    pub fn new(current: DeploymentDetail, revision: DeploymentDetail) -> Result<Self, Error> {
+        let diff = RevisionDiff::new(&current, &revision)?;
+        let spec = DeploymentSpec {
+            components: revision.components.clone(),
+            deployment_policies: revision.deployment_policies.clone(),
+            iot_job_configuration: revision.iot_job_configuration.clone(),
+            ..current.spec()
+        };
+        Ok(Self {
+            current,
+            revision,
+            spec,
+            diff,
+        })
+    }
+
+    /// Whether the earlier revision deploys the same as the current one.
+    pub fn is_noop(&self) -> bool {
+        self.diff.is_empty()
+    }
+}
+
+/// Returns the resource name at the end of an ARN such as `arn:...:thinggroup/<name>`.
+pub fn target_name(target_arn: &str) -> &str {
+    target_arn.rsplit('/').next().unwrap_or(target_arn)
+}
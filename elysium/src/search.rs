@@ -0,0 +1,175 @@
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Serialize};
+
+use crate::{Error, Property, Result, policy::matches_wildcards};
+
+/// Maximum number of things returned by [`AwsCloud::search`](crate::AwsCloud::search) unless
+/// told otherwise.
+pub const DEFAULT_SEARCH_LIMIT: usize = 500;
+
+/// Thing as indexed by fleet indexing.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ThingDocument {
+    /// The name of the thing.
+    pub thing_name: String,
+    /// The thing type, if the thing has one.
+    pub thing_type_name: Option<String>,
+    /// The names of the groups the thing is directly in.
+    pub thing_group_names: Vec<String>,
+    /// Searchable attributes of the thing.
+    pub attributes: BTreeMap<String, String>,
+    /// Whether the thing is connected, `None` if connectivity is not indexed.
+    pub connected: Option<bool>,
+    /// Why the thing last disconnected, e.g. CONNECTION_LOST.
+    pub disconnect_reason: Option<String>,
+}
+
+/// Page of search results, as returned by `SearchIndex`.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct SearchPage {
+    /// The things on the page.
+    pub things: Vec<ThingDocument>,
+    /// Token for the next page, `None` on the last page.
+    pub next_token: Option<String>,
+}
+
+/// Things matching a fleet-indexing query.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct SearchResults {
+    /// The query, e.g. `connectivity.connected:false AND attributes.site:LON*`.
+    pub query: String,
+    /// Whether more things matched than were returned.
+    pub truncated: bool,
+    /// Inner type.
+    inner: Vec<ThingDocument>,
+}
+
+impl SearchResults {
+    /// Wraps the things found by a query.
+    pub fn new(query: impl Into<String>, things: Vec<ThingDocument>, truncated: bool) -> Self {
+        Self {
+            query: query.into(),
+            truncated,
+            inner: things,
+        }
+    }
+
+    /// Returns the things.
+    pub fn as_slice(&self) -> &[ThingDocument] {
+        &self.inner
+    }
+}
+
+impl<'a> Property<'a> for SearchResults {
+    fn items(&self) -> Vec<Vec<String>> {
+        self.inner
+            .iter()
+            .map(|item| {
+                vec![
+                    item.thing_name.to_string(),
+                    match item.connected {
+                        Some(true) => String::from("yes"),
+                        Some(false) => String::from("no"),
+                        None => String::new(),
+                    },
+                    item.thing_type_name.clone().unwrap_or_default(),
+                    item.thing_group_names.join(", "),
+                    item.attributes
+                        .iter()
+                        .map(|(key, value)| format!("{key}={value}"))
+                        .collect::<Vec<_>>()
+                        .join(" "),
+                ]
+            })
+            .collect()
+    }
+}
+
+/// Term of a [`Query`], such as `attributes.site:LON*` or `NOT thingGroupNames:lab`.
+#[derive(Clone, Debug, PartialEq)]
+struct Term {
+    negated: bool,
+    field: String,
+    pattern: String,
+}
+
+/// The part of the fleet-indexing query syntax needed to search fixtures offline.
+///
+/// Supports `field:value` terms with `*` and `?` wildcards, joined by `AND` (or nothing) and
+/// `OR`, each optionally negated with `NOT`. `AND` binds tighter than `OR`. A bare value
+/// matches the thing name. Parentheses and ranges are not supported.
+#[derive(Clone, Debug, PartialEq)]
+pub(crate) struct Query {
+    /// Groups of terms that must all match, any group matching.
+    clauses: Vec<Vec<Term>>,
+}
+
+impl Query {
+    pub(crate) fn parse(query: &str) -> Result<Self> {
+        let invalid = |message: String| Error::Service {
+            code: String::from("InvalidQueryException"),
+            message,
+        };
+        let mut clauses = vec![Vec::new()];
+        let mut negated = false;
+        for word in query.split_whitespace() {
+            match word {
+                "AND" => continue,
+                "OR" => clauses.push(Vec::new()),
+                "NOT" => {
+                    negated = !negated;
+                    continue;
+                }
+                _ if word.contains(['(', ')', '[', ']', '{', '}']) => {
+                    return Err(invalid(format!("Unsupported query syntax in {word}")));
+                }
+                _ => {
+                    let (field, pattern) = word.split_once(':').unwrap_or(("thingName", word));
+                    let term = Term {
+                        negated,
+                        field: field.to_string(),
+                        pattern: pattern.trim_matches('"').to_string(),
+                    };
+                    if let Some(terms) = clauses.last_mut() {
+                        terms.push(term);
+                    }
+                }
+            }
+            negated = false;
+        }
+        if clauses.iter().any(Vec::is_empty) {
+            return Err(invalid(format!("Incomplete query {query:?}")));
+        }
+        Ok(Self { clauses })
+    }
+
+    pub(crate) fn matches(&self, thing: &ThingDocument) -> bool {
+        self.clauses.iter().any(|terms| {
+            terms.iter().all(|term| {
+                let found = field_values(thing, &term.field)
+                    .iter()
+                    .any(|value| matches_wildcards(&term.pattern, value));
+                found != term.negated
+            })
+        })
+    }
+}
+
+/// The values of a field of the indexed thing, empty if it has none.
+pub(crate) fn field_values(thing: &ThingDocument, field: &str) -> Vec<String> {
+    match field {
+        "thingName" => vec![thing.thing_name.clone()],
+        "thingTypeName" => thing.thing_type_name.iter().cloned().collect(),
+        "thingGroupNames" => thing.thing_group_names.clone(),
+        "connectivity.connected" => thing.connected.iter().map(bool::to_string).collect(),
+        "connectivity.disconnectReason" => thing.disconnect_reason.iter().cloned().collect(),
+        _ => field
+            .strip_prefix("attributes.")
+            .and_then(|name| thing.attributes.get(name))
+            .into_iter()
+            .cloned()
+            .collect(),
+    }
+}
@@ -0,0 +1,267 @@
+use aws_sdk_iot::primitives::{DateTime, DateTimeFormat};
+use serde_json::{Map, Value, json};
+
+use crate::{Error, Result, ValueDiff, diff_json};
+
+/// Name shown for a thing's unnamed shadow.
+pub const CLASSIC_SHADOW: &str = "classic";
+
+/// Classic or named device shadow of a thing.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Shadow {
+    /// The name of the thing.
+    pub thing_name: String,
+    /// The name of the shadow, or `None` for the classic shadow.
+    pub shadow_name: Option<String>,
+    /// The version of the shadow document, incremented on every update.
+    pub version: i64,
+    /// The time of the last update, in seconds since the Unix epoch.
+    pub timestamp: i64,
+    /// The state the device should be in.
+    pub desired: Option<Value>,
+    /// The state the device reports it is in.
+    pub reported: Option<Value>,
+    /// The desired values that differ from the reported ones.
+    pub delta: Option<Value>,
+}
+
+impl Shadow {
+    /// Parses a shadow document as returned by `GetThingShadow`.
+    pub fn from_document(
+        thing_name: &str,
+        shadow_name: Option<&str>,
+        payload: &[u8],
+    ) -> Result<Self> {
+        let document: Value = serde_json::from_slice(payload).map_err(|e| Error::Service {
+            code: String::from("InvalidShadow"),
+            message: format!("Shadow of {thing_name} is not valid JSON: {e}"),
+        })?;
+        let state = |key: &str| {
+            document["state"]
+                .get(key)
+                .filter(|value| !value.is_null())
+                .cloned()
+        };
+        Ok(Self {
+            thing_name: thing_name.to_string(),
+            shadow_name: shadow_name.map(str::to_string),
+            version: document["version"].as_i64().unwrap_or_default(),
+            timestamp: document["timestamp"].as_i64().unwrap_or_default(),
+            desired: state("desired"),
+            reported: state("reported"),
+            delta: state("delta"),
+        })
+    }
+
+    /// The name of the shadow, [`CLASSIC_SHADOW`] for the classic one.
+    pub fn name(&self) -> &str {
+        self.shadow_name.as_deref().unwrap_or(CLASSIC_SHADOW)
+    }
+
+    /// The time of the last update, expressed in ISO 8601 format.
+    pub fn updated_at(&self) -> String {
+        DateTime::from_secs(self.timestamp)
+            .fmt(DateTimeFormat::DateTime)
+            .unwrap_or_default()
+    }
+
+    /// The desired state as single-line JSON, `{}` if the shadow has none.
+    pub fn desired_compact(&self) -> String {
+        self.desired
+            .as_ref()
+            .map_or_else(|| String::from("{}"), Value::to_string)
+    }
+
+    /// Pretty-printed desired, reported and delta states, each `None` if the shadow has none.
+    pub fn states_pretty(&self) -> [(&'static str, Option<String>); 3] {
+        let pretty = |value: &Option<Value>| {
+            value
+                .as_ref()
+                .and_then(|value| serde_json::to_string_pretty(value).ok())
+        };
+        [
+            ("Desired", pretty(&self.desired)),
+            ("Reported", pretty(&self.reported)),
+            ("Delta", pretty(&self.delta)),
+        ]
+    }
+}
+
+/// Dry run of an update to the desired state of a shadow.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ShadowUpdate {
+    /// The shadow as it is now.
+    pub current: Shadow,
+    /// The values to merge into the desired state. `null` removes a value.
+    pub desired: Value,
+    /// The changes to the desired state.
+    pub diff: Vec<ValueDiff>,
+}
+
+impl ShadowUpdate {
+    /// Plans merging `desired` into the current desired state, as the service merges updates.
+    pub fn new(current: Shadow, desired: Value) -> Result<Self> {
+        if !desired.is_object() {
+            return Err(Error::InvalidRequest(String::from(
+                "The desired state must be a JSON object",
+            )));
+        }
+        let before = current.desired.clone().unwrap_or(Value::Null);
+        let mut after = before.clone();
+        merge(&mut after, &desired);
+        let diff = diff_json(&before, &after);
+        Ok(Self {
+            current,
+            desired,
+            diff,
+        })
+    }
+
+    /// Whether the update leaves the desired state as it is.
+    pub fn is_noop(&self) -> bool {
+        self.diff.is_empty()
+    }
+
+    /// The `UpdateThingShadow` payload, which fails if the shadow changed since it was read.
+    ///
+    /// A shadow that does not exist yet has no version to check.
+    pub fn payload(&self) -> Value {
+        match self.current.version {
+            0 => json!({ "state": { "desired": self.desired } }),
+            version => json!({ "state": { "desired": self.desired }, "version": version }),
+        }
+    }
+}
+
+/// Merges `patch` into `target` like the shadow service: objects are merged key by key and
+/// `null` removes a key.
+pub(crate) fn merge(target: &mut Value, patch: &Value) {
+    let Value::Object(patch) = patch else {
+        *target = patch.clone();
+        return;
+    };
+    if !target.is_object() {
+        *target = Value::Object(Map::new());
+    }
+    if let Value::Object(target) = target {
+        for (key, value) in patch {
+            if value.is_null() {
+                target.remove(key);
+            } else {
+                merge(target.entry(key.clone()).or_insert(Value::Null), value);
+            }
+        }
+    }
+}
+
+/// The desired values that differ from the reported ones, as the service computes the delta.
+pub(crate) fn delta(desired: &Value, reported: Option<&Value>) -> Option<Value> {
+    match (desired, reported) {
+        (Value::Object(desired), Some(Value::Object(reported))) => {
+            let delta: Map<String, Value> = desired
+                .iter()
+                .filter_map(|(key, value)| Some((key.clone(), delta(value, reported.get(key))?)))
+                .collect();
+            (!delta.is_empty()).then_some(Value::Object(delta))
+        }
+        (desired, Some(reported)) if desired == reported => None,
+        (desired, _) => Some(desired.clone()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn shadow(version: i64, desired: Option<Value>) -> Shadow {
+        Shadow {
+            thing_name: String::from("lon-gw-001"),
+            version,
+            desired,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn parses_documents() {
+        let payload = br#"{
+            "state": {
+                "desired": { "led": "on" },
+                "reported": { "led": "off" },
+                "delta": null
+            },
+            "version": 7,
+            "timestamp": 1700000000
+        }"#;
+        let shadow = Shadow::from_document("lon-gw-001", Some("config"), payload).unwrap();
+        assert_eq!(shadow.name(), "config");
+        assert_eq!(shadow.version, 7);
+        assert_eq!(shadow.desired, Some(json!({ "led": "on" })));
+        assert_eq!(shadow.delta, None);
+        assert_eq!(shadow.updated_at(), "2023-11-14T22:13:20Z");
+
+        assert!(Shadow::from_document("lon-gw-001", None, b"not json").is_err());
+    }
+
+    #[test]
+    fn merges_objects_and_removes_nulls() {
+        let mut target =
+            json!({ "led": "on", "sensors": { "rate": 5, "unit": "C" }, "tags": [1, 2] });
+        merge(
+            &mut target,
+            &json!({ "led": null, "sensors": { "rate": 10 }, "tags": [3], "mode": "eco" }),
+        );
+        assert_eq!(
+            target,
+            json!({ "sensors": { "rate": 10, "unit": "C" }, "tags": [3], "mode": "eco" })
+        );
+
+        let mut target = Value::Null;
+        merge(&mut target, &json!({ "led": "on" }));
+        assert_eq!(target, json!({ "led": "on" }));
+    }
+
+    #[test]
+    fn computes_delta_against_reported_state() {
+        let desired = json!({ "led": "on", "sensors": { "rate": 10, "unit": "C" } });
+        let reported = json!({ "led": "on", "sensors": { "rate": 5, "unit": "C" } });
+        assert_eq!(
+            delta(&desired, Some(&reported)),
+            Some(json!({ "sensors": { "rate": 10 } }))
+        );
+        assert_eq!(delta(&desired, Some(&desired)), None);
+        assert_eq!(delta(&desired, None), Some(desired.clone()));
+    }
+
+    #[test]
+    fn plans_updates() {
+        let update = ShadowUpdate::new(
+            shadow(3, Some(json!({ "led": "on" }))),
+            json!({ "led": "off" }),
+        )
+        .unwrap();
+        assert!(!update.is_noop());
+        assert_eq!(update.diff.len(), 1);
+        assert_eq!(update.diff[0].path, "/led");
+        assert_eq!(
+            update.payload(),
+            json!({ "state": { "desired": { "led": "off" } }, "version": 3 })
+        );
+
+        let update = ShadowUpdate::new(
+            shadow(3, Some(json!({ "led": "on" }))),
+            json!({ "led": "on" }),
+        )
+        .unwrap();
+        assert!(update.is_noop());
+
+        // A new shadow has no version to check.
+        let update = ShadowUpdate::new(shadow(0, None), json!({ "led": "on" })).unwrap();
+        assert_eq!(
+            update.payload(),
+            json!({ "state": { "desired": { "led": "on" } } })
+        );
+
+        assert!(ShadowUpdate::new(shadow(3, None), json!(["led"])).is_err());
+    }
+}
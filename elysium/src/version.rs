@@ -0,0 +1,478 @@
+use std::{cmp::Ordering, fmt, str::FromStr};
+
+use crate::Error;
+
+/// Semantic version of a component, e.g. `2.13.0` or `1.0.0-beta.1`.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Version {
+    pub major: u64,
+    pub minor: u64,
+    pub patch: u64,
+    /// Pre-release identifiers, e.g. `beta.1`. Empty for releases.
+    pub pre: String,
+}
+
+impl Version {
+    pub fn new(major: u64, minor: u64, patch: u64) -> Self {
+        Self {
+            major,
+            minor,
+            patch,
+            pre: String::new(),
+        }
+    }
+
+    /// Whether this is a pre-release, which ranges only match when they name one explicitly.
+    pub fn is_prerelease(&self) -> bool {
+        !self.pre.is_empty()
+    }
+}
+
+impl FromStr for Version {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = || Error::InvalidRequest(format!("`{s}` is not a semantic version"));
+        // Build metadata does not take part in comparisons.
+        let without_build = s.trim().split('+').next().unwrap_or_default();
+        let (core, pre) = without_build.split_once('-').unwrap_or((without_build, ""));
+        let mut parts = core.split('.').map(|part| {
+            (!part.is_empty() && part.chars().all(|c| c.is_ascii_digit()))
+                .then(|| part.parse::<u64>().ok())
+                .flatten()
+        });
+        match (parts.next(), parts.next(), parts.next(), parts.next()) {
+            (Some(Some(major)), Some(Some(minor)), Some(Some(patch)), None) => Ok(Self {
+                major,
+                minor,
+                patch,
+                pre: pre.to_string(),
+            }),
+            _ => Err(invalid()),
+        }
+    }
+}
+
+impl fmt::Display for Version {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}.{}.{}", self.major, self.minor, self.patch)?;
+        if self.is_prerelease() {
+            write!(f, "-{}", self.pre)?;
+        }
+        Ok(())
+    }
+}
+
+impl Ord for Version {
+    fn cmp(&self, other: &Self) -> Ordering {
+        (self.major, self.minor, self.patch)
+            .cmp(&(other.major, other.minor, other.patch))
+            .then_with(|| match (self.is_prerelease(), other.is_prerelease()) {
+                (false, false) => Ordering::Equal,
+                (true, false) => Ordering::Less,
+                (false, true) => Ordering::Greater,
+                (true, true) => compare_prerelease(&self.pre, &other.pre),
+            })
+    }
+}
+
+impl PartialOrd for Version {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+/// Compares pre-release identifiers, numeric ones as numbers and below alphanumeric ones.
+fn compare_prerelease(a: &str, b: &str) -> Ordering {
+    let mut a = a.split('.');
+    let mut b = b.split('.');
+    loop {
+        match (a.next(), b.next()) {
+            (None, None) => return Ordering::Equal,
+            (None, Some(_)) => return Ordering::Less,
+            (Some(_), None) => return Ordering::Greater,
+            (Some(x), Some(y)) => {
+                let ordering = match (x.parse::<u64>(), y.parse::<u64>()) {
+                    (Ok(x), Ok(y)) => x.cmp(&y),
+                    (Ok(_), Err(_)) => Ordering::Less,
+                    (Err(_), Ok(_)) => Ordering::Greater,
+                    (Err(_), Err(_)) => x.cmp(y),
+                };
+                if ordering != Ordering::Equal {
+                    return ordering;
+                }
+            }
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Op {
+    Eq,
+    Gt,
+    Gte,
+    Lt,
+    Lte,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+struct Comparator {
+    op: Op,
+    version: Version,
+}
+
+impl Comparator {
+    fn matches(&self, version: &Version) -> bool {
+        match self.op {
+            Op::Eq => version == &self.version,
+            Op::Gt => version > &self.version,
+            Op::Gte => version >= &self.version,
+            Op::Lt => version < &self.version,
+            Op::Lte => version <= &self.version,
+        }
+    }
+}
+
+/// npm-style version range used in recipe dependencies, e.g. `>=2.0.0 <3.0.0` or `^1.2.0`.
+///
+/// Supports comparators, `^` and `~` ranges, `x` wildcards, hyphen ranges and `||`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct VersionRequirement {
+    source: String,
+    /// Alternatives separated by `||`, each a set of comparators that must all match.
+    alternatives: Vec<Vec<Comparator>>,
+}
+
+impl VersionRequirement {
+    /// Whether `version` satisfies the requirement.
+    ///
+    /// Like npm, pre-releases only match when a comparator names a pre-release of the same
+    /// `major.minor.patch`.
+    pub fn matches(&self, version: &Version) -> bool {
+        self.alternatives.iter().any(|comparators| {
+            comparators
+                .iter()
+                .all(|comparator| comparator.matches(version))
+                && (!version.is_prerelease()
+                    || comparators.iter().any(|comparator| {
+                        comparator.version.is_prerelease()
+                            && (
+                                comparator.version.major,
+                                comparator.version.minor,
+                                comparator.version.patch,
+                            ) == (version.major, version.minor, version.patch)
+                    }))
+        })
+    }
+}
+
+impl fmt::Display for VersionRequirement {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.source)
+    }
+}
+
+impl FromStr for VersionRequirement {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = || Error::InvalidRequest(format!("`{s}` is not a version requirement"));
+
+        let alternatives = s
+            .split("||")
+            .map(|alternative| {
+                let tokens: Vec<&str> = alternative.split_whitespace().collect();
+                match tokens.as_slice() {
+                    [] => Ok(vec![]),
+                    [from, "-", to] => {
+                        let mut comparators = partial(from).ok_or_else(invalid)?.lower(Op::Gte);
+                        comparators.extend(partial(to).ok_or_else(invalid)?.upper_inclusive());
+                        Ok(comparators)
+                    }
+                    tokens => tokens
+                        .iter()
+                        .try_fold(Vec::new(), |mut comparators, token| {
+                            comparators.extend(comparator(token).ok_or_else(invalid)?);
+                            Ok(comparators)
+                        }),
+                }
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        if s.trim().is_empty() {
+            return Err(invalid());
+        }
+
+        Ok(Self {
+            source: s.trim().to_string(),
+            alternatives,
+        })
+    }
+}
+
+/// A version that may leave out or wildcard its trailing parts, e.g. `1.2` or `1.x`.
+struct Partial {
+    major: Option<u64>,
+    minor: Option<u64>,
+    patch: Option<u64>,
+    pre: String,
+}
+
+fn partial(s: &str) -> Option<Partial> {
+    let s = s.trim_start_matches(['v', '=']);
+    let s = s.split('+').next().unwrap_or_default();
+    let (core, pre) = s.split_once('-').unwrap_or((s, ""));
+    let mut parts = core.split('.');
+    let mut next = || -> Option<Option<u64>> {
+        match parts.next() {
+            None | Some("x") | Some("X") | Some("*") => Some(None),
+            Some(part) if part.chars().all(|c| c.is_ascii_digit()) && !part.is_empty() => {
+                part.parse().ok().map(Some)
+            }
+            Some(_) => None,
+        }
+    };
+    let (major, minor, patch) = (next()?, next()?, next()?);
+    if parts.next().is_some()
+        || (major.is_none() && minor.is_some())
+        || (minor.is_none() && patch.is_some())
+    {
+        return None;
+    }
+    Some(Partial {
+        major,
+        minor,
+        patch,
+        pre: pre.to_string(),
+    })
+}
+
+impl Partial {
+    fn floor(&self) -> Version {
+        Version {
+            major: self.major.unwrap_or(0),
+            minor: self.minor.unwrap_or(0),
+            patch: self.patch.unwrap_or(0),
+            pre: self.pre.clone(),
+        }
+    }
+
+    /// The first version above the range the wildcards cover, if it is bounded.
+    fn ceiling(&self) -> Option<Version> {
+        match (self.major, self.minor, self.patch) {
+            (Some(major), None, _) => Some(Version::new(major + 1, 0, 0)),
+            (Some(major), Some(minor), None) => Some(Version::new(major, minor + 1, 0)),
+            _ => None,
+        }
+    }
+
+    fn is_exact(&self) -> bool {
+        self.patch.is_some()
+    }
+
+    fn lower(&self, op: Op) -> Vec<Comparator> {
+        if self.major.is_none() {
+            return vec![];
+        }
+        match op {
+            Op::Gt if !self.is_exact() => self.ceiling().map_or_else(Vec::new, |version| {
+                vec![Comparator {
+                    op: Op::Gte,
+                    version,
+                }]
+            }),
+            op => vec![Comparator {
+                op,
+                version: self.floor(),
+            }],
+        }
+    }
+
+    fn upper_inclusive(&self) -> Vec<Comparator> {
+        if self.is_exact() {
+            vec![Comparator {
+                op: Op::Lte,
+                version: self.floor(),
+            }]
+        } else {
+            self.ceiling()
+                .map(|version| {
+                    vec![Comparator {
+                        op: Op::Lt,
+                        version,
+                    }]
+                })
+                .unwrap_or_default()
+        }
+    }
+}
+
+fn comparator(token: &str) -> Option<Vec<Comparator>> {
+    let (op, rest) = [">=", "<=", ">", "<", "=", "^", "~"]
+        .iter()
+        .find_map(|op| token.strip_prefix(op).map(|rest| (*op, rest)))
+        .unwrap_or(("", token));
+    let version = partial(rest)?;
+    let floor = version.floor();
+
+    Some(match op {
+        ">=" => version.lower(Op::Gte),
+        ">" => version.lower(Op::Gt),
+        "<=" => version.upper_inclusive(),
+        "<" if version.major.is_none() => vec![Comparator {
+            op: Op::Lt,
+            version: Version::new(0, 0, 0),
+        }],
+        "<" => vec![Comparator {
+            op: Op::Lt,
+            version: floor,
+        }],
+        "^" => {
+            let upper = match (floor.major, floor.minor, version.minor, version.patch) {
+                (0, 0, Some(_), Some(_)) => Version::new(0, 0, floor.patch + 1),
+                (0, minor, Some(_), _) => Version::new(0, minor + 1, 0),
+                (major, _, _, _) => Version::new(major + 1, 0, 0),
+            };
+            vec![
+                Comparator {
+                    op: Op::Gte,
+                    version: floor,
+                },
+                Comparator {
+                    op: Op::Lt,
+                    version: upper,
+                },
+            ]
+        }
+        "~" => {
+            let upper = match version.minor {
+                Some(minor) => Version::new(floor.major, minor + 1, 0),
+                None => Version::new(floor.major + 1, 0, 0),
+            };
+            vec![
+                Comparator {
+                    op: Op::Gte,
+                    version: floor,
+                },
+                Comparator {
+                    op: Op::Lt,
+                    version: upper,
+                },
+            ]
+        }
+        _ if version.is_exact() => vec![Comparator {
+            op: Op::Eq,
+            version: floor,
+        }],
+        _ => {
+            let mut comparators = version.lower(Op::Gte);
+            comparators.extend(version.ceiling().map(|version| Comparator {
+                op: Op::Lt,
+                version,
+            }));
+            comparators
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn version(s: &str) -> Version {
+        s.parse().unwrap()
+    }
+
+    fn requirement(s: &str) -> VersionRequirement {
+        s.parse().unwrap()
+    }
+
+    #[test]
+    fn parses_versions() {
+        assert_eq!(version("2.13.0"), Version::new(2, 13, 0));
+        assert_eq!(version("1.0.0-beta.1").pre, "beta.1");
+        assert_eq!(version("1.0.0+build.5"), Version::new(1, 0, 0));
+        assert_eq!(version("1.0.0-rc.1").to_string(), "1.0.0-rc.1");
+
+        for invalid in ["1.0", "1.0.0.0", "v1.0.0", "1.x.0", "", "1..0"] {
+            assert!(invalid.parse::<Version>().is_err(), "{invalid} parsed");
+        }
+    }
+
+    #[test]
+    fn orders_releases_above_their_prereleases() {
+        let ordered = [
+            "1.0.0-alpha",
+            "1.0.0-alpha.1",
+            "1.0.0-alpha.beta",
+            "1.0.0-beta.2",
+            "1.0.0-beta.11",
+            "1.0.0-rc.1",
+            "1.0.0",
+            "1.0.1",
+            "1.10.0",
+            "2.0.0",
+        ];
+        for pair in ordered.windows(2) {
+            assert!(
+                version(pair[0]) < version(pair[1]),
+                "{} < {}",
+                pair[0],
+                pair[1]
+            );
+        }
+    }
+
+    #[test]
+    fn matches_comparators_and_ranges() {
+        let cases = [
+            (">=2.0.0 <3.0.0", "2.5.1", true),
+            (">=2.0.0 <3.0.0", "3.0.0", false),
+            ("=1.2.3", "1.2.3", true),
+            ("1.2.3", "1.2.4", false),
+            ("^1.2.0", "1.9.9", true),
+            ("^1.2.0", "2.0.0", false),
+            ("^0.2.3", "0.2.9", true),
+            ("^0.2.3", "0.3.0", false),
+            ("^0.0.3", "0.0.4", false),
+            ("~1.2.0", "1.2.9", true),
+            ("~1.2.0", "1.3.0", false),
+            ("~1", "1.9.0", true),
+            ("1.x", "1.4.0", true),
+            ("1.x", "2.0.0", false),
+            ("*", "0.0.1", true),
+            (">1.2", "1.2.9", false),
+            (">1.2", "1.3.0", true),
+            ("<=1.2", "1.2.9", true),
+            ("1.0.0 - 1.2", "1.2.7", true),
+            ("1.0.0 - 1.2.0", "1.2.1", false),
+            ("<1.0.0 || >=2.0.0", "1.5.0", false),
+            ("<1.0.0 || >=2.0.0", "2.1.0", true),
+        ];
+        for (range, candidate, expected) in cases {
+            assert_eq!(
+                requirement(range).matches(&version(candidate)),
+                expected,
+                "{range} {candidate}"
+            );
+        }
+    }
+
+    #[test]
+    fn matches_prereleases_only_when_named() {
+        assert!(!requirement(">=1.0.0").matches(&version("2.0.0-beta.1")));
+        assert!(requirement(">=2.0.0-beta.1").matches(&version("2.0.0-beta.2")));
+        assert!(!requirement(">=2.0.0-beta.1").matches(&version("2.1.0-beta.1")));
+    }
+
+    #[test]
+    fn rejects_invalid_requirements() {
+        for invalid in ["", "  ", ">=a.b.c", "1.2.3.4", "^x.1"] {
+            assert!(
+                invalid.parse::<VersionRequirement>().is_err(),
+                "{invalid} parsed"
+            );
+        }
+        assert_eq!(requirement(" ^1.2.0 ").to_string(), "^1.2.0");
+    }
+}
@@ -0,0 +1,360 @@
+//! Runs [`AwsCloud`] against the example fleet in `fixtures/fleet.yaml`.
+
+use std::path::PathBuf;
+
+use elysium::{
+    AwsCloud, BulkMembership, BulkOptions, BulkOutcome, ComponentDiff, Error, FixtureBackend,
+    MembershipChange, Resource, parse_component_changes,
+};
+use serde_json::json;
+
+const FLEET: &str = include_str!("../fixtures/fleet.yaml");
+const LONDON: &str = "arn:aws:iot:eu-west-1:123456789012:thinggroup/london";
+
+fn cloud() -> AwsCloud {
+    cloud_from(FLEET)
+}
+
+fn cloud_from(fixture: &str) -> AwsCloud {
+    AwsCloud::with_backend(FixtureBackend::from_yaml(fixture).unwrap())
+}
+
+/// A resume file path unique to the test, removed if an earlier run left it behind.
+fn resume_file(name: &str) -> PathBuf {
+    let path = std::env::temp_dir().join(format!("elysium-{}-{name}.resume", std::process::id()));
+    let _ = std::fs::remove_file(&path);
+    path
+}
+
+fn outcomes(report: &elysium::BulkReport) -> Vec<(&str, &BulkOutcome)> {
+    report
+        .as_slice()
+        .iter()
+        .map(|item| (item.thing_name.as_str(), &item.outcome))
+        .collect()
+}
+
+async fn members(aws: &AwsCloud, group_name: &str) -> Vec<String> {
+    let members = aws.thing_group_members(group_name, false).await.unwrap();
+    members
+        .as_slice()
+        .iter()
+        .map(|member| member.thing_name.clone())
+        .collect()
+}
+
+#[tokio::test]
+async fn loads_every_resource() {
+    let mut aws = cloud();
+    aws.load().await.unwrap();
+
+    let devices: Vec<&str> = aws
+        .devices
+        .as_slice()
+        .iter()
+        .map(|device| device.name.as_str())
+        .collect();
+    assert_eq!(devices, ["lon-gw-001", "lon-gw-002", "man-gw-001"]);
+    assert_eq!(aws.devices.as_slice()[0].tags["environment"], "production");
+    assert_eq!(aws.groups.as_slice().len(), 5);
+    assert_eq!(aws.components.as_slice().len(), 2);
+    assert_eq!(aws.jobs.as_slice().len(), 2);
+    assert!(!aws.certificates.as_slice().is_empty());
+    assert!(
+        aws.devices
+            .as_slice()
+            .iter()
+            .any(|device| aws.security.get(&device.name).is_some())
+    );
+    assert!(aws.is_enabled(Resource::Security));
+
+    let summary = aws.summary.as_ref().unwrap();
+    assert_eq!(summary.connected, 2);
+    assert_eq!(summary.disconnected, 1);
+    assert!(aws.is_enabled(Resource::FleetSummary));
+}
+
+#[tokio::test]
+async fn skips_services_the_account_has_not_enabled() {
+    let fixture = format!("{FLEET}\ndisabled_services: [fleet_indexing, device_defender]\n");
+    let mut aws = cloud_from(&fixture);
+    aws.load().await.unwrap();
+
+    assert_eq!(aws.devices.as_slice().len(), 3);
+    assert!(aws.summary.is_none());
+    assert!(!aws.is_enabled(Resource::Security));
+    assert!(!aws.is_enabled(Resource::FleetSummary));
+    assert!(
+        aws.devices
+            .as_slice()
+            .iter()
+            .all(|device| aws.security.get(&device.name).is_none())
+    );
+
+    // Reloading does not try them again, unless asked to.
+    aws.load().await.unwrap();
+    assert!(aws.summary.is_none());
+    assert!(!aws.is_enabled(Resource::Security));
+    assert!(matches!(
+        aws.search("thingName:*", 10).await,
+        Err(Error::NotEnabled(_))
+    ));
+    aws.retry_disabled_services();
+    assert!(aws.is_enabled(Resource::Security));
+    assert!(aws.is_enabled(Resource::FleetSummary));
+}
+
+#[tokio::test]
+async fn follows_search_pages_up_to_the_limit() {
+    let aws = cloud();
+
+    // The fixture returns two things per page.
+    let results = aws.search("thingName:lon*", 100).await.unwrap();
+    let names: Vec<&str> = results
+        .as_slice()
+        .iter()
+        .map(|thing| thing.thing_name.as_str())
+        .collect();
+    assert_eq!(names, ["lon-gw-001", "lon-gw-002", "lon-sensor-017"]);
+    assert!(!results.truncated);
+
+    let results = aws.search("thingName:lon*", 2).await.unwrap();
+    assert_eq!(results.as_slice().len(), 2);
+    assert!(results.truncated);
+
+    let results = aws
+        .search("connectivity.connected:false", 100)
+        .await
+        .unwrap();
+    assert_eq!(results.as_slice().len(), 1);
+}
+
+#[tokio::test]
+async fn bulk_dry_run_changes_nothing() {
+    let aws = cloud();
+    let membership = BulkMembership::new(
+        MembershipChange::Add,
+        "lab",
+        vec![String::from("lon-gw-001"), String::from("lon-gw-002")],
+    );
+    let options = BulkOptions {
+        dry_run: true,
+        ..Default::default()
+    };
+
+    let report = aws.bulk_membership(&membership, &options).await.unwrap();
+    assert_eq!(
+        outcomes(&report),
+        [
+            (
+                "lon-gw-001",
+                &BulkOutcome::Skipped(String::from("already a member"))
+            ),
+            ("lon-gw-002", &BulkOutcome::Planned),
+        ]
+    );
+    assert_eq!(members(&aws, "lab").await, ["lon-gw-001"]);
+}
+
+#[tokio::test]
+async fn bulk_resumes_after_failures() {
+    let aws = cloud();
+    let path = resume_file("resume");
+    let membership = BulkMembership::new(
+        MembershipChange::Add,
+        "lab",
+        vec![
+            String::from("lon-gw-002"),
+            String::from("no-such-thing"),
+            String::from("man-gw-001"),
+        ],
+    );
+    let options = BulkOptions {
+        rate: 1000,
+        resume_file: Some(path.clone()),
+        ..Default::default()
+    };
+
+    let report = aws.bulk_membership(&membership, &options).await.unwrap();
+    assert!(report.has_failures());
+    assert_eq!(report.count(&BulkOutcome::Done), 2);
+    assert_eq!(
+        members(&aws, "lab").await,
+        ["lon-gw-001", "lon-gw-002", "man-gw-001"]
+    );
+    // The file is kept so the failed things can be retried.
+    assert!(path.exists());
+
+    let retry = BulkMembership::new(
+        MembershipChange::Add,
+        "lab",
+        vec![String::from("lon-gw-002"), String::from("man-gw-001")],
+    );
+    let report = aws.bulk_membership(&retry, &options).await.unwrap();
+    assert_eq!(report.count(&BulkOutcome::Resumed), 2);
+    assert!(!path.exists());
+
+    // A resume file of another change is refused.
+    std::fs::write(&path, "# remove lab\n").unwrap();
+    assert!(matches!(
+        aws.bulk_membership(&retry, &options).await,
+        Err(Error::InvalidRequest(_))
+    ));
+    std::fs::remove_file(&path).unwrap();
+}
+
+#[tokio::test]
+async fn bulk_refuses_dynamic_groups() {
+    let aws = cloud();
+    aws.create_dynamic_group("offline", "connectivity.connected:false")
+        .await
+        .unwrap();
+    let membership = BulkMembership::new(
+        MembershipChange::Add,
+        "offline",
+        vec![String::from("lon-gw-001")],
+    );
+
+    let result = aws
+        .bulk_membership(&membership, &BulkOptions::default())
+        .await;
+    assert!(matches!(result, Err(Error::InvalidRequest(_))));
+}
+
+#[tokio::test]
+async fn revises_the_latest_deployment() {
+    let aws = cloud();
+    let changes =
+        parse_component_changes("aws.greengrass.Nucleus=2.14.0, -com.example.SensorBridge")
+            .unwrap();
+
+    let plan = aws.revise_deployment(LONDON, &changes).await.unwrap();
+    assert_eq!(plan.current.as_ref().unwrap().revision_id, "3");
+    assert_eq!(
+        plan.diff,
+        [
+            ComponentDiff::VersionChanged {
+                name: String::from("aws.greengrass.Nucleus"),
+                from: String::from("2.13.0"),
+                to: String::from("2.14.0"),
+            },
+            ComponentDiff::Removed {
+                name: String::from("com.example.SensorBridge"),
+                version: String::from("1.4.2"),
+            },
+        ]
+    );
+
+    aws.create_deployment(&plan.spec).await.unwrap();
+    let latest = aws.latest_deployment(LONDON).await.unwrap().unwrap();
+    assert_eq!(latest.revision_id, "4");
+    assert_eq!(
+        latest.components["aws.greengrass.Nucleus"].component_version,
+        "2.14.0"
+    );
+    assert!(!latest.components.contains_key("com.example.SensorBridge"));
+
+    let plan = aws.revise_deployment(LONDON, &[]).await.unwrap();
+    assert!(plan.is_noop());
+}
+
+#[tokio::test]
+async fn rolls_back_to_an_earlier_revision() {
+    let aws = cloud();
+
+    let plan = aws.rollback_deployment(LONDON, "2").await.unwrap();
+    assert_eq!(plan.current.revision_id, "3");
+    assert_eq!(plan.revision.revision_id, "2");
+    assert!(!plan.is_noop());
+    assert_eq!(plan.spec.deployment_name, plan.current.deployment_name);
+
+    aws.create_deployment(&plan.spec).await.unwrap();
+    let latest = aws.latest_deployment(LONDON).await.unwrap().unwrap();
+    assert_eq!(latest.revision_id, "4");
+    assert_eq!(latest.components, plan.revision.components);
+
+    let history = aws.deployment_history(LONDON).await.unwrap();
+    assert_eq!(
+        history
+            .iter()
+            .filter(|deployment| deployment.is_latest_for_target)
+            .count(),
+        1
+    );
+
+    assert!(matches!(
+        aws.rollback_deployment(LONDON, "4").await,
+        Err(Error::InvalidRequest(_))
+    ));
+    assert!(matches!(
+        aws.rollback_deployment(LONDON, "9").await,
+        Err(Error::NotFound(_))
+    ));
+}
+
+#[tokio::test]
+async fn updates_shadows() {
+    let aws = cloud();
+
+    let update = aws
+        .plan_shadow_update(
+            "lon-gw-001",
+            None,
+            r#"{"telemetry_interval": 30, "led": null}"#,
+        )
+        .await
+        .unwrap();
+    let paths: Vec<&str> = update.diff.iter().map(|diff| diff.path.as_str()).collect();
+    assert_eq!(paths, ["/led", "/telemetry_interval"]);
+
+    let shadow = aws.update_shadow(&update).await.unwrap();
+    assert_eq!(shadow.version, 13);
+    assert_eq!(shadow.desired, Some(json!({ "telemetry_interval": 30 })));
+    // The reported state now matches what is desired.
+    assert_eq!(shadow.delta, None);
+
+    // The plan was made against version 12, so publishing it again conflicts.
+    assert!(matches!(
+        aws.update_shadow(&update).await,
+        Err(Error::Service { .. })
+    ));
+}
+
+#[tokio::test]
+async fn creates_missing_shadows() {
+    let aws = cloud();
+
+    let update = aws
+        .plan_shadow_update("lon-gw-002", Some("modem"), r#"{"apn": "iot.example.net"}"#)
+        .await
+        .unwrap();
+    assert_eq!(update.current.version, 0);
+    let shadow = aws.update_shadow(&update).await.unwrap();
+    assert_eq!(shadow.version, 1);
+    assert_eq!(shadow.delta, Some(json!({ "apn": "iot.example.net" })));
+
+    let shadows = aws.shadows("lon-gw-002").await.unwrap();
+    let names: Vec<&str> = shadows.iter().map(|shadow| shadow.name()).collect();
+    assert_eq!(names, ["modem"]);
+
+    assert!(matches!(
+        aws.plan_shadow_update("lon-gw-002", None, "[1]").await,
+        Err(Error::InvalidRequest(_))
+    ));
+}
+
+#[tokio::test]
+async fn conflicting_updates_create_no_shadow() {
+    let aws = cloud();
+
+    let mut update = aws
+        .plan_shadow_update("lon-gw-002", Some("modem"), r#"{"apn": "iot.example.net"}"#)
+        .await
+        .unwrap();
+    update.current.version = 3;
+    assert!(matches!(
+        aws.update_shadow(&update).await,
+        Err(Error::Service { .. })
+    ));
+    assert!(aws.shadows("lon-gw-002").await.unwrap().is_empty());
+}
@@ -1,4 +1,13 @@
-use crate::{app::Mode, enums::TabsEnum};
+use elysium::{FleetSummary, Resource, SearchResults};
+
+use crate::{
+    app::Mode,
+    components::{
+        confirm::{Confirmation, Operation},
+        detail::{Detail, Inspect},
+    },
+    enums::TabsEnum,
+};
 
 #[derive(Debug, Clone, PartialEq)]
 pub enum Action {
@@ -23,4 +32,36 @@ pub enum Action {
     GraphToggle,
     Clear,
     DataLoaded,
+    Reload,
+    LoadFailed(Resource, String),
+    /// The fleet summary, `None` if fleet indexing is not enabled.
+    SummaryLoaded(Option<Box<FleetSummary>>),
+    Select,
+    Inspect(Inspect),
+    ShowDetail(Detail),
+    Revise,
+    CancelDeployment,
+    Rollback {
+        target_arn: String,
+        revision_id: String,
+    },
+    ShowShadow {
+        thing_name: String,
+        shadow_name: Option<String>,
+    },
+    EditShadow {
+        thing_name: String,
+        shadow_name: Option<String>,
+        desired: String,
+    },
+    CheckPermission {
+        thing_name: String,
+    },
+    Search,
+    SearchResults(SearchResults),
+    SaveSearch,
+    BulkMembership,
+    Confirm(Confirmation),
+    Confirmed,
+    Execute(Operation),
 }
\ No newline at end of file
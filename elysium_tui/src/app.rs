@@ -10,7 +10,9 @@ use tracing::debug;
 use crate::{
     action::Action,
     components::{
+        confirm::ConfirmPopup,
         data_table::DataTable,
+        detail::{DetailPane, Inspect},
         header::Header,
         top_left::TopLeft,
         top_right::TopRight,
@@ -39,6 +41,8 @@ pub enum Mode {
     #[default]
     Normal,
     Input,
+    Detail,
+    Confirm,
 }
 
 #[derive(Debug, PartialEq, Eq, Hash)]
@@ -110,6 +114,107 @@ impl Default for KeyBindings {
                     StateKey::Exact(Mode::Normal, KeyCode::Char('g'), KeyModifiers::empty()),
                     Action::GraphToggle,
                 ),
+                // Drill down into the selected row
+                (
+                    StateKey::Exact(Mode::Normal, KeyCode::Enter, KeyModifiers::empty()),
+                    Action::Select,
+                ),
+                (
+                    StateKey::Exact(Mode::Normal, KeyCode::Char('e'), KeyModifiers::empty()),
+                    Action::Inspect(Inspect::EffectiveDeployments),
+                ),
+                (
+                    StateKey::Exact(Mode::Normal, KeyCode::Char('v'), KeyModifiers::empty()),
+                    Action::Inspect(Inspect::Recipe),
+                ),
+                (
+                    StateKey::Exact(Mode::Normal, KeyCode::Char('d'), KeyModifiers::empty()),
+                    Action::Inspect(Inspect::Dependencies),
+                ),
+                (
+                    StateKey::Exact(Mode::Normal, KeyCode::Char('f'), KeyModifiers::empty()),
+                    Action::Inspect(Inspect::Changes),
+                ),
+                (
+                    StateKey::Exact(Mode::Normal, KeyCode::Char('h'), KeyModifiers::empty()),
+                    Action::Inspect(Inspect::History),
+                ),
+                (
+                    StateKey::Exact(Mode::Normal, KeyCode::Char('s'), KeyModifiers::empty()),
+                    Action::Inspect(Inspect::Shadows),
+                ),
+                (
+                    StateKey::Exact(Mode::Normal, KeyCode::Char('p'), KeyModifiers::empty()),
+                    Action::Inspect(Inspect::Policies),
+                ),
+                (
+                    StateKey::Exact(Mode::Normal, KeyCode::Char('a'), KeyModifiers::empty()),
+                    Action::Inspect(Inspect::Security),
+                ),
+                (
+                    StateKey::Exact(Mode::Normal, KeyCode::Char('m'), KeyModifiers::empty()),
+                    Action::Inspect(Inspect::AllMembers),
+                ),
+                // Query the fleet index
+                (
+                    StateKey::Exact(Mode::Normal, KeyCode::Char('/'), KeyModifiers::empty()),
+                    Action::Search,
+                ),
+                // Save the query as a dynamic thing group
+                (
+                    StateKey::Exact(Mode::Normal, KeyCode::Char('n'), KeyModifiers::empty()),
+                    Action::SaveSearch,
+                ),
+                // Add or remove things to or from a group in bulk
+                (
+                    StateKey::Exact(Mode::Normal, KeyCode::Char('b'), KeyModifiers::empty()),
+                    Action::BulkMembership,
+                ),
+                // Revise the selected deployment
+                (
+                    StateKey::Exact(Mode::Normal, KeyCode::Char('r'), KeyModifiers::empty()),
+                    Action::Revise,
+                ),
+                // Cancel the selected deployment
+                (
+                    StateKey::Exact(Mode::Normal, KeyCode::Char('x'), KeyModifiers::empty()),
+                    Action::CancelDeployment,
+                ),
+                // Reload everything from the cloud
+                (
+                    StateKey::Exact(Mode::Normal, KeyCode::Char('r'), KeyModifiers::CONTROL),
+                    Action::Reload,
+                ),
+                // Leave the drill-down
+                (
+                    StateKey::Exact(Mode::Detail, KeyCode::Esc, KeyModifiers::empty()),
+                    Action::ModeChange(Mode::Normal),
+                ),
+                (
+                    StateKey::Exact(Mode::Detail, KeyCode::Enter, KeyModifiers::empty()),
+                    Action::Select,
+                ),
+                (
+                    StateKey::Exact(Mode::Detail, KeyCode::Up, KeyModifiers::empty()),
+                    Action::Up,
+                ),
+                (
+                    StateKey::Exact(Mode::Detail, KeyCode::Down, KeyModifiers::empty()),
+                    Action::Down,
+                ),
+                // Answer a confirmation
+                (
+                    StateKey::Exact(Mode::Confirm, KeyCode::Char('y'), KeyModifiers::empty()),
+                    Action::Confirmed,
+                ),
+                (
+                    StateKey::Exact(Mode::Confirm, KeyCode::Char('n'), KeyModifiers::empty()),
+                    Action::ModeChange(Mode::Normal),
+                ),
+                (
+                    StateKey::Exact(Mode::Confirm, KeyCode::Esc, KeyModifiers::empty()),
+                    Action::ModeChange(Mode::Normal),
+                ),
                 // Clear input
                 (
                     StateKey::Exact(Mode::Normal, KeyCode::Char('c'), KeyModifiers::empty()),
@@ -134,8 +239,15 @@ impl KeyBindings {
 }
 
 impl App {
-    pub fn new(aws: AwsCloud) -> Result<Self> {
-        let data_table = DataTable::new(aws);
+    pub fn new(
+        aws: AwsCloud,
+        profile: String,
+        attribute_columns: Vec<String>,
+        bulk_rate: u32,
+    ) -> Result<Self> {
+        let data_table = DataTable::new(aws, profile)
+            .with_attribute_columns(attribute_columns)
+            .with_bulk_rate(bulk_rate);
         let (action_tx, action_rx) = mpsc::unbounded_channel();
         Ok(Self {
             tick_rate: 1.0,
@@ -144,7 +256,9 @@ impl App {
                 Box::new(Header::new()),
                 Box::new(TopLeft::default()),
                 Box::new(TopRight::default()),
-                Box::new(data_table)
+                Box::new(data_table),
+                Box::new(DetailPane::default()),
+                Box::new(ConfirmPopup::default()),
             ],
             should_quit: false,
             should_suspend: false,
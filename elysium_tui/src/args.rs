@@ -1,3 +1,5 @@
+use std::path::PathBuf;
+
 use clap::Parser;
 
 use crate::utils::{get_config_dir, get_data_dir};
@@ -23,6 +25,82 @@ pub struct Args {
     )]
     pub region: String,
 
+    /// Fixture to load instead of connecting to AWS.
+    #[arg(
+        short = 'f',
+        long = "fixture",
+        help = "Path to a JSON or YAML fleet fixture to use instead of AWS"
+    )]
+    pub fixture: Option<PathBuf>,
+
+    /// Maximum number of connectivity lookups in flight.
+    #[arg(
+        long = "connectivity-concurrency",
+        help = "Maximum number of device connectivity lookups to run at once",
+        default_value_t = elysium::DEFAULT_CONNECTIVITY_CONCURRENCY
+    )]
+    pub connectivity_concurrency: usize,
+
+    /// Timeout in seconds for a single connectivity lookup.
+    #[arg(
+        long = "connectivity-timeout",
+        help = "Timeout in seconds for a single device connectivity lookup",
+        default_value_t = elysium::DEFAULT_CONNECTIVITY_TIMEOUT.as_secs()
+    )]
+    pub connectivity_timeout: u64,
+
+    /// Thing attributes to show as columns.
+    #[arg(
+        short = 'a',
+        long = "attribute",
+        value_name = "NAME",
+        help = "Thing attribute to show as a column on the Core Devices tab, can be repeated"
+    )]
+    pub attributes: Vec<String>,
+
+    /// Field to count things by in the fleet summary.
+    #[arg(
+        long = "summary-buckets",
+        value_name = "FIELD",
+        help = "Fleet-indexing field to count things by in the fleet summary",
+        default_value = elysium::DEFAULT_SUMMARY_BUCKET_FIELD
+    )]
+    pub summary_bucket_field: String,
+
+    /// Numeric field to summarize.
+    #[arg(
+        long = "summary-metric",
+        value_name = "FIELD",
+        help = "Numeric fleet-indexing field to show statistics and percentiles of in the fleet summary"
+    )]
+    pub summary_metric_field: Option<String>,
+
+    /// Maximum number of thing group membership changes per second.
+    #[arg(
+        long = "bulk-rate",
+        value_name = "PER_SECOND",
+        help = "Maximum number of thing group membership changes to make per second in bulk changes",
+        default_value_t = elysium::DEFAULT_BULK_RATE
+    )]
+    pub bulk_rate: u32,
+
+    /// Recipes to lint instead of starting the TUI.
+    #[arg(
+        long = "lint-recipe",
+        value_name = "FILE",
+        num_args = 1..,
+        help = "Validate JSON or YAML component recipes, print any problems and exit"
+    )]
+    pub lint_recipes: Vec<PathBuf>,
+
+    /// Days ahead to report expiring certificates for instead of starting the TUI.
+    #[arg(
+        long = "expiring-certificates",
+        value_name = "DAYS",
+        help = "Print the active certificates expiring within DAYS as JSON and exit, failing if there are any or some things could not be checked"
+    )]
+    pub expiring_certificates: Option<i64>,
+
     /// Increase verbosity. Can be used multiple times
     #[arg(
         short,
@@ -11,7 +11,9 @@ use crate::{
     tui::{Event, Frame}
 };
 
+pub mod confirm;
 pub mod data_table;
+pub mod detail;
 pub mod header;
 pub mod top_left;
 pub mod top_right;
@@ -0,0 +1,274 @@
+use color_eyre::Result;
+use std::path::Path;
+
+use elysium::{
+    BulkMembership, BulkOutcome, BulkReport, ComponentDiff, Deployment, DeploymentSpec,
+    MembershipChange, RollbackPlan, SearchResults, ShadowUpdate,
+};
+use ratatui::prelude::*;
+use tokio::sync::mpsc::UnboundedSender;
+use tui_popup::Popup;
+
+use crate::{
+    action::Action,
+    app::Mode,
+    components::{Component, detail::Detail},
+    tui::Frame,
+};
+
+/// A change to the fleet that only runs once the user confirms it.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Operation {
+    CreateDeployment(Box<DeploymentSpec>),
+    CancelDeployment(String),
+    UpdateShadow(Box<ShadowUpdate>),
+    CreateDynamicGroup { group_name: String, query: String },
+    BulkMembership(Box<BulkMembership>),
+}
+
+/// Planned things listed in a bulk membership confirmation before the rest are counted.
+const BULK_PREVIEW_THINGS: usize = 8;
+
+/// Prompt shown before running an [`Operation`].
+#[derive(Clone, Debug, PartialEq)]
+pub struct Confirmation {
+    pub title: String,
+    pub lines: Vec<Line<'static>>,
+    pub operation: Operation,
+}
+
+impl Confirmation {
+    /// Asks to deploy `spec`, listing the component changes it makes.
+    pub fn create_deployment(spec: DeploymentSpec, diff: &[ComponentDiff]) -> Self {
+        let mut lines = vec![
+            Line::from(format!("Deploy to {}:", spec.target_arn)),
+            Line::default(),
+        ];
+        lines.extend(diff.iter().map(|change| {
+            let style = match change {
+                ComponentDiff::Added { .. } => Style::default().fg(Color::Green),
+                ComponentDiff::Removed { .. } => Style::default().fg(Color::Red),
+                ComponentDiff::VersionChanged { .. } => Style::default().fg(Color::Yellow),
+            };
+            Line::styled(change.to_string(), style)
+        }));
+        Self {
+            title: format!("Revise {}", spec.deployment_name),
+            lines,
+            operation: Operation::CreateDeployment(Box::new(spec)),
+        }
+    }
+
+    /// Asks to re-issue an earlier revision, listing everything it changes.
+    pub fn rollback(plan: RollbackPlan) -> Self {
+        let mut lines = vec![
+            Line::from(format!(
+                "Roll back {} from revision {} to {}:",
+                plan.spec.target_arn, plan.current.revision_id, plan.revision.revision_id
+            )),
+            Line::default(),
+        ];
+        lines.extend(
+            Detail::revision_diff_lines(&plan.diff)
+                .into_iter()
+                .map(|line| Line::styled(line.text.clone(), line.style())),
+        );
+        Self {
+            title: format!("Roll back {}", plan.spec.deployment_name),
+            lines,
+            operation: Operation::CreateDeployment(Box::new(plan.spec)),
+        }
+    }
+
+    /// Asks to publish a shadow update, listing the desired values it changes.
+    pub fn update_shadow(update: ShadowUpdate) -> Self {
+        let shadow = &update.current;
+        let mut lines = vec![
+            Line::from(format!(
+                "Update the {} shadow of {} (version {}):",
+                shadow.name(),
+                shadow.thing_name,
+                shadow.version
+            )),
+            Line::default(),
+        ];
+        lines.extend(
+            Detail::shadow_update_lines(&update)
+                .into_iter()
+                .map(|line| Line::styled(line.text.clone(), line.style())),
+        );
+        Self {
+            title: format!("Update {} shadow", shadow.thing_name),
+            lines,
+            operation: Operation::UpdateShadow(Box::new(update)),
+        }
+    }
+
+    /// Asks to save a fleet-indexing query as a dynamic thing group.
+    pub fn create_dynamic_group(group_name: String, results: &SearchResults) -> Self {
+        let matches = match results.as_slice().len() {
+            1 => String::from("1 thing"),
+            count => format!("{count} things"),
+        };
+        let lines = vec![
+            Line::from(format!("Create dynamic thing group {group_name}")),
+            Line::from(format!("for {}?", results.query)),
+            Line::default(),
+            Line::from(if results.truncated {
+                format!("It currently matches more than {matches} things.")
+            } else {
+                format!("It currently matches {matches} things.")
+            }),
+        ];
+        Self {
+            title: format!("Create {group_name}"),
+            lines,
+            operation: Operation::CreateDynamicGroup {
+                group_name,
+                query: results.query.clone(),
+            },
+        }
+    }
+
+    /// Asks to run a bulk membership change, listing what its dry run would change.
+    pub fn bulk_membership(
+        membership: BulkMembership,
+        preview: &BulkReport,
+        resume_file: &Path,
+    ) -> Self {
+        let planned: Vec<&str> = preview
+            .as_slice()
+            .iter()
+            .filter(|item| item.outcome == BulkOutcome::Planned)
+            .map(|item| item.thing_name.as_str())
+            .collect();
+        let (question, marker, style) = match membership.change {
+            MembershipChange::Add => (
+                format!("Add {} things to {}?", planned.len(), membership.group_name),
+                "+",
+                Style::default().fg(Color::Green),
+            ),
+            MembershipChange::Remove => (
+                format!(
+                    "Remove {} things from {}?",
+                    planned.len(),
+                    membership.group_name
+                ),
+                "-",
+                Style::default().fg(Color::Red),
+            ),
+        };
+        let mut lines = vec![
+            Line::from(question),
+            Line::from(format!("Dry run: {}", preview.summary())),
+            Line::default(),
+        ];
+        lines.extend(
+            planned
+                .iter()
+                .take(BULK_PREVIEW_THINGS)
+                .map(|thing_name| Line::styled(format!("  {marker} {thing_name}"), style)),
+        );
+        if planned.len() > BULK_PREVIEW_THINGS {
+            lines.push(Line::from(format!(
+                "  and {} more",
+                planned.len() - BULK_PREVIEW_THINGS
+            )));
+        }
+        lines.push(Line::default());
+        lines.push(Line::from(format!(
+            "Progress is saved to {}",
+            resume_file.display()
+        )));
+        Self {
+            title: format!("Bulk {} {}", membership.change, membership.group_name),
+            lines,
+            operation: Operation::BulkMembership(Box::new(membership)),
+        }
+    }
+
+    /// Asks to cancel an in-progress deployment.
+    pub fn cancel_deployment(deployment: &Deployment) -> Self {
+        let lines = vec![
+            Line::from(format!("Cancel deployment {}", deployment.deployment_id)),
+            Line::from(format!("to {}?", deployment.target_arn)),
+            Line::default(),
+            Line::styled(
+                "Devices that have not finished it stop applying it.",
+                Style::default().fg(Color::Red),
+            ),
+        ];
+        Self {
+            title: format!("Cancel {}", deployment.deployment_name),
+            lines,
+            operation: Operation::CancelDeployment(deployment.deployment_id.clone()),
+        }
+    }
+}
+
+#[derive(Default)]
+pub struct ConfirmPopup {
+    action_tx: Option<UnboundedSender<Action>>,
+    confirmation: Option<Confirmation>,
+}
+
+impl Component for ConfirmPopup {
+    fn register_action_handler(&mut self, tx: UnboundedSender<Action>) -> Result<()> {
+        self.action_tx = Some(tx);
+        Ok(())
+    }
+
+    fn update(&mut self, action: Action) -> Result<Option<Action>> {
+        match action {
+            Action::Confirm(confirmation) => {
+                self.confirmation = Some(confirmation);
+                return Ok(Some(Action::ModeChange(Mode::Confirm)));
+            }
+            Action::Confirmed => {
+                if let Some(confirmation) = self.confirmation.take() {
+                    if let Some(tx) = &self.action_tx {
+                        tx.send(Action::Execute(confirmation.operation))?;
+                    }
+                    return Ok(Some(Action::ModeChange(Mode::Normal)));
+                }
+            }
+            Action::ModeChange(Mode::Normal) => self.confirmation = None,
+            _ => {}
+        }
+        Ok(None)
+    }
+
+    fn draw(&mut self, frame: &mut Frame<'_>, area: Rect) -> Result<()> {
+        let Some(confirmation) = &self.confirmation else {
+            return Ok(());
+        };
+
+        let mut lines = confirmation.lines.clone();
+        lines.push(Line::default());
+        lines.push(Line::from(vec![
+            Span::styled(
+                "y",
+                Style::default().add_modifier(Modifier::BOLD).fg(Color::Red),
+            ),
+            Span::styled(" confirm  ", Style::default().fg(Color::Yellow)),
+            Span::styled(
+                "n",
+                Style::default().add_modifier(Modifier::BOLD).fg(Color::Red),
+            ),
+            Span::styled("/", Style::default().fg(Color::Yellow)),
+            Span::styled(
+                "ESC",
+                Style::default().add_modifier(Modifier::BOLD).fg(Color::Red),
+            ),
+            Span::styled(" cancel", Style::default().fg(Color::Yellow)),
+        ]));
+
+        let popup = Popup::new(Text::from(lines))
+            .title(Line::from(format!("|{}|", confirmation.title)).yellow())
+            .border_style(Style::default().fg(Color::Yellow))
+            .style(Style::default());
+        frame.render_widget(&popup, area);
+
+        Ok(())
+    }
+}
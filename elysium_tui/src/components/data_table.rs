@@ -1,8 +1,16 @@
-use std::sync::Arc;
+use std::{
+    collections::{BTreeMap, BTreeSet, HashMap},
+    path::PathBuf,
+    sync::Arc,
+};
 
 use color_eyre::Result;
 use crossterm::event::{KeyCode, KeyEvent, Event};
-use elysium::{AwsCloud, Property};
+use elysium::{
+    AwsCloud, BulkMembership, BulkOptions, BulkOutcome, DEFAULT_BULK_RATE, DEFAULT_SEARCH_LIMIT,
+    Error, Filter, GroupTreeItem, MembershipChange, PermissionRequest, Property, Resource,
+    SearchResults, parse_component_changes, run_bulk_membership,
+};
 use ratatui::{
     layout::{Constraint, Rect},
     prelude::*,
@@ -19,73 +27,178 @@ use tui_input::{backend::crossterm::EventHandler, Input};
 use crate::{
     action::Action,
     app::Mode,
-    components::Component,
+    components::{
+        Component,
+        confirm::{Confirmation, Operation},
+        detail::{Detail, Inspect},
+    },
     enums::TabsEnum,
     layout::{get_vertical_layout, DEFAULT_BORDER_STYLE},
     tui::Frame,
+    utils::{describe_error, get_data_dir},
 };
 
 static INPUT_SIZE: usize = 30;
 const SPINNER_SYMBOLS: [&str; 6] = ["⠷", "⠯", "⠟", "⠻", "⠽", "⠾"];
 
+/// What the text typed into the input box is used for.
+#[derive(Clone, Debug, Default, PartialEq)]
+enum InputPurpose {
+    #[default]
+    Filter,
+    /// Component changes for a new revision of the selected deployment.
+    Revise,
+    /// JSON to merge into the desired state of a shadow.
+    ShadowDesired {
+        thing_name: String,
+        shadow_name: Option<String>,
+    },
+    /// MQTT operation to check a thing's policies for, e.g. `publish sensors/temp`.
+    Permission { thing_name: String },
+    /// Fleet-indexing query, e.g. `connectivity.connected:false AND attributes.site:LON*`.
+    Search,
+    /// Name of the dynamic thing group to save the last query as.
+    GroupName,
+    /// Bulk membership change, e.g. `add london-retail` or `remove lab @things.csv`.
+    Bulk,
+}
+
 pub struct DataTable {
     // aws: Arc<RwLock<AwsCloud>>,
     aws: Arc<Mutex<AwsCloud>>,
     // aws: AwsCloud,
+    profile: String,
     active_tab: TabsEnum,
     action_tx: Option<UnboundedSender<Action>>,
     data_list: Vec<Vec<String>>,
+    /// Thing attributes of each row in `data_list`, empty for rows that are not things.
+    row_attributes: Vec<BTreeMap<String, String>>,
+    /// Tags of each row in `data_list`, empty for rows that cannot be tagged.
+    row_tags: Vec<BTreeMap<String, String>>,
+    /// Whether the rows could not be rebuilt because a background task held the data.
+    rows_stale: bool,
+    /// ID of the deployment, component or job in each row of `data_list`, so background tasks
+    /// look it up by ID rather than by a position that may change with a reload.
+    row_ids: Vec<String>,
+    /// Things found by the last query, shown on the Search tab.
+    search_results: SearchResults,
+    /// Thing groups whose child groups are hidden on the Thing Groups tab.
+    collapsed_groups: BTreeSet<String>,
+    /// Thing attributes shown as extra columns on the Core Devices tab.
+    attribute_columns: Vec<String>,
+    /// Maximum number of membership changes per second in bulk changes.
+    bulk_rate: u32,
     table_state: TableState,
     scrollbar_state: ScrollbarState,
     input: Input,
+    input_purpose: InputPurpose,
     is_loading: bool,
     task: JoinHandle<()>,
     mode: Mode,
     filter_str: String,
+    filter: Filter,
     spinner_index: usize,
+    load_errors: HashMap<Resource, String>,
 }
 
 impl DataTable {
-    pub fn new(aws: AwsCloud) -> Self {
+    pub fn new(aws: AwsCloud, profile: String) -> Self {
         // RwLock: often read but rarely write (https://docs.rs/tokio/latest/tokio/sync/struct.RwLock.html)
         // Mutex: update data on every read (https://docs.rs/tokio/latest/tokio/sync/struct.Mutex.html)
         // let aws = Arc::new(RwLock::new(aws));
         let aws = Arc::new(Mutex::new(aws));
         Self {
-            aws: aws,
+            aws,
+            profile,
             active_tab: TabsEnum::Devices,
             action_tx: None,
             data_list: Vec::new(),
+            row_attributes: Vec::new(),
+            row_tags: Vec::new(),
+            row_ids: Vec::new(),
+            rows_stale: false,
+            search_results: SearchResults::default(),
+            attribute_columns: Vec::new(),
+            bulk_rate: DEFAULT_BULK_RATE,
+            collapsed_groups: BTreeSet::new(),
             table_state: TableState::default().with_selected(0),
             scrollbar_state: ScrollbarState::new(0),
             input: Input::default().with_value(String::from("")),
+            input_purpose: InputPurpose::Filter,
             is_loading: false,
             task: tokio::spawn(async {}),
             mode: Mode::Normal,
             filter_str: String::from(""),
+            filter: Filter::default(),
             spinner_index: 0,
+            load_errors: HashMap::new(),
         }
     }
 
+    /// Shows the given thing attributes as extra columns on the Core Devices tab.
+    pub fn with_attribute_columns(mut self, attribute_columns: Vec<String>) -> Self {
+        self.attribute_columns = attribute_columns;
+        self
+    }
+
+    /// Limits bulk membership changes to `rate` per second.
+    pub fn with_bulk_rate(mut self, rate: u32) -> Self {
+        self.bulk_rate = rate;
+        self
+    }
+
     fn reset_data(&mut self) {
         self.data_list.clear();
+        self.row_attributes.clear();
+        self.row_tags.clear();
+        self.row_ids.clear();
+        self.load_errors.clear();
         // self.data_index = 0;
     }
 
-    fn load_data(&mut self) {
+    /// Loads everything in the background. With `retry_disabled`, services found not to be
+    /// enabled for the account are tried again.
+    fn load_data(&mut self, retry_disabled: bool) {
         self.reset_data();
 
         self.is_loading = true;
 
         let tx = self.action_tx.clone().unwrap();
         let aws = self.aws.clone();
+        let profile = self.profile.clone();
 
         self.task = tokio::spawn(async move {
             // let mut write = aws.write().await;
             // write.load().await.unwrap();
             // drop(write);
-            aws.lock().await.load().await.unwrap();
+            // Load into a copy, so the table keeps rendering the previous data meanwhile.
+            let mut cloud = aws.lock().await.clone();
+            if retry_disabled {
+                cloud.retry_disabled_services();
+            }
+            let result = cloud.load().await;
+            let summary = cloud.summary.clone();
+            *aws.lock().await = cloud;
+            match result {
+                Ok(()) => {}
+                Err(Error::PartialLoad(failures)) => {
+                    for failure in failures {
+                        tracing::error!("Failed to load {}: {}", failure.resource, failure.error);
+                        tx.send(Action::LoadFailed(
+                            failure.resource,
+                            describe_error(&failure.error, &profile),
+                        ))
+                        .unwrap_or_default();
+                    }
+                }
+                Err(e) => {
+                    tx.send(Action::Error(describe_error(&e, &profile)))
+                        .unwrap_or_default();
+                }
+            }
             // tokio::time::sleep(std::time::Duration::from_millis(5000)).await;
+            tx.send(Action::SummaryLoaded(summary.map(Box::new)))
+                .unwrap_or_default();
             tx.send(Action::DataLoaded).unwrap_or_default();
         });
     }
@@ -136,40 +249,601 @@ impl DataTable {
         self.scrollbar_state = self.scrollbar_state.position(index);
     }
 
-    fn make_table(
-        data_list: &Vec<Vec<String>>,
+    fn row_matches(
+        row: &[String],
+        attributes: Option<&BTreeMap<String, String>>,
+        tags: Option<&BTreeMap<String, String>>,
+        filter: &Filter,
+    ) -> bool {
+        let empty = BTreeMap::new();
+        filter.is_empty()
+            || filter.matches(
+                &row[0],
+                attributes.unwrap_or(&empty),
+                tags.unwrap_or(&empty),
+            )
+    }
+
+    /// Indices into `data_list` of the rows that pass the filter.
+    fn filtered_indices<'a>(
+        data_list: &'a [Vec<String>],
+        row_attributes: &'a [BTreeMap<String, String>],
+        row_tags: &'a [BTreeMap<String, String>],
+        filter: &'a Filter,
+    ) -> impl Iterator<Item = usize> + 'a {
+        data_list
+            .iter()
+            .enumerate()
+            .filter(move |(idx, row)| {
+                Self::row_matches(row, row_attributes.get(*idx), row_tags.get(*idx), filter)
+            })
+            .map(|(idx, _)| idx)
+    }
+
+    fn selected_row(&self) -> Option<&Vec<String>> {
+        self.data_list.get(self.selected_index()?)
+    }
+
+    /// Index into `data_list` of the selected row, taking the filter into account.
+    fn selected_index(&self) -> Option<usize> {
+        Self::filtered_indices(
+            &self.data_list,
+            &self.row_attributes,
+            &self.row_tags,
+            &self.filter,
+        )
+        .nth(self.table_state.selected()?)
+    }
+
+    /// ID of the deployment, component or job in the selected row.
+    fn selected_id(&self) -> Option<String> {
+        self.row_ids.get(self.selected_index()?).cloned()
+    }
+
+    /// Message for a row whose resource is no longer listed after a reload.
+    /// Copies the cloud out of the lock, so that background tasks do not hold it across network
+    /// calls and the table keeps rendering.
+    async fn snapshot(aws: &Mutex<AwsCloud>) -> AwsCloud {
+        aws.lock().await.clone()
+    }
+
+    fn gone(title: String, kind: &str, id: &str) -> Detail {
+        Detail::message(
+            title,
+            format!("{kind} {id} is no longer listed, press Ctrl+R to reload"),
+        )
+    }
+
+    fn show_detail(&mut self, inspect: Option<Inspect>) {
+        let Some(row) = self.selected_row() else {
+            return;
+        };
+        let id = self.selected_id().unwrap_or_default();
+        let aws = self.aws.clone();
+
+        match (self.active_tab, inspect) {
+            (TabsEnum::Devices, None) => {
+                let thing_name = row[0].clone();
+                self.spawn_detail(thing_name.clone(), async move {
+                    let aws = Self::snapshot(&aws).await;
+                    let components = aws.installed_components(&thing_name).await?;
+                    Ok(Detail::installed_components(&thing_name, &components))
+                });
+            }
+            (TabsEnum::Devices, Some(Inspect::EffectiveDeployments)) => {
+                let thing_name = row[0].clone();
+                self.spawn_detail(thing_name.clone(), async move {
+                    let aws = Self::snapshot(&aws).await;
+                    let deployments = aws.effective_deployments(&thing_name).await?;
+                    Ok(Detail::effective_deployments(&thing_name, &deployments))
+                });
+            }
+            (TabsEnum::Devices, Some(Inspect::Shadows)) => {
+                let thing_name = row[0].clone();
+                self.spawn_detail(thing_name.clone(), async move {
+                    let aws = Self::snapshot(&aws).await;
+                    let shadows = aws.shadows(&thing_name).await?;
+                    Ok(Detail::shadows(&thing_name, &shadows))
+                });
+            }
+            (TabsEnum::Devices, Some(Inspect::Policies)) => {
+                let thing_name = row[0].clone();
+                self.spawn_detail(thing_name.clone(), async move {
+                    let aws = Self::snapshot(&aws).await;
+                    let policies = aws.thing_policies(&thing_name).await?;
+                    Ok(Detail::policies(&thing_name, &policies))
+                });
+            }
+            (TabsEnum::Devices, Some(Inspect::Security)) => {
+                let thing_name = row[0].clone();
+                self.spawn_detail(thing_name.clone(), async move {
+                    let aws = aws.lock().await;
+                    Ok(Detail::security(&thing_name, aws.security.get(&thing_name)))
+                });
+            }
+            (TabsEnum::ThingGroups, None | Some(Inspect::AllMembers)) => {
+                let group_name = Self::group_name(&row[0]).to_string();
+                let recursive = inspect.is_some();
+                self.spawn_detail(group_name.clone(), async move {
+                    let aws = Self::snapshot(&aws).await;
+                    let members = aws.thing_group_members(&group_name, recursive).await?;
+                    Ok(Detail::group_members(&group_name, recursive, &members))
+                });
+            }
+            (TabsEnum::Deployments, None) => {
+                let name = row[0].clone();
+                self.spawn_detail(name.clone(), async move {
+                    let aws = Self::snapshot(&aws).await;
+                    if aws.deployments.get(&id).is_none() {
+                        return Ok(Self::gone(name, "Deployment", &id));
+                    }
+                    let deployment = aws.deployment_detail(&id).await?;
+                    Ok(Detail::deployment(&deployment))
+                });
+            }
+            (TabsEnum::Deployments, Some(Inspect::Dependencies)) => {
+                let name = row[0].clone();
+                self.spawn_detail(name.clone(), async move {
+                    let aws = Self::snapshot(&aws).await;
+                    if aws.deployments.get(&id).is_none() {
+                        return Ok(Self::gone(name, "Deployment", &id));
+                    }
+                    let deployment = aws.deployment_detail(&id).await?;
+                    let resolution =
+                        aws.dependency_resolution(&deployment.target_arn, &deployment.components);
+                    let graph = resolution.resolve().await?;
+                    Ok(Detail::dependencies(&name, &graph))
+                });
+            }
+            (TabsEnum::Deployments, Some(Inspect::Changes)) => {
+                let name = row[0].clone();
+                self.spawn_detail(name.clone(), async move {
+                    let aws = Self::snapshot(&aws).await;
+                    let Some(deployment) = aws.deployments.get(&id) else {
+                        return Ok(Self::gone(name, "Deployment", &id));
+                    };
+                    let target_arn = deployment.target_arn.clone();
+                    Ok(match aws.latest_changes(&target_arn).await? {
+                        Some(diff) => Detail::revision_diff(&name, &diff),
+                        None => {
+                            Detail::message(name, format!("{target_arn} has a single revision"))
+                        }
+                    })
+                });
+            }
+            (TabsEnum::Deployments, Some(Inspect::History)) => {
+                let name = row[0].clone();
+                self.spawn_detail(name.clone(), async move {
+                    let aws = Self::snapshot(&aws).await;
+                    let Some(deployment) = aws.deployments.get(&id) else {
+                        return Ok(Self::gone(name, "Deployment", &id));
+                    };
+                    let target_arn = deployment.target_arn.clone();
+                    let history = aws.deployment_history(&target_arn).await?;
+                    Ok(Detail::deployment_history(&name, &history))
+                });
+            }
+            (TabsEnum::Components, None) => {
+                let name = row[0].clone();
+                self.spawn_detail(name.clone(), async move {
+                    let aws = Self::snapshot(&aws).await;
+                    let Some(component) = aws.components.get(&id).cloned() else {
+                        return Ok(Self::gone(name, "Component", &id));
+                    };
+                    let versions = aws.component_versions(&component.arn).await?;
+                    Ok(Detail::component_versions(&component, &versions))
+                });
+            }
+            (TabsEnum::Components, Some(Inspect::Recipe)) => {
+                let name = row[0].clone();
+                self.spawn_detail(name.clone(), async move {
+                    let aws = Self::snapshot(&aws).await;
+                    let Some(component) = aws.components.get(&id) else {
+                        return Ok(Self::gone(name, "Component", &id));
+                    };
+                    let arn = component.latest_version_arn();
+                    let recipe = aws.component_recipe(&arn).await?;
+                    Ok(Detail::recipe(&recipe))
+                });
+            }
+            (TabsEnum::Jobs, None) => {
+                let job_id = row[0].clone();
+                self.spawn_detail(job_id.clone(), async move {
+                    let aws = Self::snapshot(&aws).await;
+                    let Some(job) = aws.jobs.get(&id).cloned() else {
+                        return Ok(Self::gone(job_id, "Job", &id));
+                    };
+                    let executions = aws.job_executions(&job.job_id).await?;
+                    Ok(Detail::job_executions(&job, &executions))
+                });
+            }
+            _ => {}
+        }
+    }
+
+    /// Plans a new revision of the selected deployment and asks to confirm it.
+    fn revise(&self, changes: &str) {
+        let Some(deployment_id) = self.selected_id() else {
+            return;
+        };
+        let tx = self.action_tx.clone().unwrap();
+        let profile = self.profile.clone();
+        let aws = self.aws.clone();
+
+        let changes = match parse_component_changes(changes) {
+            Ok(changes) => changes,
+            Err(e) => {
+                tx.send(Action::ShowDetail(Detail::message("Revise", e.to_string())))
+                    .unwrap_or_default();
+                return;
+            }
+        };
+
+        tokio::spawn(async move {
+            let aws = Self::snapshot(&aws).await;
+            let Some(deployment) = aws.deployments.get(&deployment_id) else {
+                let detail = Self::gone(String::from("Revise"), "Deployment", &deployment_id);
+                tx.send(Action::ShowDetail(detail)).unwrap_or_default();
+                return;
+            };
+            let target_arn = deployment.target_arn.clone();
+            let action = match aws.revise_deployment(&target_arn, &changes).await {
+                Ok(plan) if plan.is_noop() => Action::ShowDetail(Detail::message(
+                    "Revise",
+                    format!("No component changes for {target_arn}"),
+                )),
+                Ok(plan) => Action::Confirm(Confirmation::create_deployment(plan.spec, &plan.diff)),
+                Err(e) => {
+                    tracing::error!("Failed to plan revision of {}: {}", target_arn, e);
+                    Action::ShowDetail(Detail::message("Revise", describe_error(&e, &profile)))
+                }
+            };
+            tx.send(action).unwrap_or_default();
+        });
+    }
+
+    /// Plans re-issuing an earlier revision of a target's deployment and asks to confirm it.
+    fn rollback(&self, target_arn: String, revision_id: String) {
+        let tx = self.action_tx.clone().unwrap();
+        let profile = self.profile.clone();
+        let aws = self.aws.clone();
+
+        tokio::spawn(async move {
+            let aws = Self::snapshot(&aws).await;
+            let action = match aws.rollback_deployment(&target_arn, &revision_id).await {
+                Ok(plan) if plan.is_noop() => Action::ShowDetail(Detail::message(
+                    "Roll back",
+                    format!(
+                        "Revision {revision_id} deploys the same as the latest for {target_arn}"
+                    ),
+                )),
+                Ok(plan) => Action::Confirm(Confirmation::rollback(plan)),
+                Err(e) => {
+                    tracing::error!("Failed to plan rollback of {}: {}", target_arn, e);
+                    Action::ShowDetail(Detail::message("Roll back", describe_error(&e, &profile)))
+                }
+            };
+            tx.send(action).unwrap_or_default();
+        });
+    }
+
+    /// Shows a shadow document.
+    fn show_shadow(&self, thing_name: String, shadow_name: Option<String>) {
+        let aws = self.aws.clone();
+        self.spawn_detail(thing_name.clone(), async move {
+            let aws = Self::snapshot(&aws).await;
+            let shadow = aws.shadow(&thing_name, shadow_name.as_deref()).await?;
+            Ok(Detail::shadow(&shadow))
+        });
+    }
+
+    /// Plans merging `desired` into the desired state of a shadow and asks to confirm it.
+    fn update_shadow(&self, thing_name: String, shadow_name: Option<String>, desired: String) {
+        let tx = self.action_tx.clone().unwrap();
+        let profile = self.profile.clone();
+        let aws = self.aws.clone();
+
+        tokio::spawn(async move {
+            let aws = Self::snapshot(&aws).await;
+            let plan = aws
+                .plan_shadow_update(&thing_name, shadow_name.as_deref(), &desired)
+                .await;
+            let action = match plan {
+                Ok(update) if update.is_noop() => Action::ShowDetail(Detail::message(
+                    "Update shadow",
+                    format!(
+                        "The desired state of {} is already up to date",
+                        update.current.name()
+                    ),
+                )),
+                Ok(update) => Action::Confirm(Confirmation::update_shadow(update)),
+                Err(e) => {
+                    tracing::error!("Failed to plan shadow update of {}: {}", thing_name, e);
+                    Action::ShowDetail(Detail::message(
+                        "Update shadow",
+                        describe_error(&e, &profile),
+                    ))
+                }
+            };
+            tx.send(action).unwrap_or_default();
+        });
+    }
+
+    /// Evaluates the policies of a thing's certificates for a request such as `publish a/b`.
+    fn check_permission(&self, thing_name: String, request: &str) {
+        let request: PermissionRequest = match request.parse() {
+            Ok(request) => request,
+            Err(e) => {
+                self.action_tx
+                    .clone()
+                    .unwrap()
+                    .send(Action::ShowDetail(Detail::message(
+                        "Check permission",
+                        e.to_string(),
+                    )))
+                    .unwrap_or_default();
+                return;
+            }
+        };
+        let aws = self.aws.clone();
+        self.spawn_detail(thing_name.clone(), async move {
+            let aws = Self::snapshot(&aws).await;
+            let evaluations = aws.check_permission(&thing_name, &request).await?;
+            Ok(Detail::permission_check(
+                &thing_name,
+                &request,
+                &evaluations,
+            ))
+        });
+    }
+
+    /// Queries the fleet index in the background and shows the results on the Search tab.
+    fn search(&self, query: String) {
+        let tx = self.action_tx.clone().unwrap();
+        let profile = self.profile.clone();
+        let aws = self.aws.clone();
+
+        tokio::spawn(async move {
+            let aws = Self::snapshot(&aws).await;
+            let action = match aws.search(&query, DEFAULT_SEARCH_LIMIT).await {
+                Ok(results) => Action::SearchResults(results),
+                Err(e) => {
+                    tracing::error!("Failed to search for {}: {}", query, e);
+                    Action::ShowDetail(Detail::message("Search", describe_error(&e, &profile)))
+                }
+            };
+            tx.send(action).unwrap_or_default();
+        });
+    }
+
+    /// Where a bulk membership change records its progress, so an interrupted run can resume.
+    fn resume_file(membership: &BulkMembership) -> PathBuf {
+        get_data_dir().join(format!(
+            "bulk-{}-{}.resume",
+            membership.change, membership.group_name
+        ))
+    }
+
+    /// Previews a bulk membership change such as `add london-retail @things.csv` and asks to
+    /// confirm it. Without a file, the things are the rows shown on the Core Devices or Search tab.
+    fn plan_bulk(&self, command: &str) {
+        let tx = self.action_tx.clone().unwrap();
+        let fail = |message: String| {
+            tx.send(Action::ShowDetail(Detail::message("Bulk change", message)))
+                .unwrap_or_default();
+        };
+
+        let words: Vec<&str> = command.split_whitespace().collect();
+        let (change, group_name, file) = match words.as_slice() {
+            [change, group_name] => (change, group_name, None),
+            [change, group_name, file] if file.starts_with('@') => {
+                (change, group_name, Some(&file[1..]))
+            }
+            _ => return fail(String::from("Expected add|remove <group> [@file]")),
+        };
+        let change: MembershipChange = match change.parse() {
+            Ok(change) => change,
+            Err(e) => return fail(e.to_string()),
+        };
+        let thing_names = match file {
+            Some(path) => match elysium::read_thing_names(path) {
+                Ok(thing_names) => thing_names,
+                Err(e) => return fail(e.to_string()),
+            },
+            None if matches!(self.active_tab, TabsEnum::Devices | TabsEnum::Search) => {
+                Self::filtered_indices(
+                    &self.data_list,
+                    &self.row_attributes,
+                    &self.row_tags,
+                    &self.filter,
+                )
+                .map(|idx| self.data_list[idx][0].clone())
+                .collect()
+            }
+            None => {
+                return fail(String::from(
+                    "Give a @file of thing names, or filter the Core Devices or Search tab",
+                ));
+            }
+        };
+        let membership = BulkMembership::new(change, *group_name, thing_names);
+        let resume_file = Self::resume_file(&membership);
+
+        let profile = self.profile.clone();
+        let aws = self.aws.clone();
+        tokio::spawn(async move {
+            let options = BulkOptions {
+                dry_run: true,
+                resume_file: Some(resume_file.clone()),
+                ..Default::default()
+            };
+            let backend = aws.lock().await.backend();
+            let action = match run_bulk_membership(backend.as_ref(), &membership, &options).await {
+                Ok(preview) if preview.count(&BulkOutcome::Planned) == 0 => {
+                    Action::ShowDetail(Detail::bulk_report(&preview))
+                }
+                Ok(preview) => Action::Confirm(Confirmation::bulk_membership(
+                    membership,
+                    &preview,
+                    &resume_file,
+                )),
+                Err(e) => {
+                    tracing::error!(
+                        "Failed to preview bulk change of {}: {}",
+                        membership.group_name,
+                        e
+                    );
+                    Action::ShowDetail(Detail::message("Bulk change", describe_error(&e, &profile)))
+                }
+            };
+            tx.send(action).unwrap_or_default();
+        });
+    }
+
+    /// Asks to confirm canceling the selected deployment if it is still in progress.
+    fn cancel_deployment(&self) {
+        let Some(deployment_id) = self.selected_id() else {
+            return;
+        };
+        let tx = self.action_tx.clone().unwrap();
+        let aws = self.aws.clone();
+
+        tokio::spawn(async move {
+            let deployment = aws.lock().await.deployments.get(&deployment_id).cloned();
+            let Some(deployment) = deployment else {
+                let detail = Self::gone(String::from("Cancel"), "Deployment", &deployment_id);
+                tx.send(Action::ShowDetail(detail)).unwrap_or_default();
+                return;
+            };
+            let action = if deployment.deployment_status == "ACTIVE" {
+                Action::Confirm(Confirmation::cancel_deployment(&deployment))
+            } else {
+                Action::ShowDetail(Detail::message(
+                    "Cancel",
+                    format!(
+                        "Deployment {} is {} and can no longer be canceled",
+                        deployment.deployment_name, deployment.deployment_status
+                    ),
+                ))
+            };
+            tx.send(action).unwrap_or_default();
+        });
+    }
+
+    /// Runs a confirmed operation, then reloads the data or shows what it changed.
+    fn execute(&self, operation: Operation) {
+        let tx = self.action_tx.clone().unwrap();
+        let profile = self.profile.clone();
+        let aws = self.aws.clone();
+        let rate = self.bulk_rate;
+
+        if let Operation::BulkMembership(membership) = &operation {
+            tx.send(Action::ShowDetail(Detail::message(
+                format!("Bulk {} {}", membership.change, membership.group_name),
+                format!(
+                    "Changing {} things at up to {rate} per second..",
+                    membership.thing_names.len()
+                ),
+            )))
+            .unwrap_or_default();
+        }
+
+        tokio::spawn(async move {
+            let aws = Self::snapshot(&aws).await;
+            let result = match &operation {
+                Operation::CreateDeployment(spec) => {
+                    aws.create_deployment(spec).await.map(|_| Action::Reload)
+                }
+                Operation::CancelDeployment(deployment_id) => aws
+                    .cancel_deployment(deployment_id)
+                    .await
+                    .map(|()| Action::Reload),
+                Operation::UpdateShadow(update) => aws
+                    .update_shadow(update)
+                    .await
+                    .map(|shadow| Action::ShowDetail(Detail::shadow(&shadow))),
+                Operation::CreateDynamicGroup { group_name, query } => aws
+                    .create_dynamic_group(group_name, query)
+                    .await
+                    .map(|_| Action::Reload),
+                Operation::BulkMembership(membership) => {
+                    let options = BulkOptions {
+                        rate,
+                        resume_file: Some(Self::resume_file(membership)),
+                        ..Default::default()
+                    };
+                    run_bulk_membership(aws.backend().as_ref(), membership, &options)
+                        .await
+                        .map(|report| Action::ShowDetail(Detail::bulk_report(&report)))
+                }
+            };
+            match result {
+                Ok(action) => tx.send(action).unwrap_or_default(),
+                Err(e) => {
+                    tracing::error!("Failed to run {:?}: {}", operation, e);
+                    tx.send(Action::ShowDetail(Detail::message(
+                        "Failed",
+                        describe_error(&e, &profile),
+                    )))
+                    .unwrap_or_default();
+                }
+            }
+        });
+    }
+
+    /// Fetches a drill-down in the background and shows it, or the error, once loaded.
+    fn spawn_detail(
+        &self,
+        title: String,
+        fetch: impl Future<Output = elysium::Result<Detail>> + Send + 'static,
+    ) {
+        let tx = self.action_tx.clone().unwrap();
+        let profile = self.profile.clone();
+
+        tokio::spawn(async move {
+            let detail = match fetch.await {
+                Ok(detail) => detail,
+                Err(e) => {
+                    tracing::error!("Failed to load {}: {}", title, e);
+                    Detail::message(title, describe_error(&e, &profile))
+                }
+            };
+            tx.send(Action::ShowDetail(detail)).unwrap_or_default();
+        });
+    }
+
+    fn make_table<'a>(
+        data_list: Vec<&'a Vec<String>>,
+        total: usize,
         is_loading: bool,
-        filter_str: String,
-    ) -> Table {
-        let header = Row::new(vec!["Name", "Status", "Last Status Update",])
+        notice: Option<Span<'a>>,
+        headers: Vec<&'a str>,
+        widths: Vec<Constraint>,
+        key_hints: &[(&'static str, &'static str)],
+    ) -> Table<'a> {
+        let header = Row::new(headers)
             .style(Style::default().fg(Color::Yellow))
             .top_margin(1)
             .bottom_margin(1);
         let mut rows = Vec::new();
 
         for data in data_list {
-            let (name, status, timestamp) = (data[0].clone(), data[1].clone(), data[2].clone());
-
-            if filter_str.is_empty() || (name.contains(&filter_str) && !filter_str.is_empty()) {
-                rows.push(Row::new(vec![
-                    Cell::from(Span::styled(
-                        format!("{}", name),
+            rows.push(Row::new(data.iter().enumerate().map(
+                |(idx, value)| match idx {
+                    0 => Cell::from(Span::styled(
+                        value.clone(),
                         Style::default().fg(Color::Blue),
                     )),
-                    Cell::from(status.green()),
-                    Cell::from(timestamp),
-                    // Cell::from(sip.vendor.as_str().yellow()),
-                ]));
-            }
+                    1 => Cell::from(value.clone().green()),
+                    _ => Cell::from(value.clone()),
+                },
+            )));
         }
 
         let mut loading_title = vec![
             Span::styled("|", Style::default().fg(Color::Yellow)),
             "◉ ".green(),
-            Span::styled(
-                format!("{}", data_list.len()),
-                Style::default().fg(Color::Red),
-            ),
+            Span::styled(format!("{}", total), Style::default().fg(Color::Red)),
             Span::styled("|", Style::default().fg(Color::Yellow)),
         ];
         if is_loading {
@@ -178,16 +852,11 @@ impl DataTable {
             loading_title.push(format!("/{}", 0).green());
             loading_title.push(")".yellow());
         }
+        if let Some(notice) = notice {
+            loading_title.push(notice);
+        }
 
-        let table = Table::new(
-            rows,
-            [
-                Constraint::Length(30),
-                Constraint::Length(10),
-                Constraint::Fill(1),
-                Constraint::Fill(1),
-            ],
-        )
+        Table::new(rows, widths)
         .header(header)
         .block(
             Block::new()
@@ -206,6 +875,20 @@ impl DataTable {
                 //     ]).left_aligned()
                 // )
                 .title_top(Line::from(loading_title).left_aligned())
+                .title_bottom(Line::from(key_hints.iter().fold(
+                    vec![Span::styled("|", Style::default().fg(Color::Yellow))],
+                    |mut spans, (key, description)| {
+                        spans.push(Span::styled(
+                            *key,
+                            Style::default().add_modifier(Modifier::BOLD).fg(Color::Red),
+                        ));
+                        spans.push(Span::styled(
+                            format!(" {description}|"),
+                            Style::default().fg(Color::Yellow),
+                        ));
+                        spans
+                    },
+                )).left_aligned())
                 .title_bottom(
                     Line::from(vec![
                         Span::styled("|", Style::default().fg(Color::Yellow)),
@@ -217,31 +900,71 @@ impl DataTable {
                 .border_style(Style::default().fg(Color::Rgb(100, 100, 100)))
                 .borders(Borders::ALL)
                 .border_type(DEFAULT_BORDER_STYLE),
-        )
-        .highlight_symbol(String::from(char::from_u32(0x25b6).unwrap_or('>')).red())
-        .column_spacing(1);
-        table
+            )
+            .highlight_symbol(String::from(char::from_u32(0x25b6).unwrap_or('>')).red())
+            .column_spacing(1)
     }
 
     pub fn make_scrollbar<'a>() -> Scrollbar<'a> {
-        let scrollbar = Scrollbar::default()
+        Scrollbar::default()
             .orientation(ScrollbarOrientation::VerticalRight)
             .style(Style::default().fg(Color::Rgb(100, 100, 100)))
             .begin_symbol(None)
-            .end_symbol(None);
-        scrollbar
+            .end_symbol(None)
     }
 
-    fn make_input(&self, scroll: usize) -> Paragraph {
-        let input = Paragraph::new(self.input.value())
+    fn make_input(&self, scroll: usize) -> Paragraph<'_> {
+        let purpose_title = match self.input_purpose {
+            InputPurpose::Filter => Line::default(),
+            InputPurpose::Revise => Line::from(vec![
+                Span::raw("|"),
+                Span::styled("name=version -name", Style::default().fg(Color::Yellow)),
+                Span::raw("|"),
+            ]),
+            InputPurpose::ShadowDesired { .. } => Line::from(vec![
+                Span::raw("|"),
+                Span::styled(
+                    "desired JSON, null removes",
+                    Style::default().fg(Color::Yellow),
+                ),
+                Span::raw("|"),
+            ]),
+            InputPurpose::Permission { .. } => Line::from(vec![
+                Span::raw("|"),
+                Span::styled("publish topic", Style::default().fg(Color::Yellow)),
+                Span::raw("|"),
+            ]),
+            InputPurpose::Search => Line::from(vec![
+                Span::raw("|"),
+                Span::styled("fleet index query", Style::default().fg(Color::Yellow)),
+                Span::raw("|"),
+            ]),
+            InputPurpose::GroupName => Line::from(vec![
+                Span::raw("|"),
+                Span::styled("dynamic group name", Style::default().fg(Color::Yellow)),
+                Span::raw("|"),
+            ]),
+            InputPurpose::Bulk => Line::from(vec![
+                Span::raw("|"),
+                Span::styled(
+                    "add|remove group [@file]",
+                    Style::default().fg(Color::Yellow),
+                ),
+                Span::raw("|"),
+            ]),
+        };
+        Paragraph::new(self.input.value())
             .style(Style::default().fg(Color::Green))
             .scroll((0, scroll as u16))
             .block(
                 Block::default()
+                    .title_top(purpose_title.left_aligned())
                     .borders(Borders::ALL)
                     .border_style(match self.mode {
                         Mode::Input => Style::default().fg(Color::Green),
-                        Mode::Normal => Style::default().fg(Color::Rgb(100, 100, 100)),
+                        Mode::Normal | Mode::Detail | Mode::Confirm => {
+                            Style::default().fg(Color::Rgb(100, 100, 100))
+                        }
                     })
                     .border_type(DEFAULT_BORDER_STYLE)
                     .title_bottom(Line::from(vec![
@@ -267,11 +990,10 @@ impl DataTable {
                         ),
                         Span::raw("|"),
                     ]).centered())
-            );
-        input
+            )
     }
 
-    fn make_spinner(&self) -> Span {
+    fn make_spinner(&self) -> Span<'_> {
         let spinner = SPINNER_SYMBOLS[self.spinner_index];
         Span::styled(
             format!("{spinner}loading.."),
@@ -280,13 +1002,203 @@ impl DataTable {
     }
 
     fn set_filter_str(&mut self, value: String) {
+        self.filter = Filter::parse(&value);
         self.filter_str = value;
     }
+
+    /// Row of the Thing Groups tab, with the name indented under its parent.
+    fn group_row(item: &GroupTreeItem<'_>) -> Vec<String> {
+        let marker = match (item.children, item.is_collapsed) {
+            (0, _) => "•",
+            (_, true) => "▸",
+            (_, false) => "▾",
+        };
+        vec![
+            format!(
+                "{:indent$}{marker} {}",
+                "",
+                item.group.name,
+                indent = item.depth * 2
+            ),
+            item.children.to_string(),
+            item.group.arn.to_string(),
+        ]
+    }
+
+    /// The group name in a Thing Groups row. Group names cannot contain spaces, so it is the
+    /// last word of the indented label.
+    fn group_name(label: &str) -> &str {
+        label.rsplit(' ').next().unwrap_or(label)
+    }
+
+    /// Expands the selected thing group, or collapses it. Collapsing a group that shows no
+    /// children selects its parent instead, as in a file tree.
+    fn toggle_group(&mut self, expand: bool) -> Result<()> {
+        let Some(row) = self.selected_row() else {
+            return Ok(());
+        };
+        let name = Self::group_name(&row[0]).to_string();
+        // Ignore the key rather than block the UI while a background task holds the data.
+        let Ok(aws) = self.aws.try_lock() else {
+            return Ok(());
+        };
+        let has_children = aws.groups.children(&name).next().is_some();
+        let is_collapsed = self.collapsed_groups.contains(&name);
+        let parent = if expand || (has_children && !is_collapsed) {
+            None
+        } else {
+            aws.groups
+                .get(&name)
+                .and_then(|group| group.parent_group_name.clone())
+        };
+        drop(aws);
+
+        if expand {
+            self.collapsed_groups.remove(&name);
+        } else if parent.is_none() {
+            self.collapsed_groups.insert(name);
+        }
+        self.tab_changed(TabsEnum::ThingGroups)?;
+
+        if let Some(parent) = parent {
+            let position = Self::filtered_indices(
+                &self.data_list,
+                &self.row_attributes,
+                &self.row_tags,
+                &self.filter,
+            )
+            .position(|idx| Self::group_name(&self.data_list[idx][0]) == parent);
+            if let Some(position) = position {
+                self.table_state.select(Some(position));
+                self.scrollbar_state = self.scrollbar_state.position(position);
+            }
+        }
+        Ok(())
+    }
+
+    /// Note for the table title: why the tab failed to load, or the query on the Search tab.
+    fn notice(&self) -> Option<Span<'static>> {
+        match self.active_tab.resource() {
+            Some(resource) => self
+                .load_errors
+                .get(&resource)
+                .map(|load_error| format!(" {load_error} ").red()),
+            None if self.search_results.query.is_empty() => None,
+            None => {
+                let mut notice = format!(" {} ", self.search_results.query);
+                if self.search_results.truncated {
+                    notice.push_str(&format!("(first {DEFAULT_SEARCH_LIMIT} things) "));
+                }
+                Some(notice.yellow())
+            }
+        }
+    }
+
+    /// Headers and widths of a tab, with the attribute columns on Core Devices.
+    fn columns(tab: TabsEnum, attribute_columns: &[String]) -> (Vec<&str>, Vec<Constraint>) {
+        let mut headers = tab.headers().to_vec();
+        let mut widths = tab.widths().to_vec();
+        if tab == TabsEnum::Devices {
+            headers.extend(attribute_columns.iter().map(String::as_str));
+            widths.extend(attribute_columns.iter().map(|_| Constraint::Fill(1)));
+        }
+        (headers, widths)
+    }
+
+    /// Rebuilds the rows of the active tab from the loaded data.
+    ///
+    /// This runs on the UI thread, so it never waits for the data: while a background task
+    /// holds it, the previous rows stay and are rebuilt on a later tick.
+    fn refresh_rows(&mut self) {
+        let aws = self.aws.clone();
+        let Ok(aws) = aws.try_lock() else {
+            self.rows_stale = true;
+            return;
+        };
+        self.rows_stale = false;
+
+        self.data_list.clear();
+        self.row_attributes.clear();
+        self.row_tags.clear();
+        self.row_ids.clear();
+
+        match self.active_tab {
+            TabsEnum::Devices => {
+                self.data_list = aws
+                    .devices
+                    .items_with(&aws.security, &self.attribute_columns);
+                self.row_attributes = aws
+                    .devices
+                    .as_slice()
+                    .iter()
+                    .map(|device| device.attributes.clone())
+                    .collect();
+                self.row_tags = aws
+                    .devices
+                    .as_slice()
+                    .iter()
+                    .map(|device| device.tags.clone())
+                    .collect();
+            }
+            TabsEnum::ThingGroups => {
+                let tree = aws.groups.tree(&self.collapsed_groups);
+                self.data_list = tree.iter().map(Self::group_row).collect();
+                self.row_tags = tree.iter().map(|item| item.group.tags.clone()).collect();
+            }
+            TabsEnum::Deployments => {
+                self.data_list = aws.deployments.items();
+                self.row_tags = aws
+                    .deployments
+                    .as_slice()
+                    .iter()
+                    .map(|deployment| deployment.tags.clone())
+                    .collect();
+                self.row_ids = aws
+                    .deployments
+                    .as_slice()
+                    .iter()
+                    .map(|deployment| deployment.deployment_id.clone())
+                    .collect();
+            }
+            TabsEnum::Components => {
+                self.data_list = aws.components.items();
+                self.row_ids = aws
+                    .components
+                    .as_slice()
+                    .iter()
+                    .map(|component| component.arn.clone())
+                    .collect();
+            }
+            TabsEnum::Jobs => {
+                self.data_list = aws.jobs.items();
+                self.row_ids = aws
+                    .jobs
+                    .as_slice()
+                    .iter()
+                    .map(|job| job.job_id.clone())
+                    .collect();
+            }
+            TabsEnum::Certificates => {
+                self.data_list = aws.certificates.items();
+            }
+            TabsEnum::Search => {
+                self.data_list = self.search_results.items();
+                self.row_attributes = self
+                    .search_results
+                    .as_slice()
+                    .iter()
+                    .map(|thing| thing.attributes.clone())
+                    .collect();
+            }
+        }
+
+        self.set_scrollbar_height();
+    }
 }
 
 impl Component for DataTable {
     fn init(&mut self, _area: Size) -> Result<()> {
-        self.load_data();
+        self.load_data(false);
         Ok(())
     }
 
@@ -302,11 +1214,74 @@ impl Component for DataTable {
     fn handle_key_events(&mut self, key: KeyEvent) -> Result<Option<Action>> {
         // if self.active_tab == TabsEnum::Devices
         let action = match self.mode {
-            Mode::Normal => return Ok(None),
+            Mode::Normal | Mode::Detail | Mode::Confirm => return Ok(None),
             Mode::Input => match key.code {
                 KeyCode::Enter => {
-                    if let Some(_sender) = &self.action_tx {
-                        self.set_filter_str(self.input.value().to_string());
+                    match self.input_purpose.clone() {
+                        InputPurpose::Filter => {
+                            self.set_filter_str(self.input.value().to_string());
+                        }
+                        InputPurpose::Revise => {
+                            // Leave input mode before the plan's detail or confirmation arrives.
+                            let changes = self.input.value().to_string();
+                            self.action_tx
+                                .clone()
+                                .unwrap()
+                                .send(Action::ModeChange(Mode::Normal))?;
+                            self.revise(&changes);
+                            return Ok(None);
+                        }
+                        InputPurpose::ShadowDesired {
+                            thing_name,
+                            shadow_name,
+                        } => {
+                            let desired = self.input.value().to_string();
+                            self.action_tx
+                                .clone()
+                                .unwrap()
+                                .send(Action::ModeChange(Mode::Normal))?;
+                            self.update_shadow(thing_name, shadow_name, desired);
+                            return Ok(None);
+                        }
+                        InputPurpose::Permission { thing_name } => {
+                            let request = self.input.value().to_string();
+                            self.action_tx
+                                .clone()
+                                .unwrap()
+                                .send(Action::ModeChange(Mode::Normal))?;
+                            self.check_permission(thing_name, &request);
+                            return Ok(None);
+                        }
+                        InputPurpose::Search => {
+                            let query = self.input.value().trim().to_string();
+                            self.action_tx
+                                .clone()
+                                .unwrap()
+                                .send(Action::ModeChange(Mode::Normal))?;
+                            if !query.is_empty() {
+                                self.search(query);
+                            }
+                            return Ok(None);
+                        }
+                        InputPurpose::Bulk => {
+                            let command = self.input.value().to_string();
+                            self.action_tx
+                                .clone()
+                                .unwrap()
+                                .send(Action::ModeChange(Mode::Normal))?;
+                            self.plan_bulk(&command);
+                            return Ok(None);
+                        }
+                        InputPurpose::GroupName => {
+                            let group_name = self.input.value().trim().to_string();
+                            if group_name.is_empty() {
+                                return Ok(Some(Action::ModeChange(Mode::Normal)));
+                            }
+                            return Ok(Some(Action::Confirm(Confirmation::create_dynamic_group(
+                                group_name,
+                                &self.search_results,
+                            ))));
+                        }
                     }
                     Action::ModeChange(Mode::Normal)
                 }
@@ -331,16 +1306,123 @@ impl Component for DataTable {
             }
         }
 
+        if action == Action::Tick && self.rows_stale {
+            self.refresh_rows();
+        }
+
+        if let Action::LoadFailed(resource, ref message) = action {
+            self.load_errors.insert(resource, message.clone());
+        }
+
         if let Action::DataLoaded = action {
             self.is_loading = false;
-            self.tab_changed(TabsEnum::Devices).unwrap();
+            self.tab_changed(self.active_tab).unwrap();
+        }
+
+        if let Action::Reload = action {
+            self.load_data(true);
         }
 
-        if let Action::Down = action {
-            self.next_in_table();
+        if let Action::Rollback {
+            ref target_arn,
+            ref revision_id,
+        } = action
+        {
+            self.rollback(target_arn.clone(), revision_id.clone());
         }
-        if let Action::Up = action {
-            self.previous_in_table();
+
+        if let Action::Execute(ref operation) = action {
+            self.execute(operation.clone());
+        }
+
+        if let Action::ShowShadow {
+            ref thing_name,
+            ref shadow_name,
+        } = action
+        {
+            self.show_shadow(thing_name.clone(), shadow_name.clone());
+        }
+
+        if let Action::EditShadow {
+            ref thing_name,
+            ref shadow_name,
+            ref desired,
+        } = action
+        {
+            // Start from the current desired state, so only the values to change need typing.
+            self.input_purpose = InputPurpose::ShadowDesired {
+                thing_name: thing_name.clone(),
+                shadow_name: shadow_name.clone(),
+            };
+            self.input = Input::default().with_value(desired.clone());
+            return Ok(Some(Action::ModeChange(Mode::Input)));
+        }
+
+        if let Action::CheckPermission { ref thing_name } = action {
+            self.input_purpose = InputPurpose::Permission {
+                thing_name: thing_name.clone(),
+            };
+            self.input.reset();
+            return Ok(Some(Action::ModeChange(Mode::Input)));
+        }
+
+        if let Action::SearchResults(ref results) = action {
+            self.search_results = results.clone();
+            self.table_state.select(Some(0));
+            self.scrollbar_state = self.scrollbar_state.position(0);
+            return Ok(Some(Action::TabChange(TabsEnum::Search)));
+        }
+
+        if self.mode == Mode::Normal {
+            if action == Action::Search && !self.is_loading {
+                // Start from the last query, so it can be refined.
+                self.input_purpose = InputPurpose::Search;
+                self.input = Input::default().with_value(self.search_results.query.clone());
+                return Ok(Some(Action::ModeChange(Mode::Input)));
+            }
+            if action == Action::SaveSearch
+                && self.active_tab == TabsEnum::Search
+                && !self.search_results.query.is_empty()
+            {
+                self.input_purpose = InputPurpose::GroupName;
+                self.input.reset();
+                return Ok(Some(Action::ModeChange(Mode::Input)));
+            }
+            if action == Action::BulkMembership && !self.is_loading {
+                self.input_purpose = InputPurpose::Bulk;
+                self.input.reset();
+                return Ok(Some(Action::ModeChange(Mode::Input)));
+            }
+            if let Action::Down = action {
+                self.next_in_table();
+            }
+            if let Action::Up = action {
+                self.previous_in_table();
+            }
+            if let Action::Select = action {
+                self.show_detail(None);
+            }
+            if matches!(action, Action::Left | Action::Right)
+                && self.active_tab == TabsEnum::ThingGroups
+                && !self.is_loading
+            {
+                self.toggle_group(action == Action::Right)?;
+            }
+            if let Action::Inspect(inspect) = action {
+                self.show_detail(Some(inspect));
+            }
+            if action == Action::Revise
+                && self.active_tab == TabsEnum::Deployments
+                && !self.is_loading
+                && self.selected_index().is_some()
+            {
+                self.input_purpose = InputPurpose::Revise;
+                self.input.reset();
+                return Ok(Some(Action::ModeChange(Mode::Input)));
+            }
+            if action == Action::CancelDeployment && self.active_tab == TabsEnum::Deployments {
+                self.cancel_deployment();
+            }
         }
 
         if let Action::ModeChange(mode) = action {
@@ -352,6 +1434,10 @@ impl Component for DataTable {
                     .unwrap();
                 return Ok(None);
             }
+            if mode == Mode::Normal && self.input_purpose != InputPurpose::Filter {
+                self.input_purpose = InputPurpose::Filter;
+                self.input = Input::default().with_value(self.filter_str.clone());
+            }
             self.mode = mode;
         }
 
@@ -361,7 +1447,7 @@ impl Component for DataTable {
 
         if let Action::Clear = action {
             self.input.reset();
-            self.filter_str = String::from("");
+            self.set_filter_str(String::new());
         }
 
         Ok(None)
@@ -369,15 +1455,7 @@ impl Component for DataTable {
 
     fn tab_changed(&mut self, tab: TabsEnum) -> Result<()> {
         self.active_tab = tab;
-
-        futures::executor::block_on(async {
-            match tab {
-                TabsEnum::Devices => self.data_list = self.aws.lock().await.devices.items(),
-                TabsEnum::Deployments => self.data_list = self.aws.lock().await.deployments.items(),
-            }
-        });
-        
-        self.set_scrollbar_height();
+        self.refresh_rows();
 
         Ok(())
     }
@@ -389,7 +1467,24 @@ impl Component for DataTable {
         table_rect.y += 1;
         table_rect.height -= 1;
 
-        let table = Self::make_table(&self.data_list, self.is_loading, self.filter_str.clone());
+        let (headers, widths) = Self::columns(self.active_tab, &self.attribute_columns);
+        let data_list = Self::filtered_indices(
+            &self.data_list,
+            &self.row_attributes,
+            &self.row_tags,
+            &self.filter,
+        )
+        .map(|idx| &self.data_list[idx])
+        .collect();
+        let table = Self::make_table(
+            data_list,
+            self.data_list.len(),
+            self.is_loading,
+            self.notice(),
+            headers,
+            widths,
+            self.active_tab.key_hints(),
+        );
         frame.render_stateful_widget(table, table_rect, &mut self.table_state);
 
         let scrollbar = Self::make_scrollbar();
@@ -430,7 +1525,7 @@ impl Component for DataTable {
                     y: input_rect.y + 1,
                 });
             }
-            Mode::Normal => {}
+            Mode::Normal | Mode::Detail | Mode::Confirm => {}
         }
 
         if self.is_loading {
@@ -0,0 +1,1183 @@
+use color_eyre::Result;
+use elysium::{
+    BulkOutcome, BulkReport, Component as GreengrassComponent, ComponentDiff, ComponentVersions,
+    DependencyEdge, DependencyGraph, DependencyIssue, Deployment, DeploymentDetail,
+    EffectiveDeployments, Evaluation, GroupMembers, InstalledComponents, Job, JobExecutions,
+    Lifecycle, MembershipChange, PermissionRequest, PrincipalPolicies, Property, Recipe,
+    RevisionDiff, Severity, Shadow, ShadowUpdate, ThingSecurity, ValueDiff,
+};
+use ratatui::{prelude::*, widgets::*};
+use tokio::sync::mpsc::UnboundedSender;
+
+use crate::{
+    action::Action,
+    app::Mode,
+    components::Component,
+    layout::{DEFAULT_BORDER_STYLE, get_vertical_layout},
+    tui::Frame,
+};
+
+const MAX_COLUMN_WIDTH: usize = 40;
+const FIELD_WIDTH: usize = 32;
+
+/// Drill-down content shown over the data table.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Detail {
+    pub title: String,
+    pub body: DetailBody,
+    /// Sent when Enter is pressed on a drill-down without rows, e.g. to edit what it shows.
+    pub action: Option<Box<Action>>,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum DetailBody {
+    Table {
+        headers: Vec<String>,
+        rows: Vec<DetailRow>,
+    },
+    Lines(Vec<DetailLine>),
+    Message(String),
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct DetailLine {
+    pub kind: LineKind,
+    pub text: String,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum LineKind {
+    Heading,
+    Plain,
+    Error,
+    /// Only in the newer side of a diff.
+    Added,
+    /// Only in the older side of a diff.
+    Removed,
+    /// Desired state of a device shadow.
+    Desired,
+    /// Reported state of a device shadow.
+    Reported,
+    /// Desired values a device has not reported yet.
+    Delta,
+}
+
+impl DetailLine {
+    pub fn new(kind: LineKind, text: impl Into<String>) -> Self {
+        Self {
+            kind,
+            text: text.into(),
+        }
+    }
+
+    fn heading(text: impl Into<String>) -> Self {
+        Self::new(LineKind::Heading, text)
+    }
+
+    fn plain(text: impl Into<String>) -> Self {
+        Self::new(LineKind::Plain, text)
+    }
+
+    fn error(text: impl Into<String>) -> Self {
+        Self::new(LineKind::Error, text)
+    }
+
+    fn added(indent: usize, text: impl std::fmt::Display) -> Self {
+        Self::new(LineKind::Added, format!("{:indent$}+ {text}", ""))
+    }
+
+    fn removed(indent: usize, text: impl std::fmt::Display) -> Self {
+        Self::new(LineKind::Removed, format!("{:indent$}- {text}", ""))
+    }
+
+    fn field(indent: usize, name: &str, value: impl std::fmt::Display) -> Self {
+        let width = FIELD_WIDTH.saturating_sub(indent);
+        Self::plain(format!("{:indent$}{name:<width$} {value}", ""))
+    }
+
+    pub fn style(&self) -> Style {
+        match self.kind {
+            LineKind::Heading => Style::default()
+                .fg(Color::Yellow)
+                .add_modifier(Modifier::BOLD),
+            LineKind::Plain => Style::default(),
+            LineKind::Error | LineKind::Removed => Style::default().fg(Color::Red),
+            LineKind::Added | LineKind::Reported => Style::default().fg(Color::Green),
+            LineKind::Desired => Style::default().fg(Color::Cyan),
+            LineKind::Delta => Style::default().fg(Color::Magenta),
+        }
+    }
+}
+
+/// Secondary drill-downs for the selected row, next to the default one opened with Enter.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Inspect {
+    EffectiveDeployments,
+    Recipe,
+    Dependencies,
+    Changes,
+    History,
+    Shadows,
+    Policies,
+    Security,
+    /// Members of a thing group and of its descendants.
+    AllMembers,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct DetailRow {
+    pub cells: Vec<String>,
+    pub highlight: bool,
+    /// Sent when the row is selected with Enter.
+    pub action: Option<Action>,
+}
+
+impl Detail {
+    pub fn message(title: impl Into<String>, message: impl Into<String>) -> Self {
+        Self {
+            title: title.into(),
+            body: DetailBody::Message(message.into()),
+            action: None,
+        }
+    }
+
+    pub fn installed_components(thing_name: &str, components: &InstalledComponents) -> Self {
+        let rows = components
+            .items()
+            .into_iter()
+            .zip(components.as_slice())
+            .map(|(cells, component)| DetailRow {
+                cells,
+                highlight: component.is_failed(),
+                action: None,
+            })
+            .collect();
+        Self {
+            title: format!("Installed components on {thing_name}"),
+            body: DetailBody::Table {
+                headers: ["Component", "Version", "State", "Root", "Status Details"]
+                    .map(String::from)
+                    .to_vec(),
+                rows,
+            },
+            action: None,
+        }
+    }
+
+    /// Outcome of a bulk membership change for each thing, failures highlighted.
+    pub fn bulk_report(report: &BulkReport) -> Self {
+        let mut title = match report.change {
+            MembershipChange::Add => format!("Add to {}", report.group_name),
+            MembershipChange::Remove => format!("Remove from {}", report.group_name),
+        };
+        if report.dry_run {
+            title.push_str(" (dry run)");
+        }
+        title.push_str(&format!(": {}", report.summary()));
+        if report.as_slice().is_empty() {
+            return Self::message(title, "No things to change");
+        }
+        let rows = report
+            .items()
+            .into_iter()
+            .zip(report.as_slice())
+            .map(|(cells, item)| DetailRow {
+                cells,
+                highlight: matches!(item.outcome, BulkOutcome::Failed(_)),
+                action: None,
+            })
+            .collect();
+        Self {
+            title,
+            body: DetailBody::Table {
+                headers: ["Thing", "Result", "Detail"].map(String::from).to_vec(),
+                rows,
+            },
+            action: None,
+        }
+    }
+
+    /// Audit findings and active violations of a thing, most severe findings first.
+    pub fn security(thing_name: &str, security: Option<&ThingSecurity>) -> Self {
+        let title = format!("Security of {thing_name}");
+        let Some(security) = security.filter(|security| !security.is_clean()) else {
+            return Self::message(title, "No recent audit findings or active violations");
+        };
+        let highlights = security
+            .findings
+            .iter()
+            .map(|finding| finding.severity >= Severity::High)
+            .chain(security.violations.iter().map(|_| true));
+        let rows = security
+            .items()
+            .into_iter()
+            .zip(highlights)
+            .map(|(cells, highlight)| DetailRow {
+                cells,
+                highlight,
+                action: None,
+            })
+            .collect();
+        Self {
+            title,
+            body: DetailBody::Table {
+                headers: ["Severity", "Source", "Check / Profile", "Reason", "Since"]
+                    .map(String::from)
+                    .to_vec(),
+                rows,
+            },
+            action: None,
+        }
+    }
+
+    pub fn group_members(group_name: &str, recursive: bool, members: &GroupMembers) -> Self {
+        let title = if recursive {
+            format!("Things in {group_name} and its subgroups")
+        } else {
+            format!("Things in {group_name}")
+        };
+        if members.as_slice().is_empty() {
+            return Self::message(title, "No things in the group");
+        }
+        let rows = members
+            .items()
+            .into_iter()
+            .zip(members.as_slice())
+            .map(|(cells, member)| DetailRow {
+                cells,
+                highlight: member.is_unhealthy(),
+                action: None,
+            })
+            .collect();
+        Self {
+            title,
+            body: DetailBody::Table {
+                headers: ["Thing", "Status", "Connected", "Last Status Update"]
+                    .map(String::from)
+                    .to_vec(),
+                rows,
+            },
+            action: None,
+        }
+    }
+
+    pub fn effective_deployments(thing_name: &str, deployments: &EffectiveDeployments) -> Self {
+        let rows = deployments
+            .items()
+            .into_iter()
+            .zip(deployments.as_slice())
+            .map(|(cells, deployment)| DetailRow {
+                cells,
+                highlight: deployment.is_failed(),
+                action: None,
+            })
+            .collect();
+        Self {
+            title: format!("Effective deployments on {thing_name}"),
+            body: DetailBody::Table {
+                headers: [
+                    "Deployment",
+                    "ID",
+                    "Target",
+                    "Status",
+                    "Created",
+                    "Modified",
+                    "Reason",
+                ]
+                .map(String::from)
+                .to_vec(),
+                rows,
+            },
+            action: None,
+        }
+    }
+
+    pub fn component_versions(
+        component: &GreengrassComponent,
+        versions: &ComponentVersions,
+    ) -> Self {
+        let rows = versions
+            .items()
+            .into_iter()
+            .map(|cells| DetailRow {
+                cells,
+                highlight: false,
+                action: None,
+            })
+            .collect();
+        Self {
+            title: format!("Versions of {}", component.component_name),
+            body: DetailBody::Table {
+                headers: ["Version", "ARN"].map(String::from).to_vec(),
+                rows,
+            },
+            action: None,
+        }
+    }
+
+    pub fn job_executions(job: &Job, executions: &JobExecutions) -> Self {
+        let rows = executions
+            .items()
+            .into_iter()
+            .zip(executions.as_slice())
+            .map(|(cells, execution)| DetailRow {
+                cells,
+                highlight: execution.is_failed(),
+                action: None,
+            })
+            .collect();
+        let title = if job.description.is_empty() {
+            format!("Executions of {}", job.job_id)
+        } else {
+            format!("Executions of {} ({})", job.job_id, job.description)
+        };
+        Self {
+            title,
+            body: DetailBody::Table {
+                headers: [
+                    "Thing",
+                    "Status",
+                    "Execution",
+                    "Retries",
+                    "Queued",
+                    "Started",
+                    "Updated",
+                ]
+                .map(String::from)
+                .to_vec(),
+                rows,
+            },
+            action: None,
+        }
+    }
+
+    /// Every revision of a target's deployment, each of the earlier ones offering a rollback.
+    pub fn deployment_history(deployment_name: &str, history: &[Deployment]) -> Self {
+        let rows = history
+            .iter()
+            .map(|deployment| {
+                let revision = if deployment.is_latest_for_target {
+                    format!("{} (latest)", deployment.revision_id)
+                } else {
+                    deployment.revision_id.clone()
+                };
+                DetailRow {
+                    cells: vec![
+                        revision,
+                        deployment.deployment_status.clone(),
+                        deployment.creation_timestamp.clone(),
+                        deployment.deployment_id.clone(),
+                    ],
+                    highlight: false,
+                    action: (!deployment.is_latest_for_target).then(|| Action::Rollback {
+                        target_arn: deployment.target_arn.clone(),
+                        revision_id: deployment.revision_id.clone(),
+                    }),
+                }
+            })
+            .collect();
+        Self {
+            title: format!("History of {deployment_name}"),
+            body: DetailBody::Table {
+                headers: ["Revision", "Status", "Created", "ID"]
+                    .map(String::from)
+                    .to_vec(),
+                rows,
+            },
+            action: None,
+        }
+    }
+
+    /// The classic and named shadows of a thing, each opening its document.
+    pub fn shadows(thing_name: &str, shadows: &[Shadow]) -> Self {
+        if shadows.is_empty() {
+            return Self::message(
+                format!("Shadows of {thing_name}"),
+                format!("{thing_name} has no shadows"),
+            );
+        }
+        let rows = shadows
+            .iter()
+            .map(|shadow| {
+                let pending = match &shadow.delta {
+                    Some(delta) => delta
+                        .as_object()
+                        .map(|delta| delta.keys().cloned().collect::<Vec<_>>().join(", "))
+                        .unwrap_or_default(),
+                    None => String::new(),
+                };
+                DetailRow {
+                    cells: vec![
+                        shadow.name().to_string(),
+                        shadow.version.to_string(),
+                        shadow.updated_at(),
+                        pending,
+                    ],
+                    highlight: shadow.delta.is_some(),
+                    action: Some(Action::ShowShadow {
+                        thing_name: shadow.thing_name.clone(),
+                        shadow_name: shadow.shadow_name.clone(),
+                    }),
+                }
+            })
+            .collect();
+        Self {
+            title: format!("Shadows of {thing_name}"),
+            body: DetailBody::Table {
+                headers: ["Shadow", "Version", "Updated", "Pending"]
+                    .map(String::from)
+                    .to_vec(),
+                rows,
+            },
+            action: None,
+        }
+    }
+
+    /// A shadow document, with the desired, reported and delta states told apart by colour.
+    pub fn shadow(shadow: &Shadow) -> Self {
+        let mut lines = vec![
+            DetailLine::field(0, "Version", shadow.version),
+            DetailLine::field(0, "Updated", shadow.updated_at()),
+        ];
+        for ((name, pretty), kind) in shadow.states_pretty().into_iter().zip([
+            LineKind::Desired,
+            LineKind::Reported,
+            LineKind::Delta,
+        ]) {
+            lines.push(DetailLine::plain(""));
+            lines.push(DetailLine::heading(name));
+            match pretty {
+                Some(pretty) => {
+                    lines.extend(
+                        pretty
+                            .lines()
+                            .map(|line| DetailLine::new(kind, format!("  {line}"))),
+                    );
+                }
+                None => lines.push(DetailLine::plain("  None")),
+            }
+        }
+        Self {
+            title: format!("{} shadow of {}", shadow.name(), shadow.thing_name),
+            body: DetailBody::Lines(lines),
+            action: Some(Box::new(Action::EditShadow {
+                thing_name: shadow.thing_name.clone(),
+                shadow_name: shadow.shadow_name.clone(),
+                desired: shadow.desired_compact(),
+            })),
+        }
+    }
+
+    /// Changes a shadow update makes to the desired state, as coloured +/- lines.
+    pub fn shadow_update_lines(update: &ShadowUpdate) -> Vec<DetailLine> {
+        let mut lines = vec![DetailLine::heading("Desired")];
+        Self::push_value_diffs(&mut lines, 2, &update.diff);
+        lines
+    }
+
+    /// The policies attached to each certificate of a thing, pretty-printed.
+    pub fn policies(thing_name: &str, principals: &[PrincipalPolicies]) -> Self {
+        let title = format!("Policies of {thing_name}");
+        if principals.is_empty() {
+            return Self::message(title, format!("{thing_name} has no certificates attached"));
+        }
+        let mut lines = Vec::new();
+        for principal in principals {
+            if !lines.is_empty() {
+                lines.push(DetailLine::plain(""));
+            }
+            lines.push(DetailLine::heading(format!(
+                "Certificate {}",
+                principal.name()
+            )));
+            if principal.policies.is_empty() {
+                lines.push(DetailLine::plain("  No policies attached"));
+            }
+            for policy in &principal.policies {
+                lines.push(DetailLine::plain(format!("  {}", policy.policy_name)));
+                lines.extend(
+                    policy
+                        .document_pretty()
+                        .lines()
+                        .map(|line| DetailLine::plain(format!("    {line}"))),
+                );
+            }
+        }
+        Self {
+            title,
+            body: DetailBody::Lines(lines),
+            action: Some(Box::new(Action::CheckPermission {
+                thing_name: thing_name.to_string(),
+            })),
+        }
+    }
+
+    /// Whether each certificate of a thing may perform a request.
+    pub fn permission_check(
+        thing_name: &str,
+        request: &PermissionRequest,
+        evaluations: &[(String, Evaluation)],
+    ) -> Self {
+        let title = format!("{} {} as {thing_name}", request.action, request.target);
+        if evaluations.is_empty() {
+            return Self::message(title, format!("{thing_name} has no certificates attached"));
+        }
+        let mut lines = Vec::new();
+        for (certificate_id, evaluation) in evaluations {
+            if !lines.is_empty() {
+                lines.push(DetailLine::plain(""));
+            }
+            lines.push(DetailLine::heading(format!("Certificate {certificate_id}")));
+            lines.push(DetailLine::field(2, "Resource", &evaluation.resource));
+            let kind =
+                if evaluation.decision.is_allowed() { LineKind::Added } else { LineKind::Error };
+            lines.push(DetailLine::new(kind, format!("  {}", evaluation.decision)));
+            for statement in &evaluation.unevaluated {
+                lines.push(DetailLine::plain(format!(
+                    "  {statement} may also apply, but cannot be evaluated locally"
+                )));
+            }
+        }
+        Self {
+            title,
+            body: DetailBody::Lines(lines),
+            action: Some(Box::new(Action::CheckPermission {
+                thing_name: thing_name.to_string(),
+            })),
+        }
+    }
+
+    pub fn deployment(deployment: &DeploymentDetail) -> Self {
+        let mut lines = vec![
+            DetailLine::field(0, "ID", &deployment.deployment_id),
+            DetailLine::field(0, "Target", &deployment.target_arn),
+            DetailLine::field(0, "Revision", &deployment.revision_id),
+            DetailLine::field(0, "Status", &deployment.deployment_status),
+            DetailLine::field(0, "Created", &deployment.creation_timestamp),
+        ];
+        if let Some(parent_target_arn) = &deployment.parent_target_arn {
+            lines.push(DetailLine::field(0, "Parent target", parent_target_arn));
+        }
+        if let Some(iot_job_id) = &deployment.iot_job_id {
+            lines.push(DetailLine::field(0, "IoT job", iot_job_id));
+        }
+
+        lines.push(DetailLine::plain(""));
+        lines.push(DetailLine::heading("Components"));
+        for (name, spec) in &deployment.components {
+            lines.push(DetailLine::field(2, name, &spec.component_version));
+            if let Some(run_with) = &spec.run_with {
+                if let Some(posix_user) = &run_with.posix_user {
+                    lines.push(DetailLine::field(4, "POSIX user", posix_user));
+                }
+                if let Some(windows_user) = &run_with.windows_user {
+                    lines.push(DetailLine::field(4, "Windows user", windows_user));
+                }
+                if let Some(limits) = &run_with.system_resource_limits {
+                    lines.push(DetailLine::field(
+                        4,
+                        "Resource limits",
+                        format!("{} KB memory, {} CPUs", limits.memory, limits.cpus),
+                    ));
+                }
+            }
+            if let Some(update) = &spec.configuration_update {
+                if let Some(merge) = update.merge_pretty() {
+                    lines.push(DetailLine::plain("    Merge"));
+                    lines.extend(
+                        merge
+                            .lines()
+                            .map(|line| DetailLine::plain(format!("      {line}"))),
+                    );
+                }
+                if !update.reset.is_empty() {
+                    lines.push(DetailLine::field(4, "Reset", update.reset.join(", ")));
+                }
+            }
+        }
+
+        if let Some(policies) = &deployment.deployment_policies {
+            lines.push(DetailLine::plain(""));
+            lines.push(DetailLine::heading("Deployment policies"));
+            if let Some(failure_handling) = &policies.failure_handling_policy {
+                lines.push(DetailLine::field(2, "Failure handling", failure_handling));
+            }
+            if let Some(update_policy) = &policies.component_update_policy {
+                if let Some(action) = &update_policy.action {
+                    lines.push(DetailLine::field(2, "Component update", action));
+                }
+                if let Some(timeout) = update_policy.timeout_in_seconds {
+                    lines.push(DetailLine::field(
+                        2,
+                        "Update timeout",
+                        format!("{timeout}s"),
+                    ));
+                }
+            }
+            if let Some(timeout) = policies.configuration_validation_timeout_in_seconds {
+                lines.push(DetailLine::field(
+                    2,
+                    "Validation timeout",
+                    format!("{timeout}s"),
+                ));
+            }
+        }
+
+        if let Some(job) = &deployment.iot_job_configuration {
+            lines.push(DetailLine::plain(""));
+            lines.push(DetailLine::heading("IoT job configuration"));
+            if let Some(maximum) = job.maximum_per_minute {
+                lines.push(DetailLine::field(2, "Maximum per minute", maximum));
+            }
+            if let Some(rate) = &job.exponential_rate {
+                lines.push(DetailLine::field(
+                    2,
+                    "Exponential rate",
+                    format!(
+                        "{}/min x{}",
+                        rate.base_rate_per_minute, rate.increment_factor
+                    ),
+                ));
+            }
+            for criteria in &job.abort_criteria {
+                lines.push(DetailLine::field(
+                    2,
+                    "Abort",
+                    format!(
+                        "{} on {} >= {}% after {} things",
+                        criteria.action,
+                        criteria.failure_type,
+                        criteria.threshold_percentage,
+                        criteria.min_number_of_executed_things,
+                    ),
+                ));
+            }
+            if let Some(timeout) = job.in_progress_timeout_in_minutes {
+                lines.push(DetailLine::field(
+                    2,
+                    "In progress timeout",
+                    format!("{timeout}min"),
+                ));
+            }
+        }
+
+        if !deployment.tags.is_empty() {
+            lines.push(DetailLine::plain(""));
+            lines.push(DetailLine::heading("Tags"));
+            for (key, value) in &deployment.tags {
+                lines.push(DetailLine::field(2, key, value));
+            }
+        }
+
+        Self {
+            title: format!("Deployment {}", deployment.deployment_name),
+            body: DetailBody::Lines(lines),
+            action: None,
+        }
+    }
+
+    pub fn recipe(recipe: &Recipe) -> Self {
+        let mut lines = vec![
+            DetailLine::field(0, "Component", &recipe.component_name),
+            DetailLine::field(0, "Version", &recipe.component_version),
+        ];
+        if let Some(component_type) = &recipe.component_type {
+            lines.push(DetailLine::field(0, "Type", component_type));
+        }
+        if let Some(publisher) = &recipe.component_publisher {
+            lines.push(DetailLine::field(0, "Publisher", publisher));
+        }
+        if let Some(description) = &recipe.component_description {
+            lines.push(DetailLine::field(0, "Description", description));
+        }
+
+        if !recipe.component_dependencies.is_empty() {
+            lines.push(DetailLine::plain(""));
+            lines.push(DetailLine::heading("Dependencies"));
+            for (name, dependency) in &recipe.component_dependencies {
+                lines.push(DetailLine::field(
+                    2,
+                    name,
+                    format!(
+                        "{} ({})",
+                        dependency.version_requirement, dependency.dependency_type
+                    ),
+                ));
+            }
+        }
+
+        if let Some(configuration) = recipe.default_configuration_pretty() {
+            lines.push(DetailLine::plain(""));
+            lines.push(DetailLine::heading("Default configuration"));
+            lines.extend(
+                configuration
+                    .lines()
+                    .map(|line| DetailLine::plain(format!("  {line}"))),
+            );
+        }
+
+        for (idx, manifest) in recipe.manifests.iter().enumerate() {
+            lines.push(DetailLine::plain(""));
+            lines.push(DetailLine::heading(match &manifest.name {
+                Some(name) => format!("Manifest {name} ({})", manifest.platform),
+                None => format!("Manifest {} ({})", idx + 1, manifest.platform),
+            }));
+            if let Some(lifecycle) = &manifest.lifecycle {
+                Self::push_lifecycle(&mut lines, 2, lifecycle);
+            }
+            for selection in &manifest.selections {
+                lines.push(DetailLine::field(2, "Selection", selection));
+                if let Some(lifecycle) = recipe.lifecycle.get(selection) {
+                    Self::push_lifecycle(&mut lines, 4, lifecycle);
+                }
+            }
+            for artifact in &manifest.artifacts {
+                lines.push(DetailLine::field(2, "Artifact", &artifact.uri));
+                lines.push(DetailLine::field(4, "Unarchive", artifact.unarchive));
+                lines.push(DetailLine::field(
+                    4,
+                    "Permission",
+                    format!(
+                        "read {}, execute {}",
+                        artifact.permission.read, artifact.permission.execute
+                    ),
+                ));
+                if let (Some(algorithm), Some(digest)) = (&artifact.algorithm, &artifact.digest) {
+                    lines.push(DetailLine::field(4, algorithm, digest));
+                }
+            }
+        }
+
+        Self {
+            title: format!(
+                "Recipe {} {}",
+                recipe.component_name, recipe.component_version
+            ),
+            body: DetailBody::Lines(lines),
+            action: None,
+        }
+    }
+
+    pub fn dependencies(deployment_name: &str, graph: &DependencyGraph) -> Self {
+        let mut lines = Vec::new();
+        if graph.is_ok() {
+            lines.push(DetailLine::plain("No conflicts or cycles"));
+        } else {
+            lines.push(DetailLine::heading("Issues"));
+            lines.extend(
+                graph
+                    .issues
+                    .iter()
+                    .map(|issue| DetailLine::error(format!("  {issue}"))),
+            );
+        }
+
+        lines.push(DetailLine::plain(""));
+        lines.push(DetailLine::heading("Dependencies"));
+        for root in &graph.roots {
+            Self::push_dependency_tree(&mut lines, graph, root, None, "", &mut Vec::new());
+        }
+
+        Self {
+            title: format!("Dependencies of {deployment_name}"),
+            body: DetailBody::Lines(lines),
+            action: None,
+        }
+    }
+
+    pub fn revision_diff(deployment_name: &str, diff: &RevisionDiff) -> Self {
+        let title = format!(
+            "Changes to {deployment_name} (revision {} to {})",
+            diff.old_revision, diff.new_revision
+        );
+        if diff.is_empty() {
+            return Self::message(
+                title,
+                "Both revisions deploy the same components, configuration and policies",
+            );
+        }
+
+        Self {
+            title,
+            body: DetailBody::Lines(Self::revision_diff_lines(diff)),
+            action: None,
+        }
+    }
+
+    /// Components, configuration, run-with settings, policies and job configuration that
+    /// differ, as coloured +/- lines.
+    pub fn revision_diff_lines(diff: &RevisionDiff) -> Vec<DetailLine> {
+        let mut lines = vec![DetailLine::heading("Components")];
+        for change in &diff.components {
+            match change {
+                ComponentDiff::Added { name, version } => {
+                    lines.push(DetailLine::added(2, format!("{name} {version}")));
+                }
+                ComponentDiff::Removed { name, version } => {
+                    lines.push(DetailLine::removed(2, format!("{name} {version}")));
+                }
+                ComponentDiff::VersionChanged { name, from, to } => {
+                    lines.push(DetailLine::removed(2, format!("{name} {from}")));
+                    lines.push(DetailLine::added(2, format!("{name} {to}")));
+                }
+            }
+        }
+        if diff.components.is_empty() {
+            lines.push(DetailLine::plain("  No changes"));
+        }
+
+        lines.push(DetailLine::plain(""));
+        lines.push(DetailLine::heading("Configuration"));
+        for configuration in &diff.configuration {
+            lines.push(DetailLine::plain(format!(
+                "  {}",
+                configuration.component_name
+            )));
+            Self::push_value_diffs(&mut lines, 4, &configuration.merge);
+            for path in &configuration.reset_removed {
+                lines.push(DetailLine::removed(4, format!("reset {path}")));
+            }
+            for path in &configuration.reset_added {
+                lines.push(DetailLine::added(4, format!("reset {path}")));
+            }
+        }
+        if diff.configuration.is_empty() {
+            lines.push(DetailLine::plain("  No changes"));
+        }
+
+        lines.push(DetailLine::plain(""));
+        lines.push(DetailLine::heading("Run with"));
+        for run_with in &diff.run_with {
+            lines.push(DetailLine::plain(format!("  {}", run_with.component_name)));
+            Self::push_value_diffs(&mut lines, 4, &run_with.changes);
+        }
+        if diff.run_with.is_empty() {
+            lines.push(DetailLine::plain("  No changes"));
+        }
+
+        lines.push(DetailLine::plain(""));
+        lines.push(DetailLine::heading("Deployment policies"));
+        Self::push_value_diffs(&mut lines, 2, &diff.deployment_policies);
+        if diff.deployment_policies.is_empty() {
+            lines.push(DetailLine::plain("  No changes"));
+        }
+
+        lines.push(DetailLine::plain(""));
+        lines.push(DetailLine::heading("IoT job configuration"));
+        Self::push_value_diffs(&mut lines, 2, &diff.iot_job_configuration);
+        if diff.iot_job_configuration.is_empty() {
+            lines.push(DetailLine::plain("  No changes"));
+        }
+
+        lines
+    }
+
+    /// Adds a removed line for each old value and an added line for each new one.
+    fn push_value_diffs(lines: &mut Vec<DetailLine>, indent: usize, diffs: &[ValueDiff]) {
+        for diff in diffs {
+            let path = if diff.path.is_empty() { "/" } else { &diff.path };
+            if let Some(old) = &diff.old {
+                lines.push(DetailLine::removed(indent, format!("{path}: {old}")));
+            }
+            if let Some(new) = &diff.new {
+                lines.push(DetailLine::added(indent, format!("{path}: {new}")));
+            }
+        }
+    }
+
+    /// Adds `name` and its dependencies below it, stopping where a cycle repeats a component.
+    fn push_dependency_tree(
+        lines: &mut Vec<DetailLine>,
+        graph: &DependencyGraph,
+        name: &str,
+        edge: Option<&DependencyEdge>,
+        prefix: &str,
+        path: &mut Vec<String>,
+    ) {
+        let component = graph.components.get(name);
+        let version = component
+            .and_then(|component| component.version.as_deref())
+            .unwrap_or("unresolved");
+        let mut text = format!("{name} {version}");
+        if let Some(edge) = edge {
+            text.push_str(&format!(
+                "  ({} {})",
+                edge.version_requirement, edge.dependency_type
+            ));
+        }
+        let in_conflict = graph.issues.iter().any(|issue| match issue {
+            DependencyIssue::Conflict { component_name, .. }
+            | DependencyIssue::Unresolved { component_name, .. } => component_name == name,
+            DependencyIssue::Unsettled(names) => names.iter().any(|unsettled| unsettled == name),
+            DependencyIssue::Cycle(_) => false,
+        });
+
+        if path.iter().any(|visited| visited == name) {
+            lines.push(DetailLine::error(format!("{prefix}{text}  cycle")));
+            return;
+        }
+        lines.push(if in_conflict {
+            DetailLine::error(format!("{prefix}{text}"))
+        } else {
+            DetailLine::plain(format!("{prefix}{text}"))
+        });
+
+        let Some(component) = component else {
+            return;
+        };
+        // Children are drawn under the last branch character of this line.
+        let child_prefix = prefix.replace("├─ ", "│  ").replace("└─ ", "   ");
+        path.push(name.to_string());
+        for (idx, dependency) in component.dependencies.iter().enumerate() {
+            let branch =
+                if idx + 1 == component.dependencies.len() { "└─ " } else { "├─ " };
+            Self::push_dependency_tree(
+                lines,
+                graph,
+                &dependency.component_name,
+                Some(dependency),
+                &format!("{child_prefix}{branch}"),
+                path,
+            );
+        }
+        path.pop();
+    }
+
+    fn push_lifecycle(lines: &mut Vec<DetailLine>, indent: usize, lifecycle: &Lifecycle) {
+        for (name, value) in &lifecycle.setenv {
+            lines.push(DetailLine::field(
+                indent,
+                "Setenv",
+                format!("{name}={value}"),
+            ));
+        }
+        for (name, step) in lifecycle.steps() {
+            let mut script = step.script().lines();
+            lines.push(DetailLine::field(
+                indent,
+                name,
+                script.next().unwrap_or_default(),
+            ));
+            for line in script {
+                lines.push(DetailLine::field(indent, "", line));
+            }
+        }
+    }
+}
+
+pub struct DetailPane {
+    action_tx: Option<UnboundedSender<Action>>,
+    detail: Option<Detail>,
+    table_state: TableState,
+}
+
+impl Default for DetailPane {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl DetailPane {
+    pub fn new() -> Self {
+        Self {
+            action_tx: None,
+            detail: None,
+            table_state: TableState::default().with_selected(0),
+        }
+    }
+
+    fn row_count(&self) -> usize {
+        match self.detail {
+            Some(Detail {
+                body: DetailBody::Table { ref rows, .. },
+                ..
+            }) => rows.len(),
+            Some(Detail {
+                body: DetailBody::Lines(ref lines),
+                ..
+            }) => lines.len(),
+            _ => 0,
+        }
+    }
+
+    /// The action of the selected row in a table drill-down.
+    fn selected_action(&self) -> Option<&Action> {
+        match &self.detail {
+            Some(Detail {
+                body: DetailBody::Table { rows, .. },
+                ..
+            }) => rows.get(self.table_state.selected()?)?.action.as_ref(),
+            Some(detail) => detail.action.as_deref(),
+            None => None,
+        }
+    }
+
+    /// What Enter does, shown next to the ⏎ hint.
+    fn select_hint(&self) -> Option<&'static str> {
+        self.selected_action().map(|action| match action {
+            Action::Rollback { .. } => "roll back",
+            Action::ShowShadow { .. } => "view",
+            Action::EditShadow { .. } => "edit",
+            Action::CheckPermission { .. } => "check permission",
+            _ => "select",
+        })
+    }
+
+    fn make_block<'a>(title: &'a str, select_hint: Option<&'a str>) -> Block<'a> {
+        let mut hints = Vec::new();
+        if let Some(hint) = select_hint {
+            hints.extend([
+                Span::styled("|", Style::default().fg(Color::Yellow)),
+                Span::styled(
+                    "⏎",
+                    Style::default().add_modifier(Modifier::BOLD).fg(Color::Red),
+                ),
+                Span::styled(format!(" {hint}"), Style::default().fg(Color::Yellow)),
+            ]);
+        }
+        hints.extend([
+            Span::styled("|", Style::default().fg(Color::Yellow)),
+            Span::styled(
+                "ESC",
+                Style::default().add_modifier(Modifier::BOLD).fg(Color::Red),
+            ),
+            Span::styled(" back|", Style::default().fg(Color::Yellow)),
+        ]);
+        Block::new()
+            .title_top(
+                Line::from(vec![
+                    Span::styled("|", Style::default().fg(Color::Yellow)),
+                    Span::styled(title, Style::default().fg(Color::Green)),
+                    Span::styled("|", Style::default().fg(Color::Yellow)),
+                ])
+                .left_aligned(),
+            )
+            .title_bottom(Line::from(hints).right_aligned())
+            .border_style(Style::default().fg(Color::Rgb(100, 100, 100)))
+            .borders(Borders::ALL)
+            .border_type(DEFAULT_BORDER_STYLE)
+    }
+
+    fn make_table<'a>(
+        title: &'a str,
+        select_hint: Option<&'a str>,
+        headers: &'a [String],
+        rows: &'a [DetailRow],
+    ) -> Table<'a> {
+        let header = Row::new(headers.iter().map(String::as_str))
+            .style(Style::default().fg(Color::Yellow))
+            .bottom_margin(1);
+        // Size columns to their content and leave the rest to the last one, which
+        // usually holds free text.
+        let widths: Vec<Constraint> = headers
+            .iter()
+            .enumerate()
+            .map(|(idx, header)| {
+                if idx + 1 == headers.len() {
+                    return Constraint::Fill(1);
+                }
+                let width = rows
+                    .iter()
+                    .filter_map(|row| row.cells.get(idx))
+                    .map(|cell| cell.chars().count())
+                    .chain([header.chars().count()])
+                    .max()
+                    .unwrap_or_default();
+                Constraint::Length(width.min(MAX_COLUMN_WIDTH) as u16)
+            })
+            .collect();
+        let rows = rows.iter().map(|row| {
+            let style =
+                if row.highlight { Style::default().fg(Color::Red) } else { Style::default() };
+            Row::new(row.cells.iter().map(String::as_str)).style(style)
+        });
+
+        Table::new(rows, widths)
+            .header(header)
+            .block(Self::make_block(title, select_hint))
+            .highlight_symbol(String::from(char::from_u32(0x25b6).unwrap_or('>')).red())
+            .column_spacing(1)
+    }
+}
+
+impl Component for DetailPane {
+    fn register_action_handler(&mut self, tx: UnboundedSender<Action>) -> Result<()> {
+        self.action_tx = Some(tx);
+        Ok(())
+    }
+
+    fn update(&mut self, action: Action) -> Result<Option<Action>> {
+        match action {
+            Action::ShowDetail(detail) => {
+                self.detail = Some(detail);
+                self.table_state.select(Some(0));
+                return Ok(Some(Action::ModeChange(Mode::Detail)));
+            }
+            // Leaving, or editing what the drill-down shows in the input box, closes it.
+            Action::ModeChange(Mode::Normal | Mode::Input) => {
+                self.detail = None;
+            }
+            Action::Up if self.detail.is_some() => {
+                let len = self.row_count();
+                let index = match self.table_state.selected() {
+                    Some(0) | None => len.saturating_sub(1),
+                    Some(index) => index - 1,
+                };
+                self.table_state.select(Some(index));
+            }
+            Action::Select if self.detail.is_some() => {
+                return Ok(self.selected_action().cloned());
+            }
+            Action::Down if self.detail.is_some() => {
+                let len = self.row_count();
+                let index = match self.table_state.selected() {
+                    Some(index) if index + 1 < len => index + 1,
+                    _ => 0,
+                };
+                self.table_state.select(Some(index));
+            }
+            _ => {}
+        }
+        Ok(None)
+    }
+
+    fn draw(&mut self, frame: &mut Frame<'_>, area: Rect) -> Result<()> {
+        let Some(detail) = &self.detail else {
+            return Ok(());
+        };
+
+        let layout = get_vertical_layout(area);
+        let mut rect = layout.bottom;
+        rect.y += 1;
+        rect.height -= 1;
+
+        frame.render_widget(Clear, rect);
+        match &detail.body {
+            DetailBody::Table { headers, rows } => {
+                let select_hint = self.select_hint();
+                let table = Self::make_table(&detail.title, select_hint, headers, rows);
+                frame.render_stateful_widget(table, rect, &mut self.table_state);
+            }
+            DetailBody::Lines(lines) => {
+                let select_hint = self.select_hint();
+                let scroll = self.table_state.selected().unwrap_or_default() as u16;
+                let text: Vec<Line> = lines
+                    .iter()
+                    .map(|line| Line::styled(line.text.as_str(), line.style()))
+                    .collect();
+                let paragraph = Paragraph::new(text)
+                    .scroll((scroll, 0))
+                    .block(Self::make_block(&detail.title, select_hint));
+                frame.render_widget(paragraph, rect);
+            }
+            DetailBody::Message(message) => {
+                let paragraph = Paragraph::new(message.as_str())
+                    .wrap(Wrap { trim: false })
+                    .block(Self::make_block(&detail.title, None));
+                frame.render_widget(paragraph, rect);
+            }
+        }
+
+        Ok(())
+    }
+}
@@ -28,7 +28,7 @@ impl Header {
         }
     }
 
-    fn make_tabs(&self) -> Paragraph {
+    fn make_tabs(&self) -> Paragraph<'_> {
         let enum_titles: Vec<Span> =
             TabsEnum::iter()
                 .enumerate()
@@ -39,7 +39,7 @@ impl TopLeft {
         }
     }
 
-    fn make_chart(&mut self) -> Chart {
+    fn make_chart(&mut self) -> Chart<'_> {
         let mut datasets = Vec::new();
         for d in &self.graph_datasets {
             let dataset = Dataset::default()
@@ -61,7 +61,7 @@ impl TopLeft {
         .map(Span::from)
         .collect();
 
-        let chart = Chart::new(datasets)
+        Chart::new(datasets)
             .block(
                 Block::new()
                     .title_top(Line::from("|Chart Title|".yellow()).right_aligned())
@@ -97,8 +97,7 @@ impl TopLeft {
                     .style(Style::default().fg(Color::Yellow)),
             )
             .legend_position(Some(LegendPosition::TopLeft))
-            .hidden_legend_constraints((Constraint::Ratio(1, 2), Constraint::Ratio(1, 2)));
-        chart        
+            .hidden_legend_constraints((Constraint::Ratio(1, 2), Constraint::Ratio(1, 2)))
     }
 }
 
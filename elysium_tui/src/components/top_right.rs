@@ -1,6 +1,7 @@
 use std::time::Instant;
 
 use color_eyre::eyre::Result;
+use elysium::{FleetSummary, Resource};
 use ratatui::{prelude::*, widgets::*};
 use tokio::sync::mpsc::UnboundedSender;
 
@@ -11,9 +12,20 @@ use crate::{
     tui::Frame,
 };
 
+/// Width of the bar of a count that covers the whole fleet.
+const BAR_WIDTH: u64 = 20;
+/// Width of the labels in front of the counts.
+const LABEL_WIDTH: usize = 24;
+
 pub struct TopRight {
     action_tx: Option<UnboundedSender<Action>>,
     last_update_time: Instant,
+    /// Aggregations over the fleet, `None` until loaded.
+    summary: Option<FleetSummary>,
+    /// Whether the summary was loaded, so a missing one means fleet indexing is not enabled.
+    loaded: bool,
+    /// Why the summary failed to load.
+    load_error: Option<String>,
 }
 
 impl Default for TopRight {
@@ -27,6 +39,9 @@ impl TopRight {
         Self {
             action_tx: None,
             last_update_time: Instant::now(),
+            summary: None,
+            loaded: false,
+            load_error: None,
         }
     }
 
@@ -39,55 +54,88 @@ impl TopRight {
         Ok(())
     }
 
-    fn make_table(&mut self) -> Table {
-        let header = Row::new(vec!["", "", "", "", ""])
-            .style(Style::default().fg(Color::Yellow))
-            .height(1);
-        let mut rows = Vec::new();
-        rows.push(
-            Row::new(vec![
-                Cell::from(Span::styled(
-                    "",
-                    Style::default().fg(Color::Red),
-                )),
-                Cell::from(Span::styled(
-                    "",
-                    Style::default().fg(Color::Green),
-                )),
-                Cell::from(""),
-                Cell::from(""),
-                Cell::from(vec![Line::from("")]),
-            ])
-            .height(1)
-        );
-        let table = Table::new(
-            rows,
-            [
-                Constraint::Length(1),
-                Constraint::Length(8),
-                Constraint::Length(18),
-                Constraint::Length(14),
-                Constraint::Length(25),
-            ],
-        )
-        .header(header)
-        .block(
-            Block::default()
-                // .title(Line::from(vec![
-                //     Span::styled("|Inter", Style::default().fg(Color::Yellow)),
-                //     Span::styled("f", Style::default().fg(Color::Red)),
-                //     Span::styled("aces|", Style::default().fg(Color::Yellow)),
-                // ]))
-                .border_style(Style::default().fg(Color::Rgb(100, 100, 100)))
-                .title_style(Style::default().fg(Color::Yellow))
-                .title_alignment(Alignment::Right)
-                .borders(Borders::ALL)
-                .border_type(DEFAULT_BORDER_STYLE)
-                .padding(Padding::new(0, 0, 1, 0)),
-        )
-        .column_spacing(1);
-        table
+    /// Lines of the fleet summary: counts, the bucketed field, then the numeric field.
+    fn summary_lines(summary: &FleetSummary) -> Vec<Line<'static>> {
+        let count = |label: &str, count: u64, color: Color, bar: bool| {
+            let mut spans = vec![
+                Span::raw(format!("{label:<LABEL_WIDTH$}")),
+                Span::styled(format!("{count:>8} "), Style::default().fg(color)),
+            ];
+            if bar && summary.things > 0 {
+                let width = (count * BAR_WIDTH).div_ceil(summary.things) as usize;
+                spans.push("█".repeat(width).green());
+            }
+            Line::from(spans)
+        };
+
+        let mut lines = vec![
+            count("Things", summary.things, Color::Blue, false),
+            count("Connected", summary.connected, Color::Green, true),
+            count("Disconnected", summary.disconnected, Color::Red, true),
+            count("Thing types", summary.thing_types, Color::Reset, false),
+            Line::from(summary.bucket_field.clone().yellow()),
+        ];
+        if summary.buckets.is_empty() {
+            lines.push(Line::from("  no values".dark_gray()));
+        }
+        lines.extend(summary.buckets.iter().map(|bucket| {
+            count(
+                &format!("  {}", bucket.key),
+                bucket.count,
+                Color::Reset,
+                true,
+            )
+        }));
+
+        if let Some(metric) = &summary.metric {
+            let statistics = &metric.statistics;
+            let values = [
+                ("min", statistics.minimum),
+                ("avg", statistics.average),
+                ("max", statistics.maximum),
+            ];
+            lines.push(Line::from(metric.field.clone().yellow()));
+            lines.push(Line::from(
+                values
+                    .iter()
+                    .map(|(label, value)| {
+                        format!(
+                            "  {label} {}",
+                            value.map(number).unwrap_or_else(|| String::from("-"))
+                        )
+                    })
+                    .collect::<String>(),
+            ));
+            lines.push(Line::from(
+                metric
+                    .percentiles
+                    .iter()
+                    .map(|percentile| {
+                        format!(
+                            "  p{} {}",
+                            number(percentile.percent),
+                            number(percentile.value)
+                        )
+                    })
+                    .collect::<String>(),
+            ));
+        }
+        lines
     }
+
+    fn make_block(&self) -> Block<'_> {
+        Block::default()
+            .title_top(Line::from("|Fleet Summary|".yellow()).right_aligned())
+            .border_style(Style::default().fg(Color::Rgb(100, 100, 100)))
+            .borders(Borders::ALL)
+            .border_type(DEFAULT_BORDER_STYLE)
+            .padding(Padding::new(1, 1, 0, 0))
+    }
+}
+
+/// Formats a value without decimals if it is whole, e.g. `42` or `3.14`.
+fn number(value: f64) -> String {
+    if value.fract() == 0.0 { format!("{value:.0}") } else { format!("{value:.2}") }
 }
 
 impl Component for TopRight {
@@ -105,8 +153,15 @@ impl Component for TopRight {
     }
 
     fn update(&mut self, action: Action) -> Result<Option<Action>> {
-        if let Action::Tick = action {
-            self.app_tick()?
+        match action {
+            Action::Tick => self.app_tick()?,
+            Action::Reload => self.load_error = None,
+            Action::LoadFailed(Resource::FleetSummary, message) => self.load_error = Some(message),
+            Action::SummaryLoaded(summary) => {
+                self.summary = summary.map(|summary| *summary);
+                self.loaded = true;
+            }
+            _ => {}
         }
 
         Ok(None)
@@ -123,8 +178,29 @@ impl Component for TopRight {
             v_layout.top.height,
         );
 
-        let block = self.make_table();
-        f.render_widget(block, table_rect);
+        let block = self.make_block();
+        match (&self.load_error, &self.summary) {
+            (Some(load_error), _) => f.render_widget(
+                Paragraph::new(load_error.clone().red())
+                    .wrap(Wrap { trim: true })
+                    .block(block),
+                table_rect,
+            ),
+            (None, Some(summary)) => f.render_widget(
+                Paragraph::new(Self::summary_lines(summary)).block(block),
+                table_rect,
+            ),
+            (None, None) if self.loaded => f.render_widget(
+                Paragraph::new("Fleet indexing is not enabled for this account".dark_gray())
+                    .wrap(Wrap { trim: true })
+                    .block(block),
+                table_rect,
+            ),
+            (None, None) => f.render_widget(
+                Paragraph::new("loading..".yellow()).block(block),
+                table_rect,
+            ),
+        }
 
         Ok(())
     }
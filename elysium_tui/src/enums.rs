@@ -1,3 +1,5 @@
+use elysium::Resource;
+use ratatui::layout::Constraint;
 use strum::{Display, EnumCount, EnumIter, FromRepr};
 
 #[derive(Default, Clone, Copy, Display, FromRepr, EnumIter, EnumCount, PartialEq, Debug)]
@@ -5,24 +7,155 @@ pub enum TabsEnum {
     #[default]
     #[strum(to_string = "Core Devices")]
     Devices,
+    #[strum(to_string = "Thing Groups")]
+    ThingGroups,
     #[strum(to_string = "Deployments")]
     Deployments,
+    #[strum(to_string = "Components")]
+    Components,
+    #[strum(to_string = "Jobs")]
+    Jobs,
+    #[strum(to_string = "Certificates")]
+    Certificates,
+    #[strum(to_string = "Search")]
+    Search,
 }
 
 impl TabsEnum {
-    #[allow(unused)]
-    pub fn headers(&self) -> &[&str] {
+    pub fn headers(&self) -> &'static [&'static str] {
         match self {
             // TabsEnum::Sdk => todo!(),
             TabsEnum::Devices => &[
-                "Name", "Status", "Last Status Update",
+                "Name",
+                "Status",
+                "Security",
+                "Last Status Update",
+                "Thing Type",
+                "Billing Group",
+            ],
+            TabsEnum::ThingGroups => &["Name", "Subgroups", "ARN"],
+            TabsEnum::Deployments => &["Name", "Status", "Created"],
+            TabsEnum::Components => &["Name", "Latest Version", "Publisher", "Description"],
+            TabsEnum::Jobs => &[
+                "Job ID",
+                "Status",
+                "Target Selection",
+                "Succeeded",
+                "Failed",
+                "In Progress",
+                "Targets",
+            ],
+            TabsEnum::Certificates => &[
+                "Thing",
+                "Status",
+                "Expires",
+                "Days Left",
+                "Created",
+                "Certificate ID",
+            ],
+            TabsEnum::Search => &["Name", "Connected", "Thing Type", "Groups", "Attributes"],
+        }
+    }
+
+    /// Widths of the columns listed in [`TabsEnum::headers`].
+    pub fn widths(&self) -> &[Constraint] {
+        match self {
+            TabsEnum::Devices => &[
+                Constraint::Length(30),
+                Constraint::Length(10),
+                Constraint::Length(22),
+                Constraint::Length(21),
+                Constraint::Length(20),
+                Constraint::Fill(1),
+            ],
+            TabsEnum::ThingGroups => &[
+                Constraint::Length(40),
+                Constraint::Length(10),
+                Constraint::Fill(1),
             ],
-            // TabsEnum::ThingGroups => &[
-            //     "Name", "ARN"
-            // ],
             TabsEnum::Deployments => &[
-                "Name", "Status", "Created",
-            ]
+                Constraint::Length(30),
+                Constraint::Length(10),
+                Constraint::Fill(1),
+            ],
+            TabsEnum::Components => &[
+                Constraint::Length(30),
+                Constraint::Length(15),
+                Constraint::Length(20),
+                Constraint::Fill(1),
+            ],
+            TabsEnum::Jobs => &[
+                Constraint::Length(30),
+                Constraint::Length(12),
+                Constraint::Length(17),
+                Constraint::Length(10),
+                Constraint::Length(7),
+                Constraint::Length(12),
+                Constraint::Fill(1),
+            ],
+            TabsEnum::Certificates => &[
+                Constraint::Length(30),
+                Constraint::Length(10),
+                Constraint::Length(21),
+                Constraint::Length(10),
+                Constraint::Length(21),
+                Constraint::Fill(1),
+            ],
+            TabsEnum::Search => &[
+                Constraint::Length(30),
+                Constraint::Length(10),
+                Constraint::Length(20),
+                Constraint::Length(30),
+                Constraint::Fill(1),
+            ],
         }
     }
-}
\ No newline at end of file
+
+    /// Keys for the drill-downs available on the tab.
+    pub fn key_hints(&self) -> &[(&'static str, &'static str)] {
+        match self {
+            TabsEnum::Devices => &[
+                ("⏎", "components"),
+                ("e", "deployments"),
+                ("s", "shadows"),
+                ("p", "policies"),
+                ("a", "security"),
+                ("b", "bulk group"),
+            ],
+            TabsEnum::ThingGroups => &[
+                ("⏎", "members"),
+                ("m", "all members"),
+                ("←→", "collapse/expand"),
+            ],
+            TabsEnum::Deployments => &[
+                ("⏎", "details"),
+                ("d", "dependencies"),
+                ("f", "diff"),
+                ("h", "history"),
+                ("r", "revise"),
+                ("x", "cancel"),
+            ],
+            TabsEnum::Components => &[("⏎", "versions"), ("v", "recipe")],
+            TabsEnum::Jobs => &[("⏎", "executions")],
+            TabsEnum::Certificates => &[],
+            TabsEnum::Search => &[
+                ("/", "query"),
+                ("n", "save as dynamic group"),
+                ("b", "bulk group"),
+            ],
+        }
+    }
+
+    /// The resource loaded for the tab, `None` for tabs filled on demand.
+    pub fn resource(&self) -> Option<Resource> {
+        match self {
+            TabsEnum::Devices => Some(Resource::Devices),
+            TabsEnum::ThingGroups => Some(Resource::ThingGroups),
+            TabsEnum::Deployments => Some(Resource::Deployments),
+            TabsEnum::Components => Some(Resource::Components),
+            TabsEnum::Jobs => Some(Resource::Jobs),
+            TabsEnum::Certificates => Some(Resource::Certificates),
+            TabsEnum::Search => None,
+        }
+    }
+}
@@ -1,11 +1,13 @@
+use std::{path::PathBuf, process::ExitCode, time::Duration};
+
 use clap::Parser;
 use args::Args;
 use color_eyre::{Report, Result};
-use elysium::AwsCloud;
+use elysium::{AwsCloud, Error, Recipe, RecipeFormat};
 
 use crate::{
     app::App,
-    utils::{initialise_logging, initialise_panic_handler}
+    utils::{describe_error, initialise_logging, initialise_panic_handler},
 };
 
 mod action;
@@ -17,28 +19,113 @@ mod layout;
 mod tui;
 mod utils;
 
-async fn tokio_main() -> Result<()> {
+/// Validates recipes for CI, printing one `file:line:column: message` line per problem.
+///
+/// Returns whether all recipes are valid, warnings aside.
+fn lint_recipes(paths: &[PathBuf]) -> bool {
+    let mut valid = true;
+    for path in paths {
+        let source = match std::fs::read_to_string(path) {
+            Ok(source) => source,
+            Err(e) => {
+                println!("{}: {e}", path.display());
+                valid = false;
+                continue;
+            }
+        };
+        let format = match path.extension().and_then(|e| e.to_str()) {
+            Some("json") => RecipeFormat::Json,
+            Some("yaml") | Some("yml") => RecipeFormat::Yaml,
+            _ => RecipeFormat::detect(&source),
+        };
+        match Recipe::parse_as(&source, format) {
+            Ok(recipe) => {
+                for issue in recipe.validate(&source) {
+                    println!("{}:{issue}", path.display());
+                }
+            }
+            Err(Error::InvalidRecipe(issues)) => {
+                for issue in issues {
+                    println!("{}:{issue}", path.display());
+                }
+                valid = false;
+            }
+            Err(e) => {
+                println!("{}: {e}", path.display());
+                valid = false;
+            }
+        }
+    }
+    valid
+}
+
+/// Prints the active certificates expiring within `days` as a JSON report.
+///
+/// Returns whether none are expiring and every thing could be checked.
+async fn report_expiring_certificates(mut aws: AwsCloud, days: i64, profile: &str) -> Result<bool> {
+    aws.load_certificates()
+        .await
+        .map_err(|e| Report::msg(describe_error(&e, profile)))?;
+
+    let report = aws.expiring_certificates(days);
+    for (thing_name, error) in &report.failures {
+        eprintln!("Failed to look up the certificates of {thing_name}: {error}");
+    }
+    println!("{}", serde_json::to_string_pretty(&report)?);
+    Ok(report.is_empty() && report.failures.is_empty())
+}
+
+async fn connect(args: &Args) -> Result<AwsCloud> {
+    let aws = match args.fixture {
+        Some(ref path) => AwsCloud::from_fixture(path),
+        None => AwsCloud::new(&args.profile, &args.region).await,
+    }
+    .map_err(|e| Report::msg(describe_error(&e, &args.profile)))?
+    .with_connectivity_concurrency(args.connectivity_concurrency)
+    .with_connectivity_timeout(Duration::from_secs(args.connectivity_timeout))
+    .with_summary_bucket_field(&args.summary_bucket_field)
+    .with_summary_metric_field(args.summary_metric_field.clone());
+    Ok(aws)
+}
+
+async fn tokio_main(args: Args) -> Result<()> {
     initialise_panic_handler()?;
     initialise_logging()?;
 
-    let args = Args::parse();
-    
-    let aws = AwsCloud::new(&args.profile, &args.region).await;
-    if aws.is_err() {
-        return Err(Report::msg(aws.unwrap_err()));
-    }
+    let aws = connect(&args).await?;
 
-    let mut app = App::new(aws.unwrap())?;
+    let mut app = App::new(aws, args.profile, args.attributes, args.bulk_rate)?;
     app.run().await?;
     Ok(())
 }
 
 #[tokio::main]
-async fn main() -> Result<()> {
-    if let Err(e) = tokio_main().await {
-        eprintln!("{} error: Something went wrong", env!("CARGO_PKG_NAME"));
-        Err(e)
+async fn main() -> Result<ExitCode> {
+    let args = Args::parse();
+    if !args.lint_recipes.is_empty() {
+        return Ok(if lint_recipes(&args.lint_recipes) {
+            ExitCode::SUCCESS
+        } else {
+            ExitCode::FAILURE
+        });
+    }
+
+    if let Some(days) = args.expiring_certificates {
+        let aws = connect(&args).await?;
+        return Ok(
+            if report_expiring_certificates(aws, days, &args.profile).await? {
+                ExitCode::SUCCESS
+            } else {
+                ExitCode::FAILURE
+            },
+        );
+    }
+
+    if let Err(e) = tokio_main(args).await {
+        // Errors from the library already carry their hint, see `describe_error`.
+        eprintln!("{} error: {e:#}", env!("CARGO_PKG_NAME"));
+        Ok(ExitCode::FAILURE)
     } else {
-        Ok(())
+        Ok(ExitCode::SUCCESS)
     }
 }
\ No newline at end of file
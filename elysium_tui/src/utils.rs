@@ -2,6 +2,7 @@ use std::{env, path::PathBuf};
 
 use color_eyre::Result;
 use directories::ProjectDirs;
+use elysium::Error;
 use lazy_static::lazy_static;
 use tracing::error;
 use tracing_error::ErrorLayer;
@@ -22,25 +23,47 @@ lazy_static! {
 }
 
 pub fn get_data_dir() -> PathBuf {
-    let directory = if let Some(s) = DATA_FOLDER.clone() {
+    if let Some(s) = DATA_FOLDER.clone() {
         s
     } else if let Some(proj_dirs) = project_directory() {
         proj_dirs.data_local_dir().to_path_buf()
     } else {
         PathBuf::from(".").join(".data")
-    };
-    directory
+    }
 }
 
 pub fn get_config_dir() -> PathBuf {
-    let directory = if let Some(s) = CONFIG_FOLDER.clone() {
+    if let Some(s) = CONFIG_FOLDER.clone() {
         s
     } else if let Some(proj_dirs) = project_directory() {
         proj_dirs.config_local_dir().to_path_buf()
     } else {
         PathBuf::from(".").join(".config")
-    };
-    directory
+    }
+}
+
+/// Describes an [`Error`] together with what the user can do about it.
+pub fn describe_error(error: &Error, profile: &str) -> String {
+    match error {
+        Error::ExpiredCredentials => {
+            format!("{error}. Run `aws sso login --profile {profile}` and try again.")
+        }
+        Error::MissingProfile(_) => {
+            format!("{error}. Configure it with `aws configure sso` or pass another --profile.")
+        }
+        Error::AccessDenied { .. } => {
+            format!("{error}. Ask for the action to be added to your IAM policy.")
+        }
+        Error::Throttling(_) => format!("{error}. Try again shortly."),
+        Error::RegionNotEnabled(_) => {
+            format!("{error}. Enable it in the AWS console or pass another --region.")
+        }
+        Error::InvalidCredentials(_) => {
+            format!("{error}. Check the access keys of profile `{profile}`.")
+        }
+        Error::Network(_) => format!("{error}. Check your internet connection and try again."),
+        _ => error.to_string(),
+    }
 }
 
 fn project_directory() -> Option<ProjectDirs> {